@@ -1,17 +1,33 @@
 use treeline::Tree;
 use vif_objects::ast::Assert;
 use vif_objects::ast::Assign;
+use vif_objects::ast::AugmentedAssign;
 use vif_objects::ast::Binary;
 use vif_objects::ast::Call;
+use vif_objects::ast::Class;
+use vif_objects::ast::Comparison;
 use vif_objects::ast::Condition;
+use vif_objects::ast::Dict;
 use vif_objects::ast::Expr;
 use vif_objects::ast::ExprBody;
+use vif_objects::ast::For;
+use vif_objects::ast::FormatPart;
+use vif_objects::ast::FormatString;
 use vif_objects::ast::Function;
+use vif_objects::ast::GetAttr;
+use vif_objects::ast::GetItem;
 use vif_objects::ast::Grouping;
+use vif_objects::ast::Import;
+use vif_objects::ast::List;
 use vif_objects::ast::Logical;
 use vif_objects::ast::LoopKeyword;
+use vif_objects::ast::Raise;
 use vif_objects::ast::Return;
+use vif_objects::ast::SetAttr;
+use vif_objects::ast::SetItem;
 use vif_objects::ast::Stmt;
+use vif_objects::ast::Try;
+use vif_objects::ast::Tuple;
 use vif_objects::ast::Unary;
 use vif_objects::ast::Value;
 use vif_objects::ast::Variable;
@@ -49,29 +65,51 @@ fn print_function(function: &Function) -> Tree<Node> {
     )
 }
 
+fn print_class(class: &Class) -> Tree<Node> {
+    Tree::new(
+        Node::new(&class.name, "class"),
+        class.methods.iter().map(print_function).collect(),
+    )
+}
+
 fn print_stmt(stmt: &Stmt) -> Tree<Node> {
     match stmt {
         Stmt::Expression(e) => print_expr(e),
         Stmt::Var(v) => print_var(v),
         Stmt::Function(f) => print_function(f),
+        Stmt::Class(c) => print_class(c),
         Stmt::Block(b) => print_block(b),
         Stmt::Condition(c) => print_condition(c),
         Stmt::While(w) => print_while(w),
+        Stmt::For(f) => print_for(f),
         Stmt::Return(r) => print_return(r),
         Stmt::Assert(a) => print_assert(a),
+        Stmt::Import(i) => print_import(i),
+        Stmt::Raise(r) => print_raise(r),
+        Stmt::Try(t) => print_try(t),
     }
 }
 
 fn print_expr(expr: &Expr) -> Tree<Node> {
     match &expr.body {
         ExprBody::Binary(b) => print_binary(&b),
+        ExprBody::Comparison(c) => print_comparison(&c),
         ExprBody::Unary(u) => print_unary(&u),
         ExprBody::Grouping(g) => print_grouping(&g),
         ExprBody::Value(v) => print_value(&v),
         ExprBody::LoopKeyword(l) => print_loop(&l),
         ExprBody::Assign(a) => print_assign(&a),
+        ExprBody::AugmentedAssign(a) => print_augmented_assign(&a),
         ExprBody::Call(c) => print_call(&c),
         ExprBody::Logical(l) => print_logical(&l),
+        ExprBody::List(l) => print_list(&l),
+        ExprBody::Dict(d) => print_dict(&d),
+        ExprBody::Tuple(t) => print_tuple(&t),
+        ExprBody::FormatString(f) => print_format_string(&f),
+        ExprBody::GetItem(g) => print_get_item(&g),
+        ExprBody::SetItem(s) => print_set_item(&s),
+        ExprBody::GetAttr(g) => print_get_attr(&g),
+        ExprBody::SetAttr(s) => print_set_attr(&s),
     }
 }
 
@@ -82,6 +120,18 @@ fn print_binary(binary: &Binary) -> Tree<Node> {
     )
 }
 
+fn print_comparison(comparison: &Comparison) -> Tree<Node> {
+    let operators = comparison
+        .comparisons
+        .iter()
+        .map(|(operator, _)| format!("{operator}"))
+        .collect::<Vec<String>>();
+    let mut operands = vec![print_expr(&comparison.left)];
+    operands.extend(comparison.comparisons.iter().map(|(_, e)| print_expr(e)));
+
+    Tree::new(Node::new(&operators.join(" "), "comparison"), operands)
+}
+
 fn print_unary(unary: &Unary) -> Tree<Node> {
     Tree::new(
         Node::new(&format!("{}", unary.operator), "unary"),
@@ -111,11 +161,21 @@ fn print_assign(assign: &Assign) -> Tree<Node> {
     )
 }
 
+fn print_augmented_assign(assign: &AugmentedAssign) -> Tree<Node> {
+    Tree::new(
+        Node::new(&format!("{}=", assign.operator), "augmented assign"),
+        vec![print_expr(&assign.target), print_expr(&assign.value)],
+    )
+}
+
 fn print_call(call: &Call) -> Tree<Node> {
     let mut callee = print_expr(&call.callee);
     call.arguments.iter().for_each(|e| {
         callee.push(print_expr(&e));
     });
+    call.keywords.iter().for_each(|(name, e)| {
+        callee.push(Tree::new(Node::new(name, "keyword"), vec![print_expr(e)]));
+    });
 
     Tree::new(Node::new("call", ""), vec![callee])
 }
@@ -127,6 +187,78 @@ fn print_logical(logical: &Logical) -> Tree<Node> {
     )
 }
 
+fn print_list(list: &List) -> Tree<Node> {
+    Tree::new(
+        Node::new("list", ""),
+        list.items.iter().map(|i| print_expr(i)).collect(),
+    )
+}
+
+fn print_dict(dict: &Dict) -> Tree<Node> {
+    Tree::new(
+        Node::new("dict", ""),
+        dict.items
+            .iter()
+            .map(|(k, v)| Tree::new(Node::new("item", ""), vec![print_expr(k), print_expr(v)]))
+            .collect(),
+    )
+}
+
+fn print_tuple(tuple: &Tuple) -> Tree<Node> {
+    Tree::new(
+        Node::new("tuple", ""),
+        tuple.items.iter().map(|i| print_expr(i)).collect(),
+    )
+}
+
+fn print_format_string(format: &FormatString) -> Tree<Node> {
+    Tree::new(
+        Node::new("format string", ""),
+        format
+            .parts
+            .iter()
+            .map(|p| match p {
+                FormatPart::Literal(l) => Tree::new(Node::new(l, "literal"), Vec::new()),
+                FormatPart::Value(v, spec) => {
+                    Tree::new(Node::new(&format!("{spec}"), "field"), vec![print_expr(v)])
+                }
+            })
+            .collect(),
+    )
+}
+
+fn print_get_item(item: &GetItem) -> Tree<Node> {
+    Tree::new(
+        Node::new("get item", ""),
+        vec![print_expr(&item.object), print_expr(&item.index)],
+    )
+}
+
+fn print_set_item(item: &SetItem) -> Tree<Node> {
+    Tree::new(
+        Node::new("set item", ""),
+        vec![
+            print_expr(&item.object),
+            print_expr(&item.index),
+            print_expr(&item.value),
+        ],
+    )
+}
+
+fn print_get_attr(attr: &GetAttr) -> Tree<Node> {
+    Tree::new(
+        Node::new(&attr.name, "get attr"),
+        vec![print_expr(&attr.object)],
+    )
+}
+
+fn print_set_attr(attr: &SetAttr) -> Tree<Node> {
+    Tree::new(
+        Node::new(&attr.name, "set attr"),
+        vec![print_expr(&attr.object), print_expr(&attr.value)],
+    )
+}
+
 fn print_var(var: &Variable) -> Tree<Node> {
     Tree::new(
         Node::new(&format!("{}", var.name), "variable"),
@@ -159,6 +291,14 @@ fn print_while(r#while: &While) -> Tree<Node> {
     Tree::new(Node::new("while", "loop"), vec![cond])
 }
 
+fn print_for(r#for: &For) -> Tree<Node> {
+    let mut leaves = vec![print_expr(&r#for.iterable), print_stmt(&r#for.body)];
+    if let Some(r#else) = &r#for.r#else {
+        leaves.push(print_stmt(r#else))
+    }
+    Tree::new(Node::new(&r#for.name, "for"), leaves)
+}
+
 fn print_return(r#return: &Return) -> Tree<Node> {
     Tree::new(Node::new("return", ""), vec![print_expr(&r#return.value)])
 }
@@ -166,3 +306,38 @@ fn print_return(r#return: &Return) -> Tree<Node> {
 fn print_assert(assert: &Assert) -> Tree<Node> {
     Tree::new(Node::new("assert", ""), vec![print_expr(&assert.value)])
 }
+
+fn print_raise(raise: &Raise) -> Tree<Node> {
+    Tree::new(
+        Node::new(raise.kind.as_deref().unwrap_or(""), "raise"),
+        raise.message.iter().map(|m| print_expr(m)).collect(),
+    )
+}
+
+fn print_try(r#try: &Try) -> Tree<Node> {
+    let mut leaves = vec![print_stmt(&r#try.body)];
+    for handler in r#try.handlers.iter() {
+        leaves.push(Tree::new(
+            Node::new(&handler.kinds.join(", "), "except"),
+            vec![print_stmt(&handler.body)],
+        ));
+    }
+    if let Some(finally) = &r#try.finally {
+        leaves.push(Tree::new(
+            Node::new("", "finally"),
+            vec![print_stmt(finally)],
+        ));
+    }
+    Tree::new(Node::new("", "try"), leaves)
+}
+
+fn print_import(import: &Import) -> Tree<Node> {
+    Tree::new(
+        Node::new(&import.module, "import"),
+        import
+            .names
+            .iter()
+            .map(|n| Tree::root(Node::new(n, "name")))
+            .collect(),
+    )
+}
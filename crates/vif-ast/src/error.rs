@@ -30,7 +30,7 @@ impl SyntaxError {
     }
 
     pub fn format(&self, content: &str) -> String {
-        let row = content.split('\n').nth(self.span.get_line() - 1).unwrap();
+        let row = self.span.get_row(content);
         format!("Line {} - {row}\n{}", self.span.get_line(), self.msg)
     }
 }
@@ -1,8 +1,11 @@
 mod debug;
 mod error;
+mod namespace;
 mod parser;
 pub use debug::print_ast_tree;
 pub use error::AstError;
+pub use namespace::get_module_names;
+pub use namespace::Namespace;
 use parser::Parser;
 use vif_loader::log;
 use vif_objects::ast::Function;
@@ -0,0 +1,452 @@
+use crate::error::AstError;
+use crate::error::SyntaxError;
+use std::collections::HashMap;
+use vif_objects::ast::Assign;
+use vif_objects::ast::Binary;
+use vif_objects::ast::Expr;
+use vif_objects::ast::ExprBody;
+use vif_objects::ast::FormatPart;
+use vif_objects::ast::Function;
+use vif_objects::ast::Signature;
+use vif_objects::ast::Stmt;
+use vif_objects::ast::Type;
+use vif_objects::ast::Typing;
+use vif_objects::ast::Value;
+
+// names declared by a module outside of its functions and classes
+pub fn get_module_names(body: &[Stmt]) -> Vec<String> {
+    let mut names = Vec::new();
+    for stmt in body {
+        collect_names(stmt, &mut names);
+    }
+    names
+}
+
+fn collect_names(stmt: &Stmt, names: &mut Vec<String>) {
+    let name = match stmt {
+        Stmt::Var(v) => &v.name,
+        Stmt::Function(f) => &f.name,
+        Stmt::Class(c) => &c.name,
+        Stmt::For(f) => &f.name,
+        Stmt::Block(b) => return b.iter().for_each(|s| collect_names(s, names)),
+        _ => return,
+    };
+
+    if !names.contains(name) {
+        names.push(name.clone());
+    }
+}
+
+// Renames what a module refers to, so that every module can be merged into
+// one program: `utils.helper` and `helper` (once imported) both become the
+// variable `utils.helper`
+#[derive(Clone)]
+pub struct Namespace {
+    names: HashMap<String, String>,
+    // imported modules and the names they declare
+    modules: HashMap<String, Vec<String>>,
+    offset: usize,
+}
+
+impl Namespace {
+    // offset is the number of lines of the modules coming before this one
+    pub fn new(offset: usize) -> Self {
+        Namespace {
+            names: HashMap::new(),
+            modules: HashMap::new(),
+            offset,
+        }
+    }
+
+    pub fn add_name(&mut self, name: String, renamed: String) {
+        self.names.insert(name, renamed);
+    }
+
+    pub fn remove_name(&mut self, name: &str) {
+        self.names.remove(name);
+        self.modules.remove(name);
+    }
+
+    pub fn add_module(&mut self, name: String, names: Vec<String>) {
+        self.names.remove(&name);
+        self.modules.insert(name, names);
+    }
+
+    pub fn apply(&self, body: &mut [Stmt]) -> Result<(), AstError> {
+        let mut namespace = self.clone();
+        for stmt in body.iter_mut() {
+            namespace.visit_statement(stmt, true)?;
+        }
+        Ok(())
+    }
+
+    // declarations made inside a function shadow the module ones
+    fn declare(&mut self, name: &mut String, top_level: bool) {
+        if top_level {
+            self.rename(name);
+        } else {
+            self.remove_name(name);
+        }
+    }
+
+    fn rename(&self, name: &mut String) {
+        if let Some(renamed) = self.names.get(name) {
+            *name = renamed.clone();
+        }
+    }
+
+    fn rename_typing(&self, typing: &mut Typing) {
+        self.rename_type(&mut typing.r#type)
+    }
+
+    fn rename_type(&self, r#type: &mut Type) {
+        match r#type {
+            Type::Object(name) => self.rename(name),
+            Type::List(t) => self.rename_type(t),
+            Type::Dict(k, v) => {
+                self.rename_type(k);
+                self.rename_type(v);
+            }
+            Type::Tuple(types) => types.iter_mut().for_each(|t| self.rename_type(t)),
+            Type::Callable(c) => {
+                if let Signature::Parameters(params, _) = &mut c.signature {
+                    params.iter_mut().for_each(|p| self.rename_typing(p));
+                }
+                self.rename_typing(&mut c.output);
+            }
+            _ => (),
+        }
+    }
+
+    fn visit_function(&self, function: &mut Function) -> Result<(), AstError> {
+        let mut namespace = self.clone();
+        function.span.set_offset(self.offset);
+        self.rename_typing(&mut function.typing);
+
+        for param in function.params.iter_mut() {
+            param.span.set_offset(self.offset);
+            self.rename_typing(&mut param.typing);
+            // defaults are evaluated by the function itself, where the previous parameters exist
+            if let Some(default) = &mut param.default {
+                namespace.visit_expression(default)?;
+            }
+            namespace.remove_name(&param.name);
+        }
+
+        for stmt in function.body.iter_mut() {
+            namespace.visit_statement(stmt, false)?;
+        }
+        Ok(())
+    }
+
+    fn visit_statement(&mut self, stmt: &mut Stmt, top_level: bool) -> Result<(), AstError> {
+        match stmt {
+            Stmt::Expression(e) => self.visit_expression(e)?,
+            Stmt::Var(v) => {
+                self.visit_expression(&mut v.value)?;
+                self.rename_typing(&mut v.typing);
+                self.declare(&mut v.name, top_level);
+            }
+            Stmt::Function(f) => {
+                self.declare(&mut f.name, top_level);
+                self.visit_function(f)?;
+            }
+            Stmt::Class(c) => {
+                let name = c.name.clone();
+                self.declare(&mut c.name, top_level);
+
+                for method in c.methods.iter_mut() {
+                    if let Some(method_name) = method.name.strip_prefix(&format!("{name}.")) {
+                        method.name = format!("{}.{method_name}", c.name);
+                    }
+                    self.visit_function(method)?;
+                }
+            }
+            Stmt::Block(b) => {
+                for stmt in b.iter_mut() {
+                    self.visit_statement(stmt, top_level)?;
+                }
+            }
+            Stmt::Condition(c) => {
+                self.visit_expression(&mut c.expr)?;
+                self.visit_statement(&mut c.then, top_level)?;
+                if let Some(r#else) = &mut c.r#else {
+                    self.visit_statement(r#else, top_level)?;
+                }
+            }
+            Stmt::While(w) => {
+                self.visit_expression(&mut w.condition)?;
+                self.visit_statement(&mut w.body, top_level)?;
+            }
+            Stmt::For(f) => {
+                self.visit_expression(&mut f.iterable)?;
+                self.rename_typing(&mut f.typing);
+                self.declare(&mut f.name, top_level);
+                self.visit_statement(&mut f.body, top_level)?;
+                if let Some(r#else) = &mut f.r#else {
+                    self.visit_statement(r#else, top_level)?;
+                }
+            }
+            Stmt::Return(r) => self.visit_expression(&mut r.value)?,
+            Stmt::Assert(a) => self.visit_expression(&mut a.value)?,
+            Stmt::Raise(r) => {
+                r.span.set_offset(self.offset);
+                if let Some(message) = &mut r.message {
+                    self.visit_expression(message)?;
+                }
+            }
+            Stmt::Try(t) => {
+                self.visit_statement(&mut t.body, top_level)?;
+                for handler in t.handlers.iter_mut() {
+                    handler.span.set_offset(self.offset);
+                    if let Some(name) = &mut handler.name {
+                        self.declare(name, top_level);
+                    }
+                    self.visit_statement(&mut handler.body, top_level)?;
+                }
+                if let Some(finally) = &mut t.finally {
+                    self.visit_statement(finally, top_level)?;
+                }
+            }
+            Stmt::Import(i) => {
+                i.span.set_offset(self.offset);
+                if !top_level {
+                    return Err(SyntaxError::new(
+                        format!("Modules can only be imported at the top level"),
+                        i.span.clone(),
+                    ));
+                }
+            }
+        };
+
+        Ok(())
+    }
+
+    // `module.name` is the variable `module.name` once the modules are merged
+    fn module_attribute(&self, expr: &Expr, name: &str) -> Result<Option<String>, AstError> {
+        let module = match &expr.body {
+            ExprBody::Value(Value::Variable(v)) => v,
+            _ => return Ok(None),
+        };
+
+        match self.modules.get(module) {
+            Some(names) if names.iter().any(|n| n == name) => Ok(Some(format!("{module}.{name}"))),
+            Some(_) => {
+                let mut span = expr.span.clone();
+                span.set_offset(self.offset);
+                Err(SyntaxError::new(
+                    format!("Module {module} has no attribute {name}"),
+                    span,
+                ))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn visit_expression(&self, expr: &mut Expr) -> Result<(), AstError> {
+        expr.span.set_offset(self.offset);
+        self.rename_typing(&mut expr.typing);
+
+        match &mut expr.body {
+            ExprBody::Binary(b) => {
+                self.visit_expression(&mut b.left)?;
+                self.visit_expression(&mut b.right)?;
+            }
+            ExprBody::Comparison(c) => {
+                self.visit_expression(&mut c.left)?;
+                for (_, e) in c.comparisons.iter_mut() {
+                    self.visit_expression(e)?;
+                }
+            }
+            ExprBody::Logical(l) => {
+                self.visit_expression(&mut l.left)?;
+                self.visit_expression(&mut l.right)?;
+            }
+            ExprBody::Unary(u) => self.visit_expression(&mut u.right)?,
+            ExprBody::Grouping(g) => self.visit_expression(&mut g.expr)?,
+            ExprBody::Value(Value::Variable(v)) => {
+                if self.modules.contains_key(v) {
+                    return Err(SyntaxError::new(
+                        format!("Module {v} cannot be used as a value"),
+                        expr.span.clone(),
+                    ));
+                }
+                self.rename(v)
+            }
+            ExprBody::Value(_) | ExprBody::LoopKeyword(_) => (),
+            ExprBody::Assign(a) => {
+                self.visit_expression(&mut a.value)?;
+                self.rename(&mut a.name);
+            }
+            ExprBody::AugmentedAssign(a) => {
+                self.visit_expression(&mut a.target)?;
+                self.visit_expression(&mut a.value)?;
+
+                // a module variable is assigned like any other variable
+                if let ExprBody::Value(Value::Variable(name)) = &a.target.body {
+                    let name = name.clone();
+                    let body = std::mem::replace(&mut expr.body, ExprBody::Value(Value::None));
+                    let ExprBody::AugmentedAssign(a) = body else {
+                        unreachable!()
+                    };
+                    let value = Box::new(Expr::new(
+                        ExprBody::Binary(Binary {
+                            left: a.target,
+                            operator: a.operator,
+                            right: a.value,
+                        }),
+                        Typing::new(true, Type::Unknown),
+                        expr.span.clone(),
+                    ));
+                    expr.body = ExprBody::Assign(Assign { name, value });
+                }
+            }
+            ExprBody::Call(c) => {
+                self.visit_expression(&mut c.callee)?;
+                for arg in c.arguments.iter_mut() {
+                    self.visit_expression(arg)?;
+                }
+                for (_, arg) in c.keywords.iter_mut() {
+                    self.visit_expression(arg)?;
+                }
+            }
+            ExprBody::List(l) => {
+                for item in l.items.iter_mut() {
+                    self.visit_expression(item)?;
+                }
+            }
+            ExprBody::Tuple(t) => {
+                for item in t.items.iter_mut() {
+                    self.visit_expression(item)?;
+                }
+            }
+            ExprBody::Dict(d) => {
+                for (key, value) in d.items.iter_mut() {
+                    self.visit_expression(key)?;
+                    self.visit_expression(value)?;
+                }
+            }
+            ExprBody::FormatString(f) => {
+                for part in f.parts.iter_mut() {
+                    if let FormatPart::Value(value, _) = part {
+                        self.visit_expression(value)?;
+                    }
+                }
+            }
+            ExprBody::GetItem(g) => {
+                self.visit_expression(&mut g.object)?;
+                self.visit_expression(&mut g.index)?;
+            }
+            ExprBody::SetItem(s) => {
+                self.visit_expression(&mut s.object)?;
+                self.visit_expression(&mut s.index)?;
+                self.visit_expression(&mut s.value)?;
+            }
+            ExprBody::GetAttr(g) => match self.module_attribute(&g.object, &g.name)? {
+                Some(name) => expr.body = ExprBody::Value(Value::Variable(name)),
+                None => self.visit_expression(&mut g.object)?,
+            },
+            ExprBody::SetAttr(s) => match self.module_attribute(&s.object, &s.name)? {
+                Some(name) => {
+                    let mut value = std::mem::replace(
+                        &mut s.value,
+                        Box::new(Expr::new(
+                            ExprBody::Value(Value::None),
+                            Typing::new(false, Type::None),
+                            expr.span.clone(),
+                        )),
+                    );
+                    self.visit_expression(&mut value)?;
+                    expr.body = ExprBody::Assign(Assign { name, value });
+                }
+                None => {
+                    self.visit_expression(&mut s.object)?;
+                    self.visit_expression(&mut s.value)?;
+                }
+            },
+        };
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Namespace;
+    use crate::build_ast;
+    use vif_objects::ast::Stmt;
+
+    // `import m`, m declaring x
+    fn namespace() -> Namespace {
+        let mut namespace = Namespace::new(0);
+        namespace.add_module("m".to_owned(), vec!["x".to_owned()]);
+        namespace
+    }
+
+    fn apply(namespace: &Namespace, string: &str) -> Result<Vec<Stmt>, String> {
+        let mut ast = build_ast(string).unwrap();
+        namespace
+            .apply(&mut ast.body)
+            .map_err(|e| e.format(string))?;
+        Ok(ast.body)
+    }
+
+    #[test]
+    fn module_attribute_is_a_variable() {
+        let body = apply(&namespace(), "var y = m.x\n").unwrap();
+        assert_eq!(format!("{}", body[0]), "y=Value[var[m.x]]");
+    }
+
+    #[test]
+    fn unknown_module_attribute() {
+        let err = apply(&namespace(), "var y = m.y\n").unwrap_err();
+        assert_eq!(err, "Line 1 - var y = m.y\nModule m has no attribute y");
+    }
+
+    #[test]
+    fn module_used_as_value() {
+        let err = apply(&namespace(), "var n = m\n").unwrap_err();
+        assert_eq!(
+            err,
+            "Line 1 - var n = m\nModule m cannot be used as a value"
+        );
+    }
+
+    #[test]
+    fn import_inside_function() {
+        let string = "def f():\n    import m\n    return 1\n";
+        let err = apply(&namespace(), string).unwrap_err();
+        assert_eq!(
+            err,
+            "Line 2 -     import m\nModules can only be imported at the top level"
+        );
+    }
+
+    #[test]
+    fn local_declaration_shadows_module_names() {
+        // `from m import x` as well
+        let mut namespace = namespace();
+        namespace.add_name("x".to_owned(), "m.x".to_owned());
+
+        let string = "def f():\n    var x = 1\n    var m = 2\n    return x + m\n\nvar y = x\n";
+        let body = apply(&namespace, string).unwrap();
+
+        let Stmt::Function(function) = &body[0] else {
+            panic!("Expected a function, got {}", body[0]);
+        };
+        assert_eq!(
+            format!("{}", function.body[2]),
+            "return Binary[Value[var[x]], +, Value[var[m]]]"
+        );
+        assert_eq!(format!("{}", body[1]), "y=Value[var[m.x]]");
+    }
+
+    #[test]
+    fn augmented_module_attribute_is_assigned() {
+        let body = apply(&namespace(), "m.x += 1\n").unwrap();
+        assert_eq!(
+            format!("{}", body[0]),
+            "Assign[Assign[m.x=Binary[Value[var[m.x]], +, Value[1]]]]"
+        );
+    }
+}
@@ -8,6 +8,8 @@ use vif_objects::ast::Function;
 use vif_objects::ast::Typing;
 use vif_objects::ast::Value;
 use vif_objects::ast::Variable;
+use vif_objects::span::Span;
+use vif_scanner::FormatStringPart;
 use vif_scanner::Scanner;
 use vif_scanner::Token;
 use vif_scanner::TokenType;
@@ -19,6 +21,8 @@ pub struct Parser<'a> {
     errors: Vec<AstError>,
     ast: Vec<ast::Stmt>,
     type_merge: SoftTypeMerger,
+    // number of except blocks being parsed, a bare raise needs one
+    excepts: usize,
 }
 
 impl<'a> Parser<'a> {
@@ -28,6 +32,7 @@ impl<'a> Parser<'a> {
             ast: Vec::new(),
             errors: Vec::new(),
             type_merge: SoftTypeMerger {},
+            excepts: 0,
         }
     }
 
@@ -39,11 +44,13 @@ impl<'a> Parser<'a> {
             typing: Typing::new(
                 false,
                 ast::Type::Callable(Box::new(Callable::new(
-                    ast::Signature::Parameters(Vec::new()),
+                    ast::Signature::new_with_params(Vec::new()),
                     Typing::new(false, ast::Type::Int),
                     false,
                 ))),
             ),
+            declared_output: true,
+            span: Span::new(0, 0),
         }
     }
 
@@ -56,6 +63,12 @@ impl<'a> Parser<'a> {
             match self.declaration() {
                 Ok(stmt) => self.ast.push(stmt),
                 Err(AstError::EOF) => break,
+                // the tokens following a scanning error cannot be trusted
+                Err(err @ AstError::ScannerError(_)) => {
+                    println!("Parsing error: {:?}", err);
+                    self.errors.push(err);
+                    break;
+                }
                 Err(err) => {
                     println!("Parsing error: {:?}", err);
                     self.errors.push(err)
@@ -73,17 +86,185 @@ impl<'a> Parser<'a> {
                 self.declaration()
             }
             t if t.r#type == TokenType::Var => self.var_declaration(),
-            t if t.r#type == TokenType::Def => self.function_declaration(),
+            t if t.r#type == TokenType::Def => {
+                Ok(ast::Stmt::Function(self.function_declaration(None)?))
+            }
+            t if t.r#type == TokenType::Class => self.class_declaration(),
+            t if t.r#type == TokenType::At => self.decorated_declaration(),
+            t if t.r#type == TokenType::Import || t.r#type == TokenType::From => {
+                self.import_declaration()
+            }
             _ => self.statement(),
         }
     }
 
-    fn function_declaration(&mut self) -> Result<ast::Stmt, AstError> {
+    // import module
+    // from module import a, b
+    fn import_declaration(&mut self) -> Result<ast::Stmt, AstError> {
+        let from = self.scanner.scan()?.r#type == TokenType::From;
+        let span = self.scanner.get_span().clone();
+
+        let module = self.identifier("Expected a module name")?;
+        let mut names = Vec::new();
+
+        if from {
+            self.consume(TokenType::Import, "Expect import after module name")?;
+
+            names.push(self.identifier("Expected a name to import")?);
+            while self.scanner.check(&TokenType::Comma) {
+                self.scanner.scan().unwrap();
+                names.push(self.identifier("Expected a name to import")?);
+            }
+        }
+
+        self.consume(TokenType::NewLine, "Expect new line after import")?;
+
+        Ok(ast::Stmt::Import(ast::Import {
+            module,
+            names,
+            span,
+        }))
+    }
+
+    fn identifier(&mut self, msg: &str) -> Result<String, AstError> {
+        match self.scanner.scan()?.r#type {
+            TokenType::ValueIdentifier(s) => Ok(s),
+            t => Err(SyntaxError::new(
+                format!("{msg}, got {t}"),
+                self.scanner.get_span().clone(),
+            )),
+        }
+    }
+
+    // @a
+    // @b
+    // def f(): ...
+    //
+    // is declared as f = a(b(f)), right after the function itself
+    fn decorated_declaration(&mut self) -> Result<ast::Stmt, AstError> {
+        let mut decorators = Vec::new();
+
+        while self.scanner.check(&TokenType::At) {
+            self.scanner.scan().unwrap();
+            decorators.push(self.call()?);
+            self.consume(TokenType::NewLine, "Expect new line after decorator")?;
+        }
+
+        if !self.scanner.check(&TokenType::Def) {
+            return Err(SyntaxError::new(
+                format!("Expected a function definition after decorator"),
+                self.scanner.get_span().clone(),
+            ));
+        }
+
+        let function = self.function_declaration(None)?;
+
+        let mut value = Box::new(Expr::new(
+            ExprBody::Value(Value::Variable(function.name.clone())),
+            Typing::new(false, ast::Type::Unknown),
+            self.scanner.get_span().clone(),
+        ));
+
+        for decorator in decorators.into_iter().rev() {
+            let typing = decorator.typing.clone();
+            let span = decorator.span.clone();
+            value = Box::new(Expr::new(
+                ExprBody::Call(ast::Call {
+                    callee: decorator,
+                    arguments: vec![value],
+                    keywords: Vec::new(),
+                }),
+                typing,
+                span,
+            ));
+        }
+
+        let name = function.name.clone();
+
+        Ok(ast::Stmt::Block(vec![
+            ast::Stmt::Function(function),
+            ast::Stmt::Var(Variable::new(name, value, false)),
+        ]))
+    }
+
+    fn class_declaration(&mut self) -> Result<ast::Stmt, AstError> {
         self.scanner.scan()?;
 
         let name = match self.scanner.scan() {
             Ok(t) => match t.r#type {
                 TokenType::ValueIdentifier(s) => s,
+                _ => {
+                    return Err(SyntaxError::new(
+                        format!("Expected an identifier after class"),
+                        self.scanner.get_span().clone(),
+                    ))
+                }
+            },
+            Err(e) => return Err(e.into()),
+        };
+
+        self.consume(TokenType::DoubleDot, "Expect : after class name")?;
+        self.consume(
+            TokenType::NewLine,
+            "Expect new line after class declaration",
+        )?;
+        self.consume(TokenType::Indent, "Expect an indented class body")?;
+
+        let mut methods = Vec::new();
+
+        loop {
+            match self.scanner.peek() {
+                Ok(t) if t.r#type == TokenType::NewLine => {
+                    self.scanner.scan().unwrap();
+                }
+                Ok(t) if t.r#type == TokenType::Def => {
+                    let mut method = self.function_declaration(Some(&name))?;
+
+                    match method.params.first_mut() {
+                        // the object is being built, so __init__ can always set its attributes
+                        Some(p)
+                            if p.name == "self" && method.name == format!("{name}.__init__") =>
+                        {
+                            p.typing.mutable = true
+                        }
+                        Some(p) if p.name == "self" => (),
+                        _ => {
+                            return Err(SyntaxError::new(
+                                format!("Expected self as first parameter of {}", method.name),
+                                self.scanner.get_span().clone(),
+                            ))
+                        }
+                    };
+
+                    methods.push(method);
+                }
+                Ok(t) if t.r#type == TokenType::Dedent => {
+                    self.scanner.scan().unwrap();
+                    break;
+                }
+                Ok(t) if t.r#type == TokenType::EOF => break,
+                _ => {
+                    return Err(SyntaxError::new(
+                        format!("Expected a method definition in class {}", name),
+                        self.scanner.get_span().clone(),
+                    ))
+                }
+            }
+        }
+
+        Ok(ast::Stmt::Class(ast::Class::new(name, methods)))
+    }
+
+    // methods are named after their class, i.e `Class.method`
+    fn function_declaration(&mut self, class_name: Option<&str>) -> Result<Function, AstError> {
+        self.scanner.scan()?;
+
+        let name = match self.scanner.scan() {
+            Ok(t) => match t.r#type {
+                TokenType::ValueIdentifier(s) => match class_name {
+                    Some(c) => format!("{c}.{s}"),
+                    None => s,
+                },
                 _ => {
                     return Err(SyntaxError::new(
                         format!("Expected an identifier after def"),
@@ -93,9 +274,12 @@ impl<'a> Parser<'a> {
             },
             Err(e) => return Err(e.into()),
         };
+        let span = self.scanner.get_span().clone();
 
         self.consume(TokenType::LeftParen, "Expect ( after function name")?;
         let mut parameters = Vec::new();
+        // reported once the whole declaration is parsed, so parsing resumes after it
+        let mut misplaced_default = None;
 
         loop {
             let mutable = match self.scanner.peek() {
@@ -116,38 +300,58 @@ impl<'a> Parser<'a> {
                         self.scanner.scan().unwrap();
                         continue;
                     }
-                    TokenType::ValueIdentifier(s) => {
-                        let func_name = s.clone();
-
-                        self.scanner.scan().unwrap();
-
-                        self.consume(TokenType::DoubleDot, "Expected : after parameter")?;
-
-                        let t = match self.scanner.peek() {
-                            Ok(t) => match &t.r#type {
-                                TokenType::Int => ast::Type::Int,
-                                TokenType::Bool => ast::Type::Bool,
-                                TokenType::Str => ast::Type::String,
-                                TokenType::Float => ast::Type::Float,
-                                t => {
-                                    return Err(SyntaxError::new(
-                                        format!("Not a type: {t}"),
-                                        self.scanner.get_span().clone(),
-                                    ))
-                                }
-                            },
-                            _ => {
+                    TokenType::Self_ => {
+                        let class_name = match class_name {
+                            Some(c) => c.to_owned(),
+                            None => {
                                 return Err(SyntaxError::new(
-                                    "Expected parameter type".to_owned(),
+                                    format!("self can only be used in class methods"),
                                     self.scanner.get_span().clone(),
                                 ))
                             }
                         };
+
+                        self.scanner.scan().unwrap();
+
+                        parameters.push(ast::FunctionParameter {
+                            name: "self".to_owned(),
+                            typing: Typing::new(mutable, ast::Type::Object(class_name)),
+                            default: None,
+                            span: self.scanner.get_span().clone(),
+                        });
+                    }
+                    TokenType::ValueIdentifier(s) => {
+                        let func_name = s.clone();
+
                         self.scanner.scan().unwrap();
+                        let span = self.scanner.get_span().clone();
+
+                        // unannotated parameters are inferred by the typer
+                        let t = if self.scanner.check(&TokenType::DoubleDot) {
+                            self.scanner.scan().unwrap();
+                            self.type_annotation()?
+                        } else {
+                            ast::Type::Unknown
+                        };
+
+                        let default = if self.scanner.check(&TokenType::Equal) {
+                            self.scanner.scan().unwrap();
+                            Some(self.expression()?)
+                        } else if parameters.iter().any(|p| p.default.is_some()) {
+                            misplaced_default.get_or_insert(SyntaxError::new(
+                                format!("Parameter {func_name} without default follows parameters with defaults"),
+                                self.scanner.get_span().clone(),
+                            ));
+                            None
+                        } else {
+                            None
+                        };
 
                         parameters.push(ast::FunctionParameter {
                             name: func_name,
                             typing: Typing::new(mutable, t),
+                            default,
+                            span,
                         });
                     }
                     _ => {
@@ -162,15 +366,122 @@ impl<'a> Parser<'a> {
         }
 
         self.consume(TokenType::RightParen, "Expect ) to close function")?;
+
+        // the annotated return type is checked by the typer against every return
+        let output = if self.scanner.check(&TokenType::Arrow) {
+            self.scanner.scan().unwrap();
+            Some(self.type_annotation()?)
+        } else {
+            None
+        };
+
         self.consume(TokenType::DoubleDot, "Expect : after function declaration")?;
         self.consume(
             TokenType::NewLine,
             "Expect new line after function declaration",
         )?;
 
-        let func = Function::new(name, parameters, self.block()?);
+        // the error handled around a function is not the one of its body
+        let excepts = std::mem::take(&mut self.excepts);
+        let body = self.block();
+        self.excepts = excepts;
+
+        if let Some(err) = misplaced_default {
+            return Err(err);
+        }
+
+        let mut function = Function::new(name, parameters, body?, span);
+
+        if let Some(output) = output {
+            let mut callable = Callable::new(
+                ast::Signature::new_with_defaults(
+                    function.params.iter().map(|p| p.typing.clone()).collect(),
+                    function
+                        .params
+                        .iter()
+                        .filter(|p| p.default.is_none())
+                        .count(),
+                ),
+                Typing::new(true, output),
+                false,
+            );
+            callable.names = function.params.iter().map(|p| p.name.clone()).collect();
+            function.typing = Typing::new(false, ast::Type::Callable(Box::new(callable)));
+            function.declared_output = true;
+        }
+
+        Ok(function)
+    }
+
+    fn type_annotation(&mut self) -> Result<ast::Type, AstError> {
+        let t = match self.scanner.peek() {
+            Ok(t) => match &t.r#type {
+                TokenType::Int => ast::Type::Int,
+                TokenType::Bool => ast::Type::Bool,
+                TokenType::Str => ast::Type::String,
+                TokenType::Float => ast::Type::Float,
+                TokenType::List => {
+                    self.scanner.scan().unwrap();
+                    self.consume(TokenType::LeftBrace, "Expected [ after list")?;
+                    let item_type = self.type_annotation()?;
+                    self.consume(TokenType::RightBrace, "Expected ] after list item type")?;
+                    return Ok(ast::Type::List(Box::new(item_type)));
+                }
+                TokenType::Dict => {
+                    self.scanner.scan().unwrap();
+                    self.consume(TokenType::LeftBrace, "Expected [ after dict")?;
+                    let key_type = self.type_annotation()?;
+                    self.consume(TokenType::Comma, "Expected , after dict key type")?;
+                    let value_type = self.type_annotation()?;
+                    self.consume(TokenType::RightBrace, "Expected ] after dict value type")?;
+                    return Ok(ast::Type::Dict(Box::new(key_type), Box::new(value_type)));
+                }
+                TokenType::None => ast::Type::None,
+                TokenType::ValueIdentifier(s) => ast::Type::Object(s.clone()),
+                // (int, mut str) -> bool
+                TokenType::LeftParen => {
+                    self.scanner.scan().unwrap();
+                    let mut params = Vec::new();
+
+                    while !self.scanner.check(&TokenType::RightParen) {
+                        let mutable = self.scanner.check(&TokenType::Mut);
+                        if mutable {
+                            self.scanner.scan().unwrap();
+                        }
+                        params.push(Typing::new(mutable, self.type_annotation()?));
+
+                        if !self.scanner.check(&TokenType::RightParen) {
+                            self.consume(TokenType::Comma, "Expected , between parameter types")?;
+                        }
+                    }
+
+                    self.consume(TokenType::RightParen, "Expected ) after parameter types")?;
+                    self.consume(TokenType::Arrow, "Expected -> after parameter types")?;
+                    let output = self.type_annotation()?;
+
+                    return Ok(ast::Type::Callable(Box::new(Callable::new(
+                        ast::Signature::new_with_params(params),
+                        Typing::new(true, output),
+                        false,
+                    ))));
+                }
+                t => {
+                    return Err(SyntaxError::new(
+                        format!("Not a type: {t}"),
+                        self.scanner.get_span().clone(),
+                    ))
+                }
+            },
+            _ => {
+                return Err(SyntaxError::new(
+                    "Expected parameter type".to_owned(),
+                    self.scanner.get_span().clone(),
+                ))
+            }
+        };
+        self.scanner.scan().unwrap();
 
-        Ok(ast::Stmt::Function(func))
+        Ok(t)
     }
 
     fn statement(&mut self) -> Result<ast::Stmt, AstError> {
@@ -178,8 +489,11 @@ impl<'a> Parser<'a> {
             Ok(t) if t.r#type == TokenType::Indent => ast::Stmt::Block(self.block()?),
             Ok(t) if t.r#type == TokenType::If => ast::Stmt::Condition(self.if_statement()?),
             Ok(t) if t.r#type == TokenType::While => ast::Stmt::While(self.while_statement()?),
+            Ok(t) if t.r#type == TokenType::For => ast::Stmt::For(self.for_statement()?),
             Ok(t) if t.r#type == TokenType::Return => ast::Stmt::Return(self.return_statement()?),
             Ok(t) if t.r#type == TokenType::Assert => ast::Stmt::Assert(self.assert_statement()?),
+            Ok(t) if t.r#type == TokenType::Raise => ast::Stmt::Raise(self.raise_statement()?),
+            Ok(t) if t.r#type == TokenType::Try => ast::Stmt::Try(self.try_statement()?),
             _ => ast::Stmt::Expression(self.expression()?),
         })
     }
@@ -206,6 +520,136 @@ impl<'a> Parser<'a> {
         Ok(stmt)
     }
 
+    fn error_kind(&mut self) -> Result<String, AstError> {
+        let kind = self.identifier("Expected an error kind")?;
+        if !ast::ERROR_KINDS.contains(&kind.as_str()) {
+            return Err(SyntaxError::new(
+                format!("Unknown error kind {kind}"),
+                self.scanner.get_span().clone(),
+            ));
+        }
+        Ok(kind)
+    }
+
+    // raise
+    // raise Kind
+    // raise Kind("message")
+    fn raise_statement(&mut self) -> Result<ast::Raise, AstError> {
+        self.scanner.scan().unwrap();
+        let span = self.scanner.get_span().clone();
+
+        if self.scanner.check(&TokenType::NewLine) {
+            if self.excepts == 0 {
+                return Err(SyntaxError::new(
+                    format!("A bare raise can only be used in an except block"),
+                    span,
+                ));
+            }
+            self.scanner.scan().unwrap();
+            return Ok(ast::Raise {
+                kind: None,
+                message: None,
+                span,
+            });
+        }
+
+        let kind = self.error_kind()?;
+        let mut message = None;
+        if self.scanner.check(&TokenType::LeftParen) {
+            self.scanner.scan().unwrap();
+            if !self.scanner.check(&TokenType::RightParen) {
+                message = Some(self.expression()?);
+            }
+            self.consume(TokenType::RightParen, "Expect ')' after error message")?;
+        }
+
+        self.consume(TokenType::NewLine, "expects new line after raise statement")?;
+
+        Ok(ast::Raise {
+            kind: Some(kind),
+            message,
+            span,
+        })
+    }
+
+    fn try_statement(&mut self) -> Result<ast::Try, AstError> {
+        self.scanner.scan().unwrap();
+        let span = self.scanner.get_span().clone();
+
+        self.consume(TokenType::DoubleDot, "Expect ':' after try")?;
+        self.consume(TokenType::NewLine, "Expect new line after :")?;
+        let body = Box::new(self.statement()?);
+
+        let mut handlers = Vec::new();
+        while self.scanner.check(&TokenType::Except) {
+            self.scanner.scan().unwrap();
+            let span = self.scanner.get_span().clone();
+
+            // several kinds are grouped in a tuple, `except (IndexError, KeyError):`
+            let mut kinds = Vec::new();
+            if self.scanner.check(&TokenType::LeftParen) {
+                self.scanner.scan().unwrap();
+                kinds.push(self.error_kind()?);
+                while self.scanner.check(&TokenType::Comma) {
+                    self.scanner.scan().unwrap();
+                    if self.scanner.check(&TokenType::RightParen) {
+                        break;
+                    }
+                    kinds.push(self.error_kind()?);
+                }
+                self.consume(TokenType::RightParen, "Expect ')' after except kinds")?;
+            } else if !self.scanner.check(&TokenType::DoubleDot) {
+                kinds.push(self.error_kind()?);
+            }
+
+            let name = match self.scanner.check(&TokenType::As) {
+                true => {
+                    self.scanner.scan().unwrap();
+                    Some(self.identifier("Expected a name after as")?)
+                }
+                false => None,
+            };
+
+            self.consume(TokenType::DoubleDot, "Expect ':' after except")?;
+            self.consume(TokenType::NewLine, "Expect new line after :")?;
+
+            self.excepts += 1;
+            let body = self.statement();
+            self.excepts -= 1;
+
+            handlers.push(ast::Except {
+                kinds,
+                name,
+                body: Box::new(body?),
+                span,
+            });
+        }
+
+        let finally = if self.scanner.check(&TokenType::Finally) {
+            self.scanner.scan().unwrap();
+
+            self.consume(TokenType::DoubleDot, "Expect ':' after finally")?;
+            self.consume(TokenType::NewLine, "Expect new line after :")?;
+
+            Some(Box::new(self.statement()?))
+        } else {
+            None
+        };
+
+        if handlers.is_empty() && finally.is_none() {
+            return Err(SyntaxError::new(
+                format!("Expected except or finally after try"),
+                span,
+            ));
+        }
+
+        Ok(ast::Try {
+            body,
+            handlers,
+            finally,
+        })
+    }
+
     fn var_declaration(&mut self) -> Result<ast::Stmt, AstError> {
         self.scanner.scan()?;
 
@@ -243,6 +687,13 @@ impl<'a> Parser<'a> {
             }
         };
 
+        let annotation = if self.scanner.check(&TokenType::DoubleDot) {
+            self.scanner.scan().unwrap();
+            Some(self.type_annotation()?)
+        } else {
+            None
+        };
+
         self.consume(TokenType::Equal, "Expected an =")?;
         let expr = self.expression()?;
         self.consume(
@@ -250,15 +701,20 @@ impl<'a> Parser<'a> {
             "Expected new line after variable declaration",
         )?;
 
-        Ok(ast::Stmt::Var(Variable::new(name, expr, mutable)))
+        Ok(ast::Stmt::Var(match annotation {
+            Some(t) => Variable::new_with_typing(name, expr, Typing::new(mutable, t)),
+            None => Variable::new(name, expr, mutable),
+        }))
     }
 
     fn unary(&mut self) -> Result<Box<Expr>, AstError> {
-        for token in [&TokenType::Minus, &TokenType::Not] {
+        for token in [&TokenType::Minus, &TokenType::Not, &TokenType::Tilde] {
             if self.scanner.check(token) {
                 self.scanner.scan().unwrap();
                 let operator = if token == &TokenType::Not {
                     ast::UnaryOperator::Not
+                } else if token == &TokenType::Tilde {
+                    ast::UnaryOperator::Invert
                 } else {
                     ast::UnaryOperator::Minus
                 };
@@ -272,7 +728,7 @@ impl<'a> Parser<'a> {
             }
         }
 
-        self.call()
+        self.power()
     }
 
     fn return_statement(&mut self) -> Result<ast::Return, AstError> {
@@ -310,6 +766,55 @@ impl<'a> Parser<'a> {
         })
     }
 
+    fn for_statement(&mut self) -> Result<ast::For, AstError> {
+        self.scanner.scan().unwrap();
+
+        let mutable = self.scanner.check(&TokenType::Mut);
+        if mutable {
+            self.scanner.scan().unwrap();
+        }
+
+        let name = match self.scanner.scan() {
+            Ok(t) => match t.r#type {
+                TokenType::ValueIdentifier(s) => s,
+                t => {
+                    return Err(SyntaxError::new(
+                        format!("Expected a loop variable name, got {}", t),
+                        self.scanner.get_span().clone(),
+                    ))
+                }
+            },
+            Err(e) => return Err(e.into()),
+        };
+
+        self.consume(TokenType::In, "Expect 'in' after loop variable")?;
+        let iterable = self.expression()?;
+        self.consume(TokenType::DoubleDot, "Expect ':' after for iterable")?;
+        self.consume(TokenType::NewLine, "Expect new line after :")?;
+
+        let body = Box::new(self.statement()?);
+
+        // only runs when the loop was not broken out of
+        let r#else = if self.scanner.check(&TokenType::Else) {
+            self.scanner.scan().unwrap();
+
+            self.consume(TokenType::DoubleDot, "Expect ':' after else")?;
+            self.consume(TokenType::NewLine, "Expect new line after :")?;
+
+            Some(Box::new(self.statement()?))
+        } else {
+            None
+        };
+
+        Ok(ast::For {
+            name,
+            typing: Typing::new(mutable, ast::Type::Unknown),
+            iterable,
+            body,
+            r#else,
+        })
+    }
+
     fn if_statement(&mut self) -> Result<ast::Condition, AstError> {
         self.scanner.scan().unwrap();
 
@@ -370,24 +875,100 @@ impl<'a> Parser<'a> {
         if self.scanner.check(&TokenType::Equal) {
             self.scanner.scan().unwrap();
             let value = self.assignment()?;
-            let typing = expr.typing.clone();
+            return Ok(self.assign(expr, value));
+        }
 
-            match expr.body {
-                ExprBody::Value(Value::Variable(var)) => {
-                    return Ok(Box::new(Expr::new(
-                        ExprBody::Assign(ast::Assign { name: var, value }),
-                        typing,
-                        self.scanner.get_span().clone(),
-                    )))
-                }
-                ref e => self.errors.push(SyntaxError::new(
-                    format!("Invalid assignement target: {}", e),
+        let operator = match self.scanner.peek() {
+            Ok(t) => match t.r#type {
+                TokenType::PlusEqual => ast::Operator::Plus,
+                TokenType::MinusEqual => ast::Operator::Minus,
+                TokenType::StarEqual => ast::Operator::Multiply,
+                TokenType::SlashEqual => ast::Operator::Divide,
+                TokenType::StarStarEqual => ast::Operator::Power,
+                TokenType::SlashSlashEqual => ast::Operator::FloorDivide,
+                TokenType::AmpersandEqual => ast::Operator::BitAnd,
+                TokenType::PipeEqual => ast::Operator::BitOr,
+                TokenType::CaretEqual => ast::Operator::BitXor,
+                TokenType::LessLessEqual => ast::Operator::ShiftLeft,
+                TokenType::GreaterGreaterEqual => ast::Operator::ShiftRight,
+                _ => return Ok(expr),
+            },
+            Err(_) => return Ok(expr),
+        };
+        self.scanner.scan().unwrap();
+        let value = self.or()?;
+
+        match expr.body {
+            // `x op= value` is `x = x op value`
+            ExprBody::Value(Value::Variable(ref name)) => {
+                let current = Box::new(Expr::new(
+                    ExprBody::Value(Value::Variable(name.clone())),
+                    expr.typing.clone(),
+                    expr.span.clone(),
+                ));
+                let value = self.binary(current, operator, value);
+
+                Ok(self.assign(expr, value))
+            }
+            ExprBody::GetItem(_) | ExprBody::GetAttr(_) => Ok(Box::new(Expr::new(
+                ExprBody::AugmentedAssign(ast::AugmentedAssign {
+                    target: expr,
+                    operator,
+                    value,
+                }),
+                Typing::new(true, ast::Type::None),
+                self.scanner.get_span().clone(),
+            ))),
+            ref e => {
+                self.errors.push(SyntaxError::new(
+                    format!("Invalid augmented assignement target: {}", e),
                     self.scanner.get_span().clone(),
-                )),
-            };
+                ));
+                Ok(expr)
+            }
         }
+    }
 
-        Ok(expr)
+    fn assign(&mut self, target: Box<Expr>, value: Box<Expr>) -> Box<Expr> {
+        let typing = target.typing.clone();
+
+        match target.body {
+            ExprBody::Value(Value::Variable(var)) => {
+                return Box::new(Expr::new(
+                    ExprBody::Assign(ast::Assign { name: var, value }),
+                    typing,
+                    self.scanner.get_span().clone(),
+                ))
+            }
+            ExprBody::GetItem(item) => {
+                return Box::new(Expr::new(
+                    ExprBody::SetItem(ast::SetItem {
+                        object: item.object,
+                        index: item.index,
+                        value,
+                    }),
+                    Typing::new(true, ast::Type::None),
+                    self.scanner.get_span().clone(),
+                ))
+            }
+            ExprBody::GetAttr(attr) => {
+                return Box::new(Expr::new(
+                    ExprBody::SetAttr(ast::SetAttr {
+                        object: attr.object,
+                        name: attr.name,
+                        value,
+                    }),
+                    Typing::new(true, ast::Type::None),
+                    self.scanner.get_span().clone(),
+                ))
+            }
+            ref e => self.errors.push(SyntaxError::new(
+                format!("Invalid assignement target: {}", e),
+                self.scanner.get_span().clone(),
+            )),
+        };
+
+        target
     }
 
     fn or(&mut self) -> Result<Box<Expr>, AstError> {
@@ -417,7 +998,7 @@ impl<'a> Parser<'a> {
     }
 
     fn and(&mut self) -> Result<Box<Expr>, AstError> {
-        let left = self.equality()?;
+        let left = self.comparison()?;
 
         if self.scanner.check(&TokenType::And) {
             self.scanner.scan().unwrap();
@@ -436,65 +1017,111 @@ impl<'a> Parser<'a> {
         Ok(left)
     }
 
-    fn equality(&mut self) -> Result<Box<Expr>, AstError> {
-        let left = self.comparison()?;
+    // all comparisons share the same precedence and chain as in python, `a < b == c`
+    // checking both `a < b` and `b == c`
+    fn comparison(&mut self) -> Result<Box<Expr>, AstError> {
+        let left = self.bit_or()?;
+        let mut comparisons = Vec::new();
 
-        for token_type in [&TokenType::BangEqual, &TokenType::EqualEqual] {
-            if self.scanner.check(token_type) {
-                self.scanner.scan().unwrap();
-                let right = self.equality()?;
-                let operator = if token_type == &TokenType::BangEqual {
-                    ast::Operator::BangEqual
-                } else {
-                    ast::Operator::Equal
-                };
+        while let Some(operator) = self.comparison_operator()? {
+            comparisons.push((operator, self.bit_or()?));
+        }
 
-                return Ok(Box::new(Expr::new(
-                    ExprBody::Binary(ast::Binary {
-                        left,
-                        operator,
-                        right,
-                    }),
-                    Typing::new(true, ast::Type::Bool),
-                    self.scanner.get_span().clone(),
-                )));
+        let body = match comparisons.len() {
+            0 => return Ok(left),
+            1 => {
+                let (operator, right) = comparisons.pop().unwrap();
+                ExprBody::Binary(ast::Binary {
+                    left,
+                    operator,
+                    right,
+                })
             }
+            _ => ExprBody::Comparison(ast::Comparison { left, comparisons }),
+        };
+
+        Ok(Box::new(Expr::new(
+            body,
+            Typing::new(true, ast::Type::Bool),
+            self.scanner.get_span().clone(),
+        )))
+    }
+
+    fn comparison_operator(&mut self) -> Result<Option<ast::Operator>, AstError> {
+        let operator = match self.scanner.peek() {
+            Ok(t) => match t.r#type {
+                TokenType::EqualEqual => ast::Operator::Equal,
+                TokenType::BangEqual => ast::Operator::BangEqual,
+                TokenType::Greater => ast::Operator::Greater,
+                TokenType::GreaterEqual => ast::Operator::GreaterEqual,
+                TokenType::Less => ast::Operator::Less,
+                TokenType::LessEqual => ast::Operator::LessEqual,
+                TokenType::In => ast::Operator::In,
+                TokenType::Not => ast::Operator::NotIn,
+                _ => return Ok(None),
+            },
+            Err(_) => return Ok(None),
+        };
+
+        self.scanner.scan().unwrap();
+        if operator == ast::Operator::NotIn {
+            self.consume(TokenType::In, "Expected in after not")?;
+        }
+
+        Ok(Some(operator))
+    }
+
+    fn bit_or(&mut self) -> Result<Box<Expr>, AstError> {
+        let mut left = self.bit_xor()?;
+
+        while self.scanner.check(&TokenType::Pipe) {
+            self.scanner.scan().unwrap();
+            let right = self.bit_xor()?;
+            left = self.binary(left, ast::Operator::BitOr, right);
         }
 
         Ok(left)
     }
 
-    fn comparison(&mut self) -> Result<Box<Expr>, AstError> {
-        let left = self.addition()?;
-
-        for token in [
-            &TokenType::Greater,
-            &TokenType::GreaterEqual,
-            &TokenType::Less,
-            &TokenType::LessEqual,
-        ] {
-            if self.scanner.check(token) {
-                self.scanner.scan().unwrap();
-                let operator = if token == &TokenType::Greater {
-                    ast::Operator::Greater
-                } else if token == &TokenType::GreaterEqual {
-                    ast::Operator::GreaterEqual
-                } else if token == &TokenType::Less {
-                    ast::Operator::Less
-                } else {
-                    ast::Operator::LessEqual
-                };
-                let right = self.comparison()?;
-                return Ok(Box::new(Expr::new(
-                    ExprBody::Binary(ast::Binary {
-                        left,
-                        operator,
-                        right,
-                    }),
-                    Typing::new(true, ast::Type::Bool),
-                    self.scanner.get_span().clone(),
-                )));
-            }
+    fn bit_xor(&mut self) -> Result<Box<Expr>, AstError> {
+        let mut left = self.bit_and()?;
+
+        while self.scanner.check(&TokenType::Caret) {
+            self.scanner.scan().unwrap();
+            let right = self.bit_and()?;
+            left = self.binary(left, ast::Operator::BitXor, right);
+        }
+
+        Ok(left)
+    }
+
+    fn bit_and(&mut self) -> Result<Box<Expr>, AstError> {
+        let mut left = self.shift()?;
+
+        while self.scanner.check(&TokenType::Ampersand) {
+            self.scanner.scan().unwrap();
+            let right = self.shift()?;
+            left = self.binary(left, ast::Operator::BitAnd, right);
+        }
+
+        Ok(left)
+    }
+
+    fn shift(&mut self) -> Result<Box<Expr>, AstError> {
+        let mut left = self.addition()?;
+
+        loop {
+            let operator = match self.scanner.peek() {
+                Ok(t) => match t.r#type {
+                    TokenType::LessLess => ast::Operator::ShiftLeft,
+                    TokenType::GreaterGreater => ast::Operator::ShiftRight,
+                    _ => break,
+                },
+                Err(_) => break,
+            };
+            self.scanner.scan().unwrap();
+            let right = self.addition()?;
+            left = self.binary(left, operator, right);
         }
 
         Ok(left)
@@ -557,41 +1184,66 @@ impl<'a> Parser<'a> {
     }
 
     fn factor(&mut self) -> Result<Box<Expr>, AstError> {
-        let left = self.unary()?;
-
-        for token in [&TokenType::Star, &TokenType::Slash, &TokenType::Modulo] {
-            if self.scanner.check(token) {
-                self.scanner.scan().unwrap();
-                let right = self.factor()?;
-                let operator = if token == &TokenType::Star {
-                    ast::Operator::Multiply
-                } else if token == &TokenType::Slash {
-                    ast::Operator::Divide
-                } else {
-                    ast::Operator::Modulo
-                };
+        let mut left = self.unary()?;
 
-                let typing = Typing::new(
-                    true,
-                    self.type_merge
-                        .merge(&left.typing.r#type, &right.typing.r#type)
-                        .unwrap(),
-                );
-                return Ok(Box::new(Expr::new(
-                    ExprBody::Binary(ast::Binary {
-                        left,
-                        operator,
-                        right,
-                    }),
-                    typing,
-                    self.scanner.get_span().clone(),
-                )));
-            }
+        // left associative, `8 / 2 / 2` being `(8 / 2) / 2`
+        loop {
+            let operator = match self.scanner.peek() {
+                Ok(t) => match t.r#type {
+                    TokenType::Star => ast::Operator::Multiply,
+                    TokenType::Slash => ast::Operator::Divide,
+                    TokenType::SlashSlash => ast::Operator::FloorDivide,
+                    TokenType::Modulo => ast::Operator::Modulo,
+                    _ => break,
+                },
+                Err(_) => break,
+            };
+            self.scanner.scan().unwrap();
+            let right = self.unary()?;
+            left = self.binary(left, operator, right);
         }
 
         Ok(left)
     }
 
+    // binds tighter than a unary operator on its left, `-2 ** 2` being `-(2 ** 2)`,
+    // and is right associative
+    fn power(&mut self) -> Result<Box<Expr>, AstError> {
+        let left = self.call()?;
+
+        if !self.scanner.check(&TokenType::StarStar) {
+            return Ok(left);
+        }
+
+        self.scanner.scan().unwrap();
+        let right = self.unary()?;
+
+        Ok(self.binary(left, ast::Operator::Power, right))
+    }
+
+    fn binary(&self, left: Box<Expr>, operator: ast::Operator, right: Box<Expr>) -> Box<Expr> {
+        let r#type = match (
+            &operator,
+            self.type_merge
+                .merge(&left.typing.r#type, &right.typing.r#type)
+                .unwrap(),
+        ) {
+            // dividing always gives a float, `7 / 2` being 3.5
+            (ast::Operator::Divide, ast::Type::Int | ast::Type::Bool) => ast::Type::Float,
+            (_, merged_type) => merged_type,
+        };
+
+        Box::new(Expr::new(
+            ExprBody::Binary(ast::Binary {
+                left,
+                operator,
+                right,
+            }),
+            Typing::new(true, r#type),
+            self.scanner.get_span().clone(),
+        ))
+    }
+
     fn call(&mut self) -> Result<Box<Expr>, AstError> {
         let mut expr = self.primary()?;
 
@@ -599,6 +1251,12 @@ impl<'a> Parser<'a> {
             if self.scanner.check(&TokenType::LeftParen) {
                 self.scanner.scan().unwrap();
                 expr = self.finish_call(expr)?;
+            } else if self.scanner.check(&TokenType::LeftBrace) {
+                self.scanner.scan().unwrap();
+                expr = self.finish_get_item(expr)?;
+            } else if self.scanner.check(&TokenType::Dot) {
+                self.scanner.scan().unwrap();
+                expr = self.finish_get_attr(expr)?;
             } else {
                 break;
             }
@@ -608,15 +1266,24 @@ impl<'a> Parser<'a> {
 
     fn finish_call(&mut self, callee: Box<Expr>) -> Result<Box<Expr>, AstError> {
         let mut arguments = Vec::new();
+        let mut keywords = Vec::new();
 
         loop {
             match self.scanner.peek() {
                 Ok(t) if t.r#type == TokenType::Comma => {
                     self.scanner.scan().unwrap();
-                    arguments.push(self.expression()?);
                 }
                 Ok(t) if t.r#type == TokenType::RightParen => break,
-                _ => arguments.push(self.expression()?),
+                _ => match self.argument()? {
+                    (Some(name), value) => keywords.push((name, value)),
+                    (None, value) if keywords.is_empty() => arguments.push(value),
+                    (None, _) => {
+                        return Err(SyntaxError::new(
+                            format!("Positional argument follows keyword argument"),
+                            self.scanner.get_span().clone(),
+                        ))
+                    }
+                },
             }
         }
 
@@ -624,14 +1291,167 @@ impl<'a> Parser<'a> {
         let typing = callee.typing.clone();
 
         Ok(Box::new(Expr::new(
-            ExprBody::Call(ast::Call { callee, arguments }),
+            ExprBody::Call(ast::Call {
+                callee,
+                arguments,
+                keywords,
+            }),
+            typing,
+            self.scanner.get_span().clone(),
+        )))
+    }
+
+    // either `value` or `name=value`
+    fn argument(&mut self) -> Result<(Option<String>, Box<Expr>), AstError> {
+        let expr = self.or()?;
+
+        if !self.scanner.check(&TokenType::Equal) {
+            return Ok((None, expr));
+        }
+
+        match expr.body {
+            ExprBody::Value(Value::Variable(name)) => {
+                self.scanner.scan().unwrap();
+                Ok((Some(name), self.expression()?))
+            }
+            e => Err(SyntaxError::new(
+                format!("Invalid argument name: {e}"),
+                self.scanner.get_span().clone(),
+            )),
+        }
+    }
+
+    fn finish_get_item(&mut self, object: Box<Expr>) -> Result<Box<Expr>, AstError> {
+        let index = self.expression()?;
+        self.consume(TokenType::RightBrace, "Expected ] after index")?;
+
+        let typing = match &object.typing.r#type {
+            ast::Type::List(t) => Typing::new(object.typing.mutable, *t.clone()),
+            ast::Type::Dict(_, t) => Typing::new(object.typing.mutable, *t.clone()),
+            _ => Typing::new(object.typing.mutable, ast::Type::Unknown),
+        };
+
+        Ok(Box::new(Expr::new(
+            ExprBody::GetItem(ast::GetItem { object, index }),
+            typing,
+            self.scanner.get_span().clone(),
+        )))
+    }
+
+    fn finish_get_attr(&mut self, object: Box<Expr>) -> Result<Box<Expr>, AstError> {
+        let name = match self.scanner.scan()?.r#type {
+            TokenType::ValueIdentifier(s) => s,
+            t => {
+                return Err(SyntaxError::new(
+                    format!("Expected an attribute name after ., got {}", t),
+                    self.scanner.get_span().clone(),
+                ))
+            }
+        };
+
+        let typing = Typing::new(object.typing.mutable, ast::Type::Unknown);
+
+        Ok(Box::new(Expr::new(
+            ExprBody::GetAttr(ast::GetAttr { object, name }),
             typing,
             self.scanner.get_span().clone(),
         )))
     }
 
+    fn finish_list(&mut self) -> Result<Box<Expr>, AstError> {
+        let mut items = Vec::new();
+
+        loop {
+            match self.scanner.peek() {
+                Ok(t) if t.r#type == TokenType::Comma => {
+                    self.scanner.scan().unwrap();
+                }
+                Ok(t) if t.r#type == TokenType::RightBrace => break,
+                _ => items.push(self.expression()?),
+            }
+        }
+
+        self.consume(TokenType::RightBrace, "Expected ] after list items")?;
+
+        let item_type = items
+            .iter()
+            .map(|i| i.typing.r#type.clone())
+            .reduce(|a, b| self.type_merge.merge(&a, &b).unwrap())
+            .unwrap_or(ast::Type::Unknown);
+
+        Ok(Box::new(Expr::new(
+            ExprBody::List(ast::List { items }),
+            Typing::new(true, ast::Type::List(Box::new(item_type))),
+            self.scanner.get_span().clone(),
+        )))
+    }
+
+    fn finish_dict(&mut self) -> Result<Box<Expr>, AstError> {
+        let mut items = Vec::new();
+
+        loop {
+            match self.scanner.peek() {
+                Ok(t) if t.r#type == TokenType::Comma => {
+                    self.scanner.scan().unwrap();
+                }
+                Ok(t) if t.r#type == TokenType::RightAccolade => break,
+                _ => {
+                    let key = self.expression()?;
+                    self.consume(TokenType::DoubleDot, "Expected : after dict key")?;
+                    items.push((key, self.expression()?));
+                }
+            }
+        }
+
+        self.consume(TokenType::RightAccolade, "Expected } after dict items")?;
+
+        let key_type = items
+            .iter()
+            .map(|(k, _)| k.typing.r#type.clone())
+            .reduce(|a, b| self.type_merge.merge(&a, &b).unwrap())
+            .unwrap_or(ast::Type::Unknown);
+        let value_type = items
+            .iter()
+            .map(|(_, v)| v.typing.r#type.clone())
+            .reduce(|a, b| self.type_merge.merge(&a, &b).unwrap())
+            .unwrap_or(ast::Type::Unknown);
+
+        Ok(Box::new(Expr::new(
+            ExprBody::Dict(ast::Dict { items }),
+            Typing::new(
+                true,
+                ast::Type::Dict(Box::new(key_type), Box::new(value_type)),
+            ),
+            self.scanner.get_span().clone(),
+        )))
+    }
+
+    fn finish_tuple(&mut self, first: Box<Expr>) -> Result<Box<Expr>, AstError> {
+        let mut items = vec![first];
+
+        loop {
+            match self.scanner.peek() {
+                Ok(t) if t.r#type == TokenType::Comma => {
+                    self.scanner.scan().unwrap();
+                }
+                Ok(t) if t.r#type == TokenType::RightParen => break,
+                _ => items.push(self.expression()?),
+            }
+        }
+
+        self.consume(TokenType::RightParen, "Expected ) after tuple items")?;
+
+        let types = items.iter().map(|i| i.typing.r#type.clone()).collect();
+
+        Ok(Box::new(Expr::new(
+            ExprBody::Tuple(ast::Tuple { items }),
+            Typing::new(true, ast::Type::Tuple(types)),
+            self.scanner.get_span().clone(),
+        )))
+    }
+
     fn primary(&mut self) -> Result<Box<Expr>, AstError> {
-        let next = self.scanner.scan().unwrap();
+        let next = self.scanner.scan()?;
 
         Ok(match next.r#type {
             TokenType::False => Box::new(Expr::new(
@@ -664,11 +1484,17 @@ impl<'a> Parser<'a> {
                 Typing::new(true, ast::Type::String),
                 self.scanner.get_span().clone(),
             )),
+            TokenType::ValueFormatString(parts) => self.format_string(parts)?,
             TokenType::ValueIdentifier(s) => Box::new(Expr::new(
                 ExprBody::Value(Value::Variable(s.to_owned())),
                 Typing::new(false, ast::Type::Unknown),
                 self.scanner.get_span().clone(),
             )),
+            TokenType::Self_ => Box::new(Expr::new(
+                ExprBody::Value(Value::Variable("self".to_owned())),
+                Typing::new(false, ast::Type::Unknown),
+                self.scanner.get_span().clone(),
+            )),
             TokenType::Break => {
                 self.consume(TokenType::NewLine, "Expect new line after break")?;
                 Box::new(Expr::new(
@@ -686,8 +1512,13 @@ impl<'a> Parser<'a> {
                 ))
             }
             TokenType::EOF => return Err(AstError::EOF),
+            TokenType::LeftBrace => self.finish_list()?,
+            TokenType::LeftAccolade => self.finish_dict()?,
             TokenType::LeftParen => {
                 let expr = self.expression()?;
+                if self.scanner.check(&TokenType::Comma) {
+                    return self.finish_tuple(expr);
+                }
                 let typing = expr.typing.clone();
                 self.consume(TokenType::RightParen, "expect ')' after expression")?;
                 Box::new(Expr::new(
@@ -700,10 +1531,107 @@ impl<'a> Parser<'a> {
                     self.scanner.get_span().clone(),
                 ))
             }
-            e => panic!("Parsing not yet implemented: {}", e),
+            e => {
+                return Err(SyntaxError::new(
+                    format!("Expected an expression, got {e}"),
+                    self.scanner.get_span().clone(),
+                ))
+            }
         })
     }
 
+    fn format_string(&mut self, parts: Vec<FormatStringPart>) -> Result<Box<Expr>, AstError> {
+        let span = self.scanner.get_span().clone();
+        let parts = parts
+            .into_iter()
+            .map(|part| match part {
+                FormatStringPart::Literal(l) => Ok(ast::FormatPart::Literal(l)),
+                FormatStringPart::Field(source, spec) => {
+                    let spec = match spec {
+                        Some(spec) => self.format_spec(&spec, &span)?,
+                        None => ast::FormatSpec::default(),
+                    };
+                    Ok(ast::FormatPart::Value(
+                        self.format_field(&source, &span)?,
+                        spec,
+                    ))
+                }
+            })
+            .collect::<Result<Vec<ast::FormatPart>, AstError>>()?;
+
+        Ok(Box::new(Expr::new(
+            ExprBody::FormatString(ast::FormatString { parts }),
+            Typing::new(true, ast::Type::String),
+            span,
+        )))
+    }
+
+    // the expression of a replacement field is parsed on its own, as a line of the same source
+    fn format_field(&mut self, source: &str, span: &Span) -> Result<Box<Expr>, AstError> {
+        let source = format!("{}\n", source.trim());
+        let mut parser = Parser::new(Scanner::new_at_line(&source, span.get_line()));
+        let invalid = || {
+            SyntaxError::new(
+                format!("Invalid expression in format string: {}", source.trim()),
+                span.clone(),
+            )
+        };
+
+        let expr = parser.expression().map_err(|_| invalid())?;
+
+        if !parser.scanner.check(&TokenType::NewLine) {
+            return Err(invalid());
+        }
+
+        Ok(expr)
+    }
+
+    // [[fill]align][sign][0][width][.precision][type]
+    fn format_spec(&mut self, spec: &str, span: &Span) -> Result<ast::FormatSpec, AstError> {
+        let invalid = || SyntaxError::new(format!("Invalid format spec: {spec}"), span.clone());
+        let is_align = |c: &char| ['<', '>', '^'].contains(c);
+
+        let mut format = ast::FormatSpec::default();
+        let mut chars = spec.chars().peekable();
+
+        let mut lookahead = spec.chars().skip(1);
+        if lookahead.next().is_some_and(|c| is_align(&c)) {
+            format.fill = chars.next();
+            format.align = chars.next();
+        } else if chars.peek().is_some_and(is_align) {
+            format.align = chars.next();
+        }
+
+        if chars.peek().is_some_and(|c| ['+', '-', ' '].contains(c)) {
+            format.sign = chars.next();
+        }
+        if chars.peek() == Some(&'0') {
+            format.zero = true;
+            chars.next();
+        }
+
+        let number = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+            let mut digits = String::new();
+            while let Some(d) = chars.next_if(|c| c.is_ascii_digit()) {
+                digits.push(d);
+            }
+            digits.parse::<usize>().ok()
+        };
+
+        format.width = number(&mut chars);
+        if chars.next_if_eq(&'.').is_some() {
+            format.precision = Some(number(&mut chars).ok_or_else(invalid)?);
+        }
+        format.kind = chars.next_if(|c| "deEfFgGosxX%".contains(*c));
+
+        // the fill is written byte by byte
+        if chars.next().is_some() || format.fill.is_some_and(|c| !c.is_ascii()) {
+            return Err(invalid());
+        }
+
+        Ok(format)
+    }
+
     fn consume(&mut self, expected: TokenType, msg: &str) -> Result<Token, AstError> {
         if self.scanner.check(&expected) {
             return Ok(self.scanner.scan()?);
@@ -721,13 +1649,21 @@ mod tests {
     use super::ast::Binary;
     use super::ast::Call;
     use super::ast::Condition;
+    use super::ast::Dict;
+    use super::ast::FormatPart;
+    use super::ast::FormatSpec;
     use super::ast::Function;
     use super::ast::FunctionParameter;
+    use super::ast::GetItem;
+    use super::ast::List;
     use super::ast::Logical;
     use super::ast::LogicalOperator;
     use super::ast::Operator;
     use super::ast::Return;
+    use super::ast::SetAttr;
+    use super::ast::Signature;
     use super::ast::Stmt;
+    use super::ast::Type;
     use super::ast::Typing;
     use super::ast::Unary;
     use super::ast::UnaryOperator;
@@ -817,6 +1753,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn var_declaration_with_annotation() {
+        let string = "var mut coucou: float = 1\n";
+        let scanner = Scanner::new(string);
+        let mut parser = Parser::new(scanner);
+
+        let success = parser.build();
+
+        assert!(success);
+        assert_eq!(parser.ast.len(), 1);
+
+        let Stmt::Var(variable) = &parser.ast[0] else {
+            panic!("Expected a variable, got {}", parser.ast[0]);
+        };
+        assert_eq!(
+            variable.typing,
+            Typing::new(true, vif_objects::ast::Type::Float)
+        );
+        assert!(variable.typing.mutable);
+        assert_eq!(variable.value.typing.r#type, vif_objects::ast::Type::Int);
+    }
+
     #[test]
     fn var_mut_declaration() {
         let string = "var mut coucou = -1\n";
@@ -976,6 +1934,7 @@ mod tests {
                         Span::new(1, 11)
                     )),
                     arguments: Vec::new(),
+                    keywords: Vec::new(),
                 }),
                 Typing::new(false, vif_objects::ast::Type::Unknown),
                 Span::new(1, 13)
@@ -984,8 +1943,8 @@ mod tests {
     }
 
     #[test]
-    fn call_with_args() {
-        let string = "my_function(a, b, c)";
+    fn list_get_item() {
+        let string = "[1, 2][0]";
         let scanner = Scanner::new(string);
         let mut parser = Parser::new(scanner);
 
@@ -996,16 +1955,487 @@ mod tests {
         assert_eq!(
             parser.ast[0],
             Stmt::Expression(Box::new(Expr::new(
-                ExprBody::Call(Call {
-                    callee: Box::new(Expr::new(
-                        ExprBody::Value(Value::Variable("my_function".to_owned())),
-                        Typing::new(false, vif_objects::ast::Type::Unknown),
-                        Span::new(1, 11)
+                ExprBody::GetItem(GetItem {
+                    object: Box::new(Expr::new(
+                        ExprBody::List(List {
+                            items: vec![
+                                Box::new(Expr::new(
+                                    ExprBody::Value(Value::Integer(1)),
+                                    Typing::new(true, Type::Int),
+                                    Span::new(1, 2)
+                                )),
+                                Box::new(Expr::new(
+                                    ExprBody::Value(Value::Integer(2)),
+                                    Typing::new(true, Type::Int),
+                                    Span::new(1, 5)
+                                )),
+                            ]
+                        }),
+                        Typing::new(true, Type::List(Box::new(Type::Int))),
+                        Span::new(1, 6)
                     )),
-                    arguments: vec![
-                        Box::new(Expr::new(
-                            ExprBody::Value(Value::Variable("a".to_owned())),
-                            Typing::new(false, vif_objects::ast::Type::Unknown),
+                    index: Box::new(Expr::new(
+                        ExprBody::Value(Value::Integer(0)),
+                        Typing::new(true, Type::Int),
+                        Span::new(1, 8)
+                    )),
+                }),
+                Typing::new(true, Type::Int),
+                Span::new(1, 9)
+            )))
+        );
+    }
+
+    #[test]
+    fn dict_membership() {
+        let string = "1 in {1: 2}";
+        let scanner = Scanner::new(string);
+        let mut parser = Parser::new(scanner);
+
+        let success = parser.build();
+
+        assert!(success);
+        assert_eq!(parser.ast.len(), 1);
+        assert_eq!(
+            parser.ast[0],
+            Stmt::Expression(Box::new(Expr::new(
+                ExprBody::Binary(Binary {
+                    left: Box::new(Expr::new(
+                        ExprBody::Value(Value::Integer(1)),
+                        Typing::new(true, Type::Int),
+                        Span::new(1, 1)
+                    )),
+                    operator: Operator::In,
+                    right: Box::new(Expr::new(
+                        ExprBody::Dict(Dict {
+                            items: vec![(
+                                Box::new(Expr::new(
+                                    ExprBody::Value(Value::Integer(1)),
+                                    Typing::new(true, Type::Int),
+                                    Span::new(1, 7)
+                                )),
+                                Box::new(Expr::new(
+                                    ExprBody::Value(Value::Integer(2)),
+                                    Typing::new(true, Type::Int),
+                                    Span::new(1, 10)
+                                )),
+                            )]
+                        }),
+                        Typing::new(true, Type::Dict(Box::new(Type::Int), Box::new(Type::Int))),
+                        Span::new(1, 11)
+                    )),
+                }),
+                Typing::new(true, Type::Bool),
+                Span::new(1, 11)
+            )))
+        );
+    }
+
+    #[test]
+    fn attribute_assignment() {
+        let string = "p.x = 1";
+        let scanner = Scanner::new(string);
+        let mut parser = Parser::new(scanner);
+
+        let success = parser.build();
+
+        assert!(success);
+        assert_eq!(parser.ast.len(), 1);
+        assert_eq!(
+            parser.ast[0],
+            Stmt::Expression(Box::new(Expr::new(
+                ExprBody::SetAttr(SetAttr {
+                    object: Box::new(Expr::new(
+                        ExprBody::Value(Value::Variable("p".to_owned())),
+                        Typing::new(false, Type::Unknown),
+                        Span::new(1, 1)
+                    )),
+                    name: "x".to_owned(),
+                    value: Box::new(Expr::new(
+                        ExprBody::Value(Value::Integer(1)),
+                        Typing::new(true, Type::Int),
+                        Span::new(1, 7)
+                    )),
+                }),
+                Typing::new(true, Type::None),
+                Span::new(1, 7)
+            )))
+        );
+    }
+
+    #[test]
+    fn class_declaration() {
+        let string = "class Point:\n    def __init__(self, x: int):\n        self.x = x\n    def get(self):\n        return self.x\n";
+        let scanner = Scanner::new(string);
+        let mut parser = Parser::new(scanner);
+
+        let success = parser.build();
+
+        assert!(success);
+        assert_eq!(parser.ast.len(), 1);
+
+        let Stmt::Class(class) = &parser.ast[0] else {
+            panic!("Expected a class, got {}", parser.ast[0]);
+        };
+        assert_eq!(class.name, "Point");
+        assert_eq!(
+            class
+                .methods
+                .iter()
+                .map(|m| m.name.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["Point.__init__", "Point.get"]
+        );
+        assert_eq!(
+            class.methods[0].params,
+            vec![
+                FunctionParameter {
+                    name: "self".to_owned(),
+                    typing: Typing::new(true, Type::Object("Point".to_owned())),
+                    default: None,
+                    span: Span::new(2, 21),
+                },
+                FunctionParameter {
+                    name: "x".to_owned(),
+                    typing: Typing::new(false, Type::Int),
+                    default: None,
+                    span: Span::new(2, 24),
+                },
+            ]
+        );
+        assert!(!class.methods[1].params[0].typing.mutable);
+    }
+
+    #[test]
+    fn decorated_function() {
+        let string = "@outer\n@inner(1)\ndef f(x: int):\n    return x\n";
+        let scanner = Scanner::new(string);
+        let mut parser = Parser::new(scanner);
+
+        let success = parser.build();
+
+        assert!(success);
+        assert_eq!(parser.ast.len(), 1);
+
+        let Stmt::Block(stmts) = &parser.ast[0] else {
+            panic!("Expected a block, got {}", parser.ast[0]);
+        };
+        let [Stmt::Function(function), Stmt::Var(var)] = stmts.as_slice() else {
+            panic!("Expected a function and a variable, got {:?}", stmts);
+        };
+        assert_eq!(function.params[0].typing, Typing::new(false, Type::Int));
+        assert_eq!(var.name, "f");
+
+        // f = outer(inner(1)(f))
+        let ExprBody::Call(outer) = &var.value.body else {
+            panic!("Expected a call, got {}", var.value);
+        };
+        assert_eq!(
+            outer.callee.body,
+            ExprBody::Value(Value::Variable("outer".to_owned()))
+        );
+        let ExprBody::Call(inner) = &outer.arguments[0].body else {
+            panic!("Expected a call, got {}", outer.arguments[0]);
+        };
+        assert!(matches!(inner.callee.body, ExprBody::Call(_)));
+        assert_eq!(
+            inner.arguments[0].body,
+            ExprBody::Value(Value::Variable("f".to_owned()))
+        );
+    }
+
+    #[test]
+    fn callable_annotation_with_mutable_parameters() {
+        let string = "def f(func: (int, mut str) -> bool):\n    return True\n";
+        let scanner = Scanner::new(string);
+        let mut parser = Parser::new(scanner);
+
+        let success = parser.build();
+
+        assert!(success);
+        assert_eq!(parser.ast.len(), 1);
+
+        let Stmt::Function(function) = &parser.ast[0] else {
+            panic!("Expected a function, got {}", parser.ast[0]);
+        };
+        let Type::Callable(callable) = &function.params[0].typing.r#type else {
+            panic!("Expected a callable, got {}", function.params[0].typing);
+        };
+        let Signature::Parameters(params, _) = &callable.signature else {
+            panic!("Expected parameters, got {}", callable.signature);
+        };
+        assert_eq!(params.len(), 2);
+        assert!(!params[0].mutable);
+        assert!(params[1].mutable);
+        assert_eq!(params[1].r#type, Type::String);
+        assert_eq!(callable.output.r#type, Type::Bool);
+    }
+
+    #[test]
+    fn for_statement() {
+        let string = "for mut i in range(3):\n    i = 1\nelse:\n    i\n";
+        let scanner = Scanner::new(string);
+        let mut parser = Parser::new(scanner);
+
+        let success = parser.build();
+
+        assert!(success);
+        assert_eq!(parser.ast.len(), 1);
+
+        let Stmt::For(r#for) = &parser.ast[0] else {
+            panic!("Expected a for loop, got {}", parser.ast[0]);
+        };
+        assert_eq!(r#for.name, "i");
+        assert!(r#for.typing.mutable);
+        assert!(matches!(r#for.iterable.body, ExprBody::Call(_)));
+        assert!(matches!(*r#for.body, Stmt::Block(_)));
+        assert!(matches!(r#for.r#else.as_deref(), Some(Stmt::Block(_))));
+    }
+
+    #[test]
+    fn try_statement() {
+        let string = "try:\n    raise ValueError(\"a\")\nexcept (IndexError, KeyError) as e:\n    raise\nexcept:\n    e\nfinally:\n    e\n";
+        let scanner = Scanner::new(string);
+        let mut parser = Parser::new(scanner);
+
+        let success = parser.build();
+
+        assert!(success);
+        assert_eq!(parser.ast.len(), 1);
+
+        let Stmt::Try(r#try) = &parser.ast[0] else {
+            panic!("Expected a try statement, got {}", parser.ast[0]);
+        };
+        let Stmt::Block(body) = r#try.body.as_ref() else {
+            panic!("Expected a block, got {}", r#try.body);
+        };
+        let Stmt::Raise(raise) = &body[0] else {
+            panic!("Expected a raise statement, got {}", body[0]);
+        };
+        assert_eq!(raise.kind.as_deref(), Some("ValueError"));
+        assert!(raise.message.is_some());
+
+        assert_eq!(r#try.handlers.len(), 2);
+        assert_eq!(r#try.handlers[0].kinds, vec!["IndexError", "KeyError"]);
+        assert_eq!(r#try.handlers[0].name.as_deref(), Some("e"));
+        assert!(r#try.handlers[1].kinds.is_empty());
+        assert!(r#try.handlers[1].name.is_none());
+        assert!(r#try.finally.is_some());
+    }
+
+    #[test]
+    fn bare_raise_outside_except() {
+        let string = "def f():\n    raise\n";
+        let scanner = Scanner::new(string);
+        let mut parser = Parser::new(scanner);
+
+        assert!(!parser.build());
+    }
+
+    #[test]
+    fn format_string() {
+        let string = "f\"total: {count + 1} ({ratio:>8.2f})\"\n";
+        let scanner = Scanner::new(string);
+        let mut parser = Parser::new(scanner);
+
+        let success = parser.build();
+
+        assert!(success);
+        let Stmt::Expression(expr) = &parser.ast[0] else {
+            panic!("Expected an expression, got {}", parser.ast[0]);
+        };
+        let ExprBody::FormatString(format) = &expr.body else {
+            panic!("Expected a format string, got {}", expr);
+        };
+        assert_eq!(format.parts.len(), 5);
+        assert_eq!(format.parts[0], FormatPart::Literal("total: ".to_owned()));
+        let FormatPart::Value(value, spec) = &format.parts[1] else {
+            panic!("Expected a field, got {:?}", format.parts[1]);
+        };
+        assert!(matches!(value.body, ExprBody::Binary(_)));
+        assert_eq!(*spec, FormatSpec::default());
+        let FormatPart::Value(_, spec) = &format.parts[3] else {
+            panic!("Expected a field, got {:?}", format.parts[3]);
+        };
+        assert_eq!(spec.align, Some('>'));
+        assert_eq!(spec.width, Some(8));
+        assert_eq!(spec.precision, Some(2));
+        assert_eq!(spec.kind, Some('f'));
+    }
+
+    #[test]
+    fn invalid_format_spec() {
+        let string = "f\"{ratio:.f}\"\n";
+        let scanner = Scanner::new(string);
+        let mut parser = Parser::new(scanner);
+
+        assert!(!parser.build());
+    }
+
+    #[test]
+    fn invalid_format_field() {
+        let string = "f\"{1 +}\"\n";
+        let scanner = Scanner::new(string);
+        let mut parser = Parser::new(scanner);
+
+        assert!(!parser.build());
+        let errors = parser.get_errors();
+        assert!(errors[0]
+            .format(string)
+            .ends_with("Invalid expression in format string: 1 +"));
+    }
+
+    #[test]
+    fn arithmetic_precedence() {
+        let string = "-2 ** 3 ** 2 // 4 / 5 % 6\n";
+        let scanner = Scanner::new(string);
+        let mut parser = Parser::new(scanner);
+
+        let success = parser.build();
+
+        assert!(success);
+        assert_eq!(
+            format!("{}", parser.ast[0]),
+            "Binary[Binary[Binary[Unary[- Binary[Value[2], **, Binary[Value[3], **, Value[2]]]], //, Value[4]], /, Value[5]], %, Value[6]]"
+        );
+    }
+
+    #[test]
+    fn bitwise_precedence() {
+        let string = "a | b ^ c & ~d << 1 + 1 == e\n";
+        let scanner = Scanner::new(string);
+        let mut parser = Parser::new(scanner);
+
+        let success = parser.build();
+
+        assert!(success);
+        assert_eq!(
+            format!("{}", parser.ast[0]),
+            "Binary[Binary[Value[var[a]], |, Binary[Value[var[b]], ^, Binary[Value[var[c]], &, Binary[Unary[~ Value[var[d]]], <<, Binary[Value[1], +, Value[1]]]]]], =, Value[var[e]]]"
+        );
+    }
+
+    #[test]
+    fn chained_comparison() {
+        let string = "0 <= i < n == True\n";
+        let scanner = Scanner::new(string);
+        let mut parser = Parser::new(scanner);
+
+        let success = parser.build();
+
+        assert!(success);
+        assert_eq!(
+            format!("{}", parser.ast[0]),
+            "Comparison[Value[0], <=, Value[var[i]], <, Value[var[n]], =, Value[True]]"
+        );
+    }
+
+    #[test]
+    fn augmented_assignment() {
+        let string = "x += 1\nxs[f()] **= 2\n";
+        let scanner = Scanner::new(string);
+        let mut parser = Parser::new(scanner);
+
+        let success = parser.build();
+
+        assert!(success);
+        assert_eq!(
+            format!("{}", parser.ast[0]),
+            "Assign[Assign[x=Binary[Value[var[x]], +, Value[1]]]]"
+        );
+        // the index is kept once, to be evaluated once
+        assert_eq!(
+            format!("{}", parser.ast[1]),
+            "AugmentedAssign[GetItem[Value[var[xs]][Call[Function[Value[var[f]]]]]] **= Value[2]]"
+        );
+    }
+
+    #[test]
+    fn function_with_defaults() {
+        let string = "def f(a: int, b: int = 3):\n    return a\n";
+        let scanner = Scanner::new(string);
+        let mut parser = Parser::new(scanner);
+
+        let success = parser.build();
+
+        assert!(success);
+        let Stmt::Function(function) = &parser.ast[0] else {
+            panic!("Expected a function, got {}", parser.ast[0]);
+        };
+        assert!(function.params[0].default.is_none());
+        assert_eq!(
+            function.params[1].default.as_ref().unwrap().body,
+            ExprBody::Value(Value::Integer(3))
+        );
+    }
+
+    #[test]
+    fn call_with_keywords() {
+        let string = "greet(\"vif\", punctuation=\"!\")";
+        let scanner = Scanner::new(string);
+        let mut parser = Parser::new(scanner);
+
+        let success = parser.build();
+
+        assert!(success);
+        let Stmt::Expression(expr) = &parser.ast[0] else {
+            panic!("Expected an expression, got {}", parser.ast[0]);
+        };
+        let ExprBody::Call(call) = &expr.body else {
+            panic!("Expected a call, got {}", expr);
+        };
+        assert_eq!(call.arguments.len(), 1);
+        assert_eq!(call.keywords.len(), 1);
+        assert_eq!(call.keywords[0].0, "punctuation");
+        assert_eq!(
+            call.keywords[0].1.body,
+            ExprBody::Value(Value::String("!".to_owned()))
+        );
+    }
+
+    #[test]
+    fn import_statements() {
+        let string = "import utils\nfrom geometry import Point, origin\n";
+        let scanner = Scanner::new(string);
+        let mut parser = Parser::new(scanner);
+
+        let success = parser.build();
+
+        assert!(success);
+        assert_eq!(parser.ast.len(), 2);
+
+        let [Stmt::Import(utils), Stmt::Import(geometry)] = parser.ast.as_slice() else {
+            panic!("Expected two imports, got {:?}", parser.ast);
+        };
+        assert_eq!(utils.module, "utils");
+        assert!(utils.names.is_empty());
+        assert_eq!(geometry.module, "geometry");
+        assert_eq!(geometry.names, vec!["Point", "origin"]);
+    }
+
+    #[test]
+    fn call_with_args() {
+        let string = "my_function(a, b, c)";
+        let scanner = Scanner::new(string);
+        let mut parser = Parser::new(scanner);
+
+        let success = parser.build();
+
+        assert!(success);
+        assert_eq!(parser.ast.len(), 1);
+        assert_eq!(
+            parser.ast[0],
+            Stmt::Expression(Box::new(Expr::new(
+                ExprBody::Call(Call {
+                    callee: Box::new(Expr::new(
+                        ExprBody::Value(Value::Variable("my_function".to_owned())),
+                        Typing::new(false, vif_objects::ast::Type::Unknown),
+                        Span::new(1, 11)
+                    )),
+                    arguments: vec![
+                        Box::new(Expr::new(
+                            ExprBody::Value(Value::Variable("a".to_owned())),
+                            Typing::new(false, vif_objects::ast::Type::Unknown),
                             Span::new(1, 13)
                         )),
                         Box::new(Expr::new(
@@ -1018,7 +2448,8 @@ mod tests {
                             Typing::new(false, vif_objects::ast::Type::Unknown),
                             Span::new(1, 19)
                         )),
-                    ]
+                    ],
+                    keywords: Vec::new(),
                 }),
                 Typing::new(false, vif_objects::ast::Type::Unknown),
                 Span::new(1, 20)
@@ -1046,15 +2477,21 @@ mod tests {
                 params: vec![
                     FunctionParameter {
                         name: "a".to_owned(),
-                        typing: Typing::new(false, vif_objects::ast::Type::String)
+                        typing: Typing::new(false, vif_objects::ast::Type::Unknown),
+                        default: None,
+                        span: Span::new(2, 29),
                     },
                     FunctionParameter {
                         name: "b".to_owned(),
-                        typing: Typing::new(false, vif_objects::ast::Type::String)
+                        typing: Typing::new(false, vif_objects::ast::Type::Unknown),
+                        default: None,
+                        span: Span::new(2, 32),
                     },
                     FunctionParameter {
                         name: "c".to_owned(),
-                        typing: Typing::new(true, vif_objects::ast::Type::String)
+                        typing: Typing::new(true, vif_objects::ast::Type::Unknown),
+                        default: None,
+                        span: Span::new(2, 39),
                     },
                 ],
                 body: vec![Stmt::Return(Return {
@@ -1064,7 +2501,9 @@ mod tests {
                         Span::new(3, 23)
                     ))
                 })],
-                typing: Typing::new(false, vif_objects::ast::Type::None)
+                typing: Typing::new(false, vif_objects::ast::Type::Unknown),
+                declared_output: false,
+                span: Span::new(2, 27),
             })
         );
     }
@@ -1089,15 +2528,21 @@ mod tests {
                 params: vec![
                     FunctionParameter {
                         name: "a".to_owned(),
-                        typing: Typing::new(false, vif_objects::ast::Type::Unknown)
+                        typing: Typing::new(false, vif_objects::ast::Type::Unknown),
+                        default: None,
+                        span: Span::new(2, 29),
                     },
                     FunctionParameter {
                         name: "b".to_owned(),
-                        typing: Typing::new(true, vif_objects::ast::Type::Unknown)
+                        typing: Typing::new(true, vif_objects::ast::Type::Unknown),
+                        default: None,
+                        span: Span::new(2, 36),
                     },
                     FunctionParameter {
                         name: "c".to_owned(),
-                        typing: Typing::new(false, vif_objects::ast::Type::Unknown)
+                        typing: Typing::new(false, vif_objects::ast::Type::Unknown),
+                        default: None,
+                        span: Span::new(2, 39),
                     },
                 ],
                 body: vec![Stmt::Return(Return {
@@ -1107,11 +2552,55 @@ mod tests {
                         Span::new(3, 26)
                     ))
                 })],
-                typing: Typing::new(false, vif_objects::ast::Type::Float)
+                typing: Typing::new(false, vif_objects::ast::Type::Unknown),
+                declared_output: false,
+                span: Span::new(2, 27),
             })
         );
     }
 
+    #[test]
+    fn function_with_return_annotation() {
+        let string = "def my_function(a: int) -> float:\n    return 1.5\n";
+        let scanner = Scanner::new(string);
+        let mut parser = Parser::new(scanner);
+
+        let success = parser.build();
+
+        assert!(success);
+        assert_eq!(parser.ast.len(), 1);
+
+        let Stmt::Function(function) = &parser.ast[0] else {
+            panic!("Expected a function, got {}", parser.ast[0]);
+        };
+        match &function.typing.r#type {
+            vif_objects::ast::Type::Callable(c) => {
+                assert_eq!(c.output.r#type, vif_objects::ast::Type::Float);
+                assert_eq!(c.names, vec!["a".to_owned()]);
+            }
+            t => panic!("Expected a callable, got {t}"),
+        }
+    }
+
+    #[test]
+    fn parameter_without_default_after_defaults() {
+        let string = "def f(a: int = 1, b: int) -> int:\n    return a + b\nvar c = 1\n";
+        let scanner = Scanner::new(string);
+        let mut parser = Parser::new(scanner);
+
+        let success = parser.build();
+
+        assert!(!success);
+        assert_eq!(parser.ast.len(), 1);
+        assert!(matches!(parser.ast[0], Stmt::Var(_)));
+
+        let errors = parser.get_errors();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0]
+            .format(string)
+            .ends_with("Parameter b without default follows parameters with defaults"));
+    }
+
     #[test]
     fn if_statement() {
         let string = "if True:\n    return \"coucou\"\n";
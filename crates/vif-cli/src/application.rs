@@ -1,4 +1,6 @@
-use std::fs;
+use crate::modules::load_program;
+use crate::modules::read_file;
+use crate::modules::Program;
 use std::io;
 use std::io::Write;
 use std::path::PathBuf;
@@ -41,30 +43,26 @@ impl Vif {
     }
 
     fn get_llvm_ir(&self, path: &PathBuf) -> Result<String, String> {
-        self.get_ast(&path)
-            .and_then(|ast| get_llvm_ir(&ast).map_err(|e| format!("{e}")))
+        self.build_ast(path).and_then(|(ast, content)| {
+            get_llvm_ir(&ast, content.as_str()).map_err(|e| format!("{e}"))
+        })
     }
 
     fn get_ast(&self, path: &PathBuf) -> Result<Function, String> {
-        self.read_file(&path)
-            .and_then(|content| self.build_ast(content.as_str()))
+        let content = read_file(path)?;
+        build_ast(content.as_str()).map_err(|errors| errors[0].format(content.as_str()))
     }
 
     fn execute_file(&self, path: &PathBuf) -> Result<(), String> {
-        self.get_ast(&path)
-            .and_then(|ast| compile_and_execute(&ast).map_err(|e| format!("{e}")))
+        self.build_ast(path).and_then(|(ast, content)| {
+            compile_and_execute(&ast, content.as_str()).map_err(|e| format!("{e}"))
+        })
     }
 
     fn build_binary(&self, path: &PathBuf) -> Result<(), String> {
-        self.get_ast(&path)
-            .and_then(|ast| compile_and_build_binary(&ast).map_err(|e| format!("{e}")))
-    }
-
-    fn read_file(&self, path: &PathBuf) -> Result<String, String> {
-        fs::read_to_string(&path).or(Err(format!(
-            "Could not read file {}",
-            path.to_string_lossy()
-        )))
+        self.build_ast(path).and_then(|(ast, content)| {
+            compile_and_build_binary(&ast, content.as_str()).map_err(|e| format!("{e}"))
+        })
     }
 
     fn run_prompt(&mut self) {
@@ -80,15 +78,13 @@ impl Vif {
         }
     }
 
-    fn build_ast(&self, content: &str) -> Result<Function, String> {
-        let mut ast = match build_ast(content) {
-            Ok(ast) => ast,
-            Err(errors) => return Err(errors[0].format(content)),
-        };
+    // returns the typed program along with its sources
+    fn build_ast(&self, path: &PathBuf) -> Result<(Function, String), String> {
+        let Program { mut ast, content } = load_program(path, &CONFIG.search_path)?;
 
         match run_typing_checks(&mut ast) {
-            Err(err) => Err(err.format(content)),
-            _ => Ok(ast),
+            Err(err) => Err(err.format(content.as_str())),
+            _ => Ok((ast, content)),
         }
     }
 }
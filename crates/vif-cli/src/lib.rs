@@ -1,4 +1,5 @@
 mod application;
+mod modules;
 
 use vif_loader::setup_logging;
 
@@ -8,6 +9,9 @@ pub fn run_cli() {
     let mut vif = application::Vif::init();
     match vif.run() {
         Ok(_) => (),
-        Err(e) => println!("{e}"),
+        Err(e) => {
+            println!("{e}");
+            std::process::exit(1);
+        }
     }
 }
@@ -0,0 +1,321 @@
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use vif_ast::build_ast;
+use vif_ast::get_module_names;
+use vif_ast::Namespace;
+use vif_objects::ast::Function;
+use vif_objects::ast::Import;
+use vif_objects::ast::Stmt;
+
+// The entrypoint merged with every module it imports. The program sources
+// are the ones of each module, in the order the modules are run
+pub struct Program {
+    pub ast: Function,
+    pub content: String,
+}
+
+struct Module {
+    // None for the entrypoint, whose names are not namespaced
+    name: Option<String>,
+    path: PathBuf,
+    content: String,
+    ast: Function,
+    names: Vec<String>,
+}
+
+struct Loader<'a> {
+    directory: PathBuf,
+    search_path: &'a [PathBuf],
+    // imported modules come before the ones importing them
+    modules: Vec<Module>,
+    // modules being loaded, to detect circular imports
+    stack: Vec<String>,
+}
+
+pub fn load_program(path: &Path, search_path: &[PathBuf]) -> Result<Program, String> {
+    let mut loader = Loader {
+        directory: path.parent().map(Path::to_path_buf).unwrap_or_default(),
+        search_path,
+        modules: Vec::new(),
+        stack: Vec::new(),
+    };
+
+    let name = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    loader.load(name, path, true)?;
+    loader.link()
+}
+
+// the sources of the modules are merged, so errors tell which file they come from
+fn in_file(path: &Path, error: String) -> String {
+    format!("In {}\n{error}", path.to_string_lossy())
+}
+
+pub fn read_file(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).or(Err(format!(
+        "Could not read file {}",
+        path.to_string_lossy()
+    )))
+}
+
+fn get_imports(ast: &Function) -> impl Iterator<Item = &Import> {
+    ast.body.iter().filter_map(|stmt| match stmt {
+        Stmt::Import(i) => Some(i),
+        _ => None,
+    })
+}
+
+impl<'a> Loader<'a> {
+    fn load(&mut self, name: String, path: &Path, entrypoint: bool) -> Result<(), String> {
+        let content = read_file(path)?;
+        let ast = match build_ast(content.as_str()) {
+            Ok(ast) => ast,
+            Err(errors) => return Err(in_file(path, errors[0].format(content.as_str()))),
+        };
+
+        self.stack.push(name.clone());
+        for import in get_imports(&ast) {
+            self.import(import, path, content.as_str())?;
+        }
+        self.stack.pop();
+
+        self.modules.push(Module {
+            names: get_module_names(&ast.body),
+            name: (!entrypoint).then_some(name),
+            path: path.to_path_buf(),
+            content,
+            ast,
+        });
+
+        Ok(())
+    }
+
+    fn import(&mut self, import: &Import, path: &Path, content: &str) -> Result<(), String> {
+        if self.stack.contains(&import.module) {
+            let cycle = self
+                .stack
+                .iter()
+                .chain(std::iter::once(&import.module))
+                .cloned()
+                .collect::<Vec<String>>();
+            return Err(in_file(
+                path,
+                import.span.format(
+                    content,
+                    format!("Circular import: {}", cycle.join(" -> ")).as_str(),
+                ),
+            ));
+        }
+
+        if self.get_module(&import.module).is_none() {
+            let module_path = self.resolve(&import.module).ok_or_else(|| {
+                in_file(
+                    path,
+                    import.span.format(
+                        content,
+                        format!("Could not find module {}", import.module).as_str(),
+                    ),
+                )
+            })?;
+            self.load(import.module.clone(), &module_path, false)?;
+        }
+
+        let module = self.get_module(&import.module).unwrap();
+        match import.names.iter().find(|n| !module.names.contains(n)) {
+            Some(name) => Err(in_file(
+                path,
+                import.span.format(
+                    content,
+                    format!("Cannot import {name} from module {}", import.module).as_str(),
+                ),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    // modules are looked for next to the entrypoint, then in the search path
+    fn resolve(&self, module: &str) -> Option<PathBuf> {
+        std::iter::once(&self.directory)
+            .chain(self.search_path.iter())
+            .map(|directory| directory.join(format!("{module}.vif")))
+            .find(|path| path.is_file())
+    }
+
+    fn get_module(&self, name: &str) -> Option<&Module> {
+        self.modules
+            .iter()
+            .find(|m| m.name.as_deref() == Some(name))
+    }
+
+    fn get_namespace(&self, module: &Module, offset: usize) -> Namespace {
+        let mut namespace = Namespace::new(offset);
+
+        for import in get_imports(&module.ast) {
+            let names = &self.get_module(&import.module).unwrap().names;
+            if import.names.is_empty() {
+                namespace.add_module(import.module.clone(), names.clone());
+            }
+            for name in import.names.iter() {
+                namespace.add_name(name.clone(), format!("{}.{name}", import.module));
+            }
+        }
+
+        for name in module.names.iter() {
+            match &module.name {
+                Some(prefix) => namespace.add_name(name.clone(), format!("{prefix}.{name}")),
+                None => namespace.remove_name(name),
+            }
+        }
+
+        namespace
+    }
+
+    fn link(self) -> Result<Program, String> {
+        let content = self
+            .modules
+            .iter()
+            .map(|m| m.content.as_str())
+            .collect::<Vec<&str>>()
+            .join("\n");
+
+        let mut offset = 0;
+        let mut namespaces = Vec::new();
+        for module in self.modules.iter() {
+            namespaces.push(self.get_namespace(module, offset));
+            offset += module.content.split('\n').count();
+        }
+
+        let mut body = Vec::new();
+        let mut ast = None;
+        for (mut module, namespace) in self.modules.into_iter().zip(namespaces) {
+            namespace
+                .apply(&mut module.ast.body)
+                .map_err(|e| in_file(&module.path, e.format(content.as_str())))?;
+
+            body.append(&mut module.ast.body);
+            ast = Some(module.ast);
+        }
+
+        // the entrypoint is the last module
+        let mut ast = ast.unwrap();
+        ast.body = body;
+
+        Ok(Program { ast, content })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::load_program;
+    use std::fs;
+    use std::path::PathBuf;
+
+    // writes the modules in a directory of their own, the first one being the entrypoint
+    fn write_modules(test: &str, modules: &[(&str, &str)]) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("vif-{test}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        for (name, content) in modules {
+            fs::write(directory.join(format!("{name}.vif")), content).unwrap();
+        }
+        directory.join(format!("{}.vif", modules[0].0))
+    }
+
+    fn load_error(path: &PathBuf) -> String {
+        match load_program(path, &[]) {
+            Ok(_) => panic!("Expected {} to fail loading", path.to_string_lossy()),
+            Err(e) => e,
+        }
+    }
+
+    #[test]
+    fn circular_import() {
+        let path = write_modules(
+            "circular",
+            &[
+                ("main", "import a\n"),
+                ("a", "import b\nvar x = 1\n"),
+                ("b", "import a\nvar y = 1\n"),
+            ],
+        );
+
+        assert_eq!(
+            load_error(&path),
+            format!(
+                "In {}\nLine 1 - import a\nCircular import: main -> a -> b -> a",
+                path.with_file_name("b.vif").to_string_lossy()
+            )
+        );
+    }
+
+    #[test]
+    fn missing_module() {
+        let path = write_modules("missing", &[("main", "import nowhere\n")]);
+
+        assert_eq!(
+            load_error(&path),
+            format!(
+                "In {}\nLine 1 - import nowhere\nCould not find module nowhere",
+                path.to_string_lossy()
+            )
+        );
+    }
+
+    #[test]
+    fn missing_imported_name() {
+        let path = write_modules(
+            "missing-name",
+            &[("main", "from m import x\n"), ("m", "var y = 1\n")],
+        );
+
+        assert_eq!(
+            load_error(&path),
+            format!(
+                "In {}\nLine 1 - from m import x\nCannot import x from module m",
+                path.to_string_lossy()
+            )
+        );
+    }
+
+    #[test]
+    fn link_error_names_its_module() {
+        let path = write_modules(
+            "link",
+            &[
+                ("main", "import m\n\nvar z = m.x\n"),
+                ("m", "import n\n\nvar x = n.y\n"),
+                ("n", "var x = 1\n"),
+            ],
+        );
+
+        assert_eq!(
+            load_error(&path),
+            format!(
+                "In {}\nLine 3 - var x = n.y\nModule n has no attribute y",
+                path.with_file_name("m.vif").to_string_lossy()
+            )
+        );
+    }
+
+    #[test]
+    fn modules_are_merged_before_their_importers() {
+        let path = write_modules(
+            "merged",
+            &[
+                ("main", "from m import x\n\nvar z = x\n"),
+                ("m", "var x = 1\n"),
+            ],
+        );
+
+        let program = load_program(&path, &[]).unwrap();
+        assert_eq!(
+            program.content,
+            "var x = 1\n\nfrom m import x\n\nvar z = x\n"
+        );
+        assert_eq!(format!("{}", program.ast.body[0]), "m.x=Value[1]");
+    }
+}
@@ -1,23 +1,33 @@
 #[test]
 fn vif_tests() {
-    use vif_ast::build_ast;
-    use vif_compiler::compile;
-    use vif_typing::run_typing_checks;
     let test_folders = std::env::current_dir()
         .unwrap()
         .canonicalize()
         .unwrap()
         .join("../../tests");
 
-    for file in std::fs::read_dir(test_folders).unwrap() {
-        println!("{:?}", file);
-        let content = std::fs::read_to_string(file.unwrap().path()).unwrap();
+    // modules are imported by their own entry point, which goes last
+    let mut paths = std::fs::read_dir(&test_folders)
+        .unwrap()
+        .map(|file| file.unwrap().path())
+        .filter(|path| path.is_file())
+        .collect::<Vec<std::path::PathBuf>>();
+    paths.push(test_folders.join("imports/main.vif"));
+
+    for path in paths {
+        println!("{:?}", path);
 
-        let mut ast = build_ast(content.as_str()).unwrap();
-        run_typing_checks(&mut ast).unwrap();
-        let (f, g) = compile(&ast).unwrap();
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_vif-cli"))
+            .arg("run")
+            .arg(&path)
+            .output()
+            .unwrap();
 
-        let result = vif_vm::interpret(f, g, content.as_str());
-        assert!(result.is_ok(), "{}", result.unwrap_err());
+        assert!(
+            output.status.success(),
+            "{:?} failed:\n{}",
+            path,
+            String::from_utf8_lossy(&output.stdout)
+        );
     }
 }
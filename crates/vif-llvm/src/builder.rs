@@ -1,9 +1,12 @@
 use crate::error::CompilerError;
 use inkwell::basic_block::BasicBlock;
-use inkwell::module::Module;
-use inkwell::types::{BasicMetadataTypeEnum, BasicType, PointerType};
+use inkwell::module::{Linkage, Module};
+use inkwell::types::{
+    BasicMetadataTypeEnum, BasicType, BasicTypeEnum, FunctionType, PointerType, StructType,
+};
 use inkwell::values::{
-    BasicMetadataValueEnum, BasicValue, BasicValueEnum, FunctionValue, PointerValue,
+    BasicMetadataValueEnum, BasicValue, BasicValueEnum, CallSiteValue, FloatValue, FunctionValue,
+    IntValue, PointerValue,
 };
 use inkwell::AddressSpace;
 use vif_objects::ast::{self, Typing};
@@ -28,6 +31,9 @@ impl<'ctx> VariablePointer<'ctx> {
 pub struct FunctionPointer<'ctx> {
     ptr: FunctionValue<'ctx>,
     typing: Typing,
+    // user functions receive their environment as first parameter. When used as values,
+    // they are passed around as this closure, which has no environment
+    closure: Option<PointerValue<'ctx>>,
 }
 
 impl<'ctx> FunctionPointer<'ctx> {
@@ -35,9 +41,21 @@ impl<'ctx> FunctionPointer<'ctx> {
         self.ptr
             .get_params()
             .iter()
+            .skip(self.closure.is_some() as usize)
             .map(|p| p.into_pointer_value())
             .collect()
     }
+
+    pub fn get_environment(&self) -> Option<PointerValue<'ctx>> {
+        self.closure
+            .and_then(|_| self.ptr.get_first_param())
+            .map(|p| p.into_pointer_value())
+    }
+
+    fn as_pointer(&self) -> PointerValue<'ctx> {
+        self.closure
+            .unwrap_or_else(|| self.ptr.as_global_value().as_pointer_value())
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -78,6 +96,7 @@ impl<'ctx> LLVMValue<'ctx> {
         LLVMValue::Function(FunctionPointer {
             ptr: function,
             typing,
+            closure: None,
         })
     }
 
@@ -120,7 +139,7 @@ impl<'ctx> LLVMValue<'ctx> {
         match self {
             Self::RawValue(_) => unreachable!(),
             Self::Variable(v) => v.ptr,
-            Self::Function(f) => f.ptr.as_global_value().as_pointer_value(),
+            Self::Function(f) => f.as_pointer(),
         }
     }
 
@@ -136,9 +155,7 @@ impl<'ctx> LLVMValue<'ctx> {
         match self {
             Self::RawValue(r) => BasicValueEnum::from(r.value),
             Self::Variable(v) => BasicValueEnum::PointerValue(v.ptr),
-            Self::Function(f) => {
-                BasicValueEnum::PointerValue(f.ptr.as_global_value().as_pointer_value())
-            }
+            Self::Function(f) => BasicValueEnum::PointerValue(f.as_pointer()),
         }
     }
 
@@ -181,23 +198,15 @@ impl<'ctx> Builder<'ctx> {
                 .as_basic_type_enum(),
             ast::Type::Bool => self.context.bool_type().as_basic_type_enum(),
             ast::Type::None => self.context.bool_type().as_basic_type_enum(),
-            ast::Type::Callable(c) => self.get_pointer(&c.output),
-            ast::Type::Unknown => panic!("cannot convert unknown to llvm type"),
-            ast::Type::KeyWord => panic!("cannot convert keyword to llvm type"),
-        }
-    }
-
-    fn get_pointer(&self, typing: &ast::Typing) -> inkwell::types::BasicTypeEnum<'ctx> {
-        match &typing.r#type {
-            ast::Type::Int => self.context.i64_type().as_basic_type_enum(),
-            ast::Type::Float => self.context.f64_type().as_basic_type_enum(),
-            ast::Type::String => self
+            // functions are passed around as pointers
+            ast::Type::Callable(_)
+            | ast::Type::List(_)
+            | ast::Type::Dict(_, _)
+            | ast::Type::Tuple(_)
+            | ast::Type::Object(_) => self
                 .context
                 .ptr_type(AddressSpace::default())
                 .as_basic_type_enum(),
-            ast::Type::Bool => self.context.bool_type().as_basic_type_enum(),
-            ast::Type::None => self.context.bool_type().as_basic_type_enum(),
-            ast::Type::Callable(c) => self.get_pointer(&c.output),
             ast::Type::Unknown => panic!("cannot convert unknown to llvm type"),
             ast::Type::KeyWord => panic!("cannot convert keyword to llvm type"),
         }
@@ -230,45 +239,103 @@ impl<'ctx> Builder<'ctx> {
         token: &ast::Variable,
         value: LLVMValue<'ctx>,
     ) -> Result<LLVMValue<'ctx>, CompilerError> {
-        let v = match value {
-            LLVMValue::RawValue(v) => v.value,
-            LLVMValue::Variable(v) => v.ptr.as_basic_value_enum(),
-            LLVMValue::Function(f) => f
-                .ptr
-                .as_global_value()
-                .as_pointer_value()
-                .as_basic_value_enum(),
-        };
-        self.allocate_and_store_value(v, token.name.as_str(), token.typing.clone())
+        match value {
+            LLVMValue::RawValue(v) => {
+                self.allocate_and_store_value(v.value, token.name.as_str(), token.typing.clone())
+            }
+            // variables are shared by reference
+            LLVMValue::Variable(v) => Ok(LLVMValue::new_variable(v.ptr, token.typing.clone())),
+            LLVMValue::Function(f) => self.allocate_and_store_value(
+                f.as_pointer().into(),
+                token.name.as_str(),
+                token.typing.clone(),
+            ),
+        }
     }
 
-    pub fn allocate(&self, value: LLVMValue<'ctx>) -> Result<PointerValue<'ctx>, CompilerError> {
-        let v = value.get_basic_value_enum();
-        if v.is_pointer_value() {
-            return Ok(v.into_pointer_value());
+    // numbers widen to the declared type of their variable, e.g an int to a float
+    pub fn convert(
+        &self,
+        value: LLVMValue<'ctx>,
+        typing: &Typing,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let value_type = value.get_typing().r#type;
+        match (&typing.r#type, &value_type) {
+            (ast::Type::Float, ast::Type::Int | ast::Type::Bool)
+            | (ast::Type::Int, ast::Type::Bool) => {
+                let number = self.load_llvm_value("", &value)?;
+                Ok(LLVMValue::new_value(
+                    self.cast_number(number, &typing.r#type)?,
+                    Typing::new(true, typing.r#type.clone()),
+                ))
+            }
+            _ => Ok(value),
         }
+    }
 
+    pub fn allocate(
+        &self,
+        value: BasicValueEnum<'ctx>,
+    ) -> Result<PointerValue<'ctx>, CompilerError> {
         self.builder
-            .build_alloca(v.get_type(), "")
+            .build_alloca(value.get_type(), "")
             .map_err(|e| CompilerError::LLVM(format!("{e}")))
     }
 
+    // a variable whose value is only stored later on, e.g a loop variable
+    pub fn allocate_variable(
+        &self,
+        name: &str,
+        typing: Typing,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let ptr = self
+            .builder
+            .build_alloca(self.get_llvm_type(&typing), name)
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        Ok(LLVMValue::new_variable(ptr, typing))
+    }
+
     pub fn allocate_and_store_value(
         &self,
         value: BasicValueEnum<'ctx>,
         name: &str,
         typing: Typing,
     ) -> Result<LLVMValue<'ctx>, CompilerError> {
-        let ptr = if let BasicValueEnum::PointerValue(p) = value {
-            p
-        } else {
-            let ptr = self
-                .builder
-                .build_alloca(value.get_type(), name)
-                .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
-            self.store_value(ptr, value)?;
-            ptr
-        };
+        let ptr = self
+            .builder
+            .build_alloca(value.get_type(), name)
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        self.store_value(ptr, value)?;
+
+        Ok(LLVMValue::new_variable(ptr, typing))
+    }
+
+    // unlike allocate_and_store_value, the value outlives the function storing it
+    pub fn malloc_and_store_value(
+        &self,
+        value: BasicValueEnum<'ctx>,
+        typing: Typing,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let ptr = self
+            .builder
+            .build_malloc(value.get_type(), "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        self.store_value(ptr, value)?;
+
+        Ok(LLVMValue::new_variable(ptr, typing))
+    }
+
+    // a variable captured by a closure, which may outlive the function declaring it
+    pub fn malloc_variable(
+        &self,
+        name: &str,
+        typing: Typing,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let ptr = self
+            .builder
+            .build_malloc(self.get_llvm_type(&typing), name)
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
 
         Ok(LLVMValue::new_variable(ptr, typing))
     }
@@ -289,33 +356,138 @@ impl<'ctx> Builder<'ctx> {
         self.context.ptr_type(AddressSpace::default())
     }
 
-    fn declare_function(&self, function: &ast::Function, module: &Module<'ctx>) -> LLVMValue<'ctx> {
-        let function_ptr_type = if function.typing.return_as_pointer().unwrap() {
-            self.get_new_ptr().as_basic_type_enum()
-        } else {
-            self.get_llvm_type(&function.typing)
+    fn function_type(&self, typing: &Typing, environment: bool) -> FunctionType<'ctx> {
+        let callable = match &typing.r#type {
+            ast::Type::Callable(c) => c,
+            t => unreachable!("Not a function type: {}", t),
         };
 
-        let args = function
-            .params
-            .iter()
-            .map(|_| self.context.ptr_type(AddressSpace::default()).into())
+        let environment = environment.then(|| self.get_new_ptr().into());
+        let params = environment
+            .into_iter()
+            .chain(
+                callable
+                    .signature
+                    .get_params()
+                    .into_iter()
+                    .flatten()
+                    .map(|_| self.get_new_ptr().into()),
+            )
             .collect::<Vec<BasicMetadataTypeEnum>>();
 
-        let llvm_function = function_ptr_type.fn_type(&args, false);
+        if callable.return_pointer {
+            self.get_new_ptr().fn_type(&params, false)
+        } else {
+            self.get_llvm_type(&callable.output).fn_type(&params, false)
+        }
+    }
+
+    // main is run as is, without any environment
+    fn declare_function(
+        &self,
+        function: &ast::Function,
+        module: &Module<'ctx>,
+        is_main: bool,
+    ) -> LLVMValue<'ctx> {
+        let llvm_function = self.function_type(&function.typing, !is_main);
+        let ptr = module.add_function(function.name.as_str(), llvm_function, None);
+
+        let closure = (!is_main).then(|| {
+            let closure = module.add_global(
+                self.closure_type(),
+                None,
+                format!("{}.closure", function.name).as_str(),
+            );
+            closure.set_constant(true);
+            closure.set_initializer(&self.closure_type().const_named_struct(&[
+                ptr.as_global_value().as_pointer_value().into(),
+                self.get_new_ptr().const_null().into(),
+            ]));
+            closure.as_pointer_value()
+        });
+
+        LLVMValue::Function(FunctionPointer {
+            ptr,
+            typing: function.typing.clone(),
+            closure,
+        })
+    }
 
-        LLVMValue::new_function(
-            module.add_function(function.name.as_str(), llvm_function, None),
-            function.typing.clone(),
+    // a function value is a pointer to a { function, environment } closure
+    fn closure_type(&self) -> StructType<'ctx> {
+        self.context.struct_type(
+            &[self.get_new_ptr().into(), self.get_new_ptr().into()],
+            false,
         )
     }
 
+    // the environment holds the pointers to the variables captured by a closure
+    fn environment_type(&self, size: usize) -> StructType<'ctx> {
+        self.context
+            .struct_type(&vec![self.get_new_ptr().into(); size], false)
+    }
+
+    pub fn new_closure(
+        &self,
+        function: &LLVMValue<'ctx>,
+        captured: &[PointerValue<'ctx>],
+    ) -> Result<PointerValue<'ctx>, CompilerError> {
+        let environment_type = self.environment_type(captured.len());
+        let environment = self
+            .builder
+            .build_malloc(environment_type, "environment")
+            .map_err(|e| CompilerError::LLVM(format!("Could not allocate environment: {e}")))?;
+        for (i, variable) in captured.iter().enumerate() {
+            self.store_value(
+                self.struct_field(environment_type, environment, i as u32)?,
+                variable.as_basic_value_enum(),
+            )?;
+        }
+
+        let closure = self
+            .builder
+            .build_malloc(self.closure_type(), "closure")
+            .map_err(|e| CompilerError::LLVM(format!("Could not allocate closure: {e}")))?;
+        let function = function.get_function_value().ptr.as_global_value();
+        self.store_value(
+            self.struct_field(self.closure_type(), closure, 0)?,
+            function.as_pointer_value().as_basic_value_enum(),
+        )?;
+        self.store_value(
+            self.struct_field(self.closure_type(), closure, 1)?,
+            environment.as_basic_value_enum(),
+        )?;
+
+        Ok(closure)
+    }
+
+    pub fn get_captured_variables(
+        &self,
+        environment: PointerValue<'ctx>,
+        size: usize,
+    ) -> Result<Vec<PointerValue<'ctx>>, CompilerError> {
+        (0..size)
+            .map(|i| {
+                self.load_struct_field(self.environment_type(size), environment, i as u32)
+                    .map(|v| v.into_pointer_value())
+            })
+            .collect()
+    }
+
+    pub fn declare_main_function(
+        &self,
+        function: &ast::Function,
+        module: &Module<'ctx>,
+    ) -> LLVMValue<'ctx> {
+        self.declare_function(function, module, true)
+    }
+
     pub fn declare_user_function(
         &self,
         function: &ast::Function,
         module: &Module<'ctx>,
     ) -> LLVMValue<'ctx> {
-        self.declare_function(function, module)
+        self.declare_function(function, module, false)
     }
 
     pub fn create_function_block(
@@ -344,6 +516,14 @@ impl<'ctx> Builder<'ctx> {
     }
 
     pub fn goto_block(&self, block: BasicBlock) -> Result<(), CompilerError> {
+        // the block might already be closed by a return, break or continue
+        if self
+            .get_current_block()
+            .is_some_and(|b| b.get_terminator().is_some())
+        {
+            return Ok(());
+        }
+
         self.builder
             .build_unconditional_branch(block)
             .map_err(|_| CompilerError::LLVM("Cannot go to block".to_owned()))?;
@@ -352,6 +532,50 @@ impl<'ctx> Builder<'ctx> {
     }
 
     pub fn is_truthy(&self, value: LLVMValue<'ctx>) -> Result<LLVMValue<'ctx>, CompilerError> {
+        // functions are always truthy
+        if let ast::Type::Callable(_) = value.get_typing().r#type {
+            return Ok(LLVMValue::new_value(
+                self.value_bool(true),
+                Typing::new(true, ast::Type::Bool),
+            ));
+        }
+
+        // collections are truthy when they are not empty
+        let length = match value.get_typing().r#type.get_concrete_type() {
+            ast::Type::List(_) => {
+                Some(self.list_length(self.load_llvm_value("", &value)?.into_pointer_value())?)
+            }
+            ast::Type::Dict(_, _) => {
+                Some(self.dict_length(self.load_llvm_value("", &value)?.into_pointer_value())?)
+            }
+            ast::Type::Tuple(t) => Some(self.context.i64_type().const_int(t.len() as u64, false)),
+            // objects are always truthy
+            ast::Type::Object(_) => {
+                return Ok(LLVMValue::new_value(
+                    self.value_bool(true),
+                    Typing::new(true, ast::Type::Bool),
+                ))
+            }
+            _ => None,
+        };
+
+        if let Some(length) = length {
+            let cmp = self
+                .builder
+                .build_int_compare(
+                    inkwell::IntPredicate::NE,
+                    length,
+                    length.get_type().const_zero(),
+                    "",
+                )
+                .map_err(|e| CompilerError::LLVM(format!("Could not check collection: {e}")))?;
+
+            return Ok(LLVMValue::new_value(
+                cmp.as_basic_value_enum(),
+                Typing::new(true, ast::Type::Bool),
+            ));
+        }
+
         let value = match &value {
             LLVMValue::RawValue(v) => v.value.as_basic_value_enum(),
             LLVMValue::Variable(v) => self.load_variable("", &v)?,
@@ -366,12 +590,7 @@ impl<'ctx> Builder<'ctx> {
         let cmp = match value {
             BasicValueEnum::IntValue(i) => self
                 .builder
-                .build_int_compare(
-                    inkwell::IntPredicate::NE,
-                    i,
-                    self.context.bool_type().const_int(0, false),
-                    "",
-                )
+                .build_int_compare(inkwell::IntPredicate::NE, i, i.get_type().const_zero(), "")
                 .map_err(|e| {
                     CompilerError::LLVM(format!("Could not compare ints {} and 0: {e}", value))
                 })?,
@@ -387,7 +606,25 @@ impl<'ctx> Builder<'ctx> {
                     CompilerError::LLVM(format!("Could not convert float {} to int: {e}", f))
                 })?,
 
-            //TODO: empty string ????
+            // strings are truthy when they are not empty
+            BasicValueEnum::PointerValue(p) => {
+                let first_char = self
+                    .builder
+                    .build_load(self.context.i8_type(), p, "")
+                    .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+                    .into_int_value();
+
+                self.builder
+                    .build_int_compare(
+                        inkwell::IntPredicate::NE,
+                        first_char,
+                        self.context.i8_type().const_zero(),
+                        "",
+                    )
+                    .map_err(|e| {
+                        CompilerError::LLVM(format!("Could not check string {}: {e}", p))
+                    })?
+            }
             _ => unreachable!(),
         };
 
@@ -431,21 +668,19 @@ impl<'ctx> Builder<'ctx> {
         };
 
         if value.is_int_value() {
-            Ok(LLVMValue::new_value(
-                self.builder
-                    .build_int_neg(value.into_int_value(), "")
-                    .map_err(|e| CompilerError::LLVM(format!("{e}")))?
-                    .as_basic_value_enum(),
-                expression.get_typing(),
-            ))
+            let result = self
+                .builder
+                .build_int_neg(self.to_int(value)?.into_int_value(), "")
+                .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+                .as_basic_value_enum();
+            Ok(LLVMValue::new_value(result, self.number_typing(&result)))
         } else if value.is_float_value() {
-            Ok(LLVMValue::new_value(
-                self.builder
-                    .build_float_neg(value.into_float_value(), "")
-                    .map_err(|e| CompilerError::LLVM(format!("{e}")))?
-                    .as_basic_value_enum(),
-                expression.get_typing(),
-            ))
+            let result = self
+                .builder
+                .build_float_neg(value.into_float_value(), "")
+                .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+                .as_basic_value_enum();
+            Ok(LLVMValue::new_value(result, self.number_typing(&result)))
         } else {
             unimplemented!()
         }
@@ -474,6 +709,86 @@ impl<'ctx> Builder<'ctx> {
         Ok(())
     }
 
+    // prints where the error was raised, then `name: message` or only its name
+    pub fn exit_with_error(
+        &self,
+        printf: FunctionValue<'ctx>,
+        exit: FunctionValue<'ctx>,
+        strlen: FunctionValue<'ctx>,
+        location: BasicValueEnum<'ctx>,
+        name: BasicValueEnum<'ctx>,
+        message: BasicValueEnum<'ctx>,
+    ) -> Result<(), CompilerError> {
+        let length = self.strlen(strlen, message.into_pointer_value())?;
+        let is_empty = self
+            .builder
+            .build_int_compare(
+                inkwell::IntPredicate::EQ,
+                length,
+                self.context.i64_type().const_zero(),
+                "",
+            )
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        let separator = self
+            .builder
+            .build_select(
+                is_empty,
+                self.global_string("no_separator", "")?.as_pointer_value(),
+                self.global_string("separator", ": ")?.as_pointer_value(),
+                "",
+            )
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        let format = self.global_string("error_format", "%s%s%s%s\n")?;
+
+        self.builder
+            .build_direct_call(
+                printf,
+                &[
+                    format.as_pointer_value().into(),
+                    location.into(),
+                    name.into(),
+                    separator.into(),
+                    message.into(),
+                ],
+                "",
+            )
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        self.builder
+            .build_direct_call(
+                exit,
+                &[BasicMetadataValueEnum::IntValue(
+                    self.context.i32_type().const_int(1, false),
+                )],
+                "",
+            )
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        self.builder
+            .build_unreachable()
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        Ok(())
+    }
+
+    pub fn switch(
+        &self,
+        value: IntValue<'ctx>,
+        default: BasicBlock<'ctx>,
+        cases: &[(u64, BasicBlock<'ctx>)],
+    ) -> Result<(), CompilerError> {
+        let cases = cases
+            .iter()
+            .map(|(case, block)| (value.get_type().const_int(*case, false), *block))
+            .collect::<Vec<(IntValue<'ctx>, BasicBlock<'ctx>)>>();
+
+        self.builder
+            .build_switch(value, default, &cases)
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        Ok(())
+    }
+
     pub fn get_current_block(&self) -> Option<inkwell::basic_block::BasicBlock<'ctx>> {
         self.builder.get_insert_block()
     }
@@ -517,7 +832,7 @@ impl<'ctx> Builder<'ctx> {
         match value {
             LLVMValue::RawValue(r) => Ok(r.value.clone()),
             LLVMValue::Variable(var) => self.load_variable(name, var),
-            v => unreachable!("Not a variable: {v}"),
+            LLVMValue::Function(f) => Ok(f.as_pointer().into()),
         }
     }
 
@@ -526,13 +841,73 @@ impl<'ctx> Builder<'ctx> {
         name: &str,
         var: &VariablePointer<'ctx>,
     ) -> Result<BasicValueEnum<'ctx>, CompilerError> {
-        match var.get_typing().r#type.get_concrete_type() {
-            ast::Type::String => Ok(var.ptr.as_basic_value_enum()),
-            _ => self
-                .builder
-                .build_load(self.get_llvm_type(&var.typing), var.ptr, name)
-                .map_err(|e| CompilerError::LLVM(format!("{e}"))),
+        self.builder
+            .build_load(self.get_llvm_type(var.get_typing()), var.ptr, name)
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))
+    }
+
+    pub fn load_pointer(
+        &self,
+        ptr: PointerValue<'ctx>,
+    ) -> Result<PointerValue<'ctx>, CompilerError> {
+        self.builder
+            .build_load(self.get_new_ptr(), ptr, "")
+            .map(|v| v.into_pointer_value())
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))
+    }
+
+    fn current_function(&self) -> FunctionValue<'ctx> {
+        self.builder
+            .get_insert_block()
+            .unwrap()
+            .get_parent()
+            .unwrap()
+    }
+
+    // holds what the current function returns while a finally block runs
+    pub fn allocate_return_value(&self) -> Result<PointerValue<'ctx>, CompilerError> {
+        let return_type = self
+            .current_function()
+            .get_type()
+            .get_return_type()
+            .ok_or_else(|| CompilerError::LLVM("Function does not return a value".to_owned()))?;
+
+        self.builder
+            .build_alloca(return_type, "returned")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))
+    }
+
+    pub fn load_return_value(
+        &self,
+        ptr: PointerValue<'ctx>,
+    ) -> Result<BasicValueEnum<'ctx>, CompilerError> {
+        let return_type = self
+            .current_function()
+            .get_type()
+            .get_return_type()
+            .ok_or_else(|| CompilerError::LLVM("Function does not return a value".to_owned()))?;
+
+        self.builder
+            .build_load(return_type, ptr, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))
+    }
+
+    // what a function raising an error returns is never used
+    pub fn return_default(&self) -> Result<(), CompilerError> {
+        match self.current_function().get_type().get_return_type() {
+            Some(t) => self.builder.build_return(Some(&t.const_zero())),
+            None => self.builder.build_return(None),
         }
+        .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        Ok(())
+    }
+
+    pub fn unreachable(&self) -> Result<(), CompilerError> {
+        self.builder
+            .build_unreachable()
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        Ok(())
     }
 
     pub fn return_statement(&self, value: &LLVMValue<'ctx>) -> Result<(), CompilerError> {
@@ -566,295 +941,2861 @@ impl<'ctx> Builder<'ctx> {
         Ok(())
     }
 
+    // functions are called directly, function values through their closure
     pub fn call(
+        &self,
+        function: &LLVMValue<'ctx>,
+        args: &[BasicMetadataValueEnum<'ctx>],
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        match function {
+            LLVMValue::Function(f) => self.direct_call(f, args, f.ptr.get_name().to_str().unwrap()),
+            // e.g a parameter or a decorated function
+            value => self.indirect_call(
+                self.load_llvm_value("", value)?.into_pointer_value(),
+                &value.get_typing(),
+                args,
+                "",
+            ),
+        }
+    }
+
+    fn direct_call(
         &self,
         function: &FunctionPointer<'ctx>,
         args: &[BasicMetadataValueEnum<'ctx>],
         name: &str,
     ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let mut args = self.missing_arguments(args, &function.typing);
+        // user functions declared with `def` have no environment
+        if function.closure.is_some() {
+            args.insert(0, self.get_new_ptr().const_null().into());
+        }
         let call_result = self
             .builder
-            .build_direct_call(function.ptr.clone(), args, name)
+            .build_direct_call(function.ptr.clone(), &args, name)
             .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
 
-        if let Some(v) = call_result.try_as_basic_value().left() {
-            Ok(self.allocate_and_store_value(v, "", function.typing.clone())?)
-        } else {
-            self.allocate_and_store_value(
-                BasicValueEnum::IntValue(self.context.i64_type().const_int(0, false)),
-                "",
-                Typing::new(true, ast::Type::None),
-            )
-        }
+        self.call_result(call_result, &function.typing)
     }
 
-    pub fn add(
+    // calls a function only known through its closure
+    fn indirect_call(
         &self,
-        value_left: LLVMValue<'ctx>,
-        value_right: LLVMValue<'ctx>,
+        closure: PointerValue<'ctx>,
+        typing: &Typing,
+        args: &[BasicMetadataValueEnum<'ctx>],
+        name: &str,
     ) -> Result<LLVMValue<'ctx>, CompilerError> {
-        let l = self.load_llvm_value("", &value_left)?;
-        let r = self.load_llvm_value("", &value_right)?;
+        let function = self
+            .load_struct_field(self.closure_type(), closure, 0)?
+            .into_pointer_value();
+        let environment = self.load_struct_field(self.closure_type(), closure, 1)?;
 
-        let result = self
+        let mut args = self.missing_arguments(args, typing);
+        args.insert(0, environment.into());
+        let call_result = self
             .builder
-            .build_int_add(l.into_int_value(), r.into_int_value(), "")
+            .build_indirect_call(self.function_type(typing, true), function, &args, name)
             .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
 
-        Ok(LLVMValue::new_value(
-            result.as_basic_value_enum(),
-            value_left.get_typing(),
-        ))
+        self.call_result(call_result, typing)
     }
 
-    pub fn sub(
+    // arguments left out are passed as null, the function then uses their default value
+    fn missing_arguments(
         &self,
-        value_left: LLVMValue<'ctx>,
-        value_right: LLVMValue<'ctx>,
-    ) -> Result<LLVMValue<'ctx>, CompilerError> {
-        let l = self.load_llvm_value("", &value_left)?;
-        let r = self.load_llvm_value("", &value_right)?;
+        args: &[BasicMetadataValueEnum<'ctx>],
+        typing: &Typing,
+    ) -> Vec<BasicMetadataValueEnum<'ctx>> {
+        let params = match &typing.r#type {
+            ast::Type::Callable(c) => c.signature.get_params().map_or(0, |p| p.len()),
+            _ => 0,
+        };
 
-        let result = self
+        let mut args = args.to_vec();
+        while args.len() < params {
+            args.push(self.get_new_ptr().const_null().into());
+        }
+        args
+    }
+
+    pub fn is_null(&self, ptr: PointerValue<'ctx>) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let value = self
             .builder
-            .build_int_sub(l.into_int_value(), r.into_int_value(), "")
+            .build_is_null(ptr, "")
             .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
 
         Ok(LLVMValue::new_value(
-            result.as_basic_value_enum(),
-            value_left.get_typing(),
+            value.as_basic_value_enum(),
+            Typing::new(false, ast::Type::Bool),
         ))
     }
 
-    pub fn divide(
+    pub fn pointer_phi(
         &self,
-        value_left: LLVMValue<'ctx>,
-        value_right: LLVMValue<'ctx>,
-    ) -> Result<LLVMValue<'ctx>, CompilerError> {
-        let l = self.load_llvm_value("", &value_left)?;
-        let r = self.load_llvm_value("", &value_right)?;
-
-        let result = self
+        incoming: &[(PointerValue<'ctx>, BasicBlock<'ctx>)],
+    ) -> Result<PointerValue<'ctx>, CompilerError> {
+        let phi = self
             .builder
-            .build_int_signed_div(l.into_int_value(), r.into_int_value(), "")
+            .build_phi(self.get_new_ptr(), "")
             .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
 
-        Ok(LLVMValue::new_value(
-            result.as_basic_value_enum(),
-            value_left.get_typing(),
-        ))
+        for (value, block) in incoming {
+            phi.add_incoming(&[(value, *block)]);
+        }
+
+        Ok(phi.as_basic_value().into_pointer_value())
     }
 
-    pub fn multiply(
+    pub fn bool_phi(
         &self,
-        value_left: LLVMValue<'ctx>,
-        value_right: LLVMValue<'ctx>,
+        incoming: &[(IntValue<'ctx>, BasicBlock<'ctx>)],
     ) -> Result<LLVMValue<'ctx>, CompilerError> {
-        let l = self.load_llvm_value("", &value_left)?;
-        let r = self.load_llvm_value("", &value_right)?;
-
-        let result = self
+        let phi = self
             .builder
-            .build_int_mul(l.into_int_value(), r.into_int_value(), "")
+            .build_phi(self.context.bool_type(), "")
             .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
 
+        for (value, block) in incoming {
+            phi.add_incoming(&[(value, *block)]);
+        }
+
         Ok(LLVMValue::new_value(
-            result.as_basic_value_enum(),
-            value_left.get_typing(),
+            phi.as_basic_value(),
+            Typing::new(true, ast::Type::Bool),
         ))
     }
 
-    pub fn and(
+    fn call_result(
         &self,
-        value_left: LLVMValue<'ctx>,
-        value_right: LLVMValue<'ctx>,
+        call_result: CallSiteValue<'ctx>,
+        typing: &Typing,
     ) -> Result<LLVMValue<'ctx>, CompilerError> {
-        let result = self
-            .builder
-            .build_and(
-                value_left.get_basic_value_enum().into_int_value(),
-                value_right.get_basic_value_enum().into_int_value(),
+        let output = match &typing.r#type {
+            ast::Type::Callable(c) => Typing::new(typing.mutable, c.output.r#type.clone()),
+            _ => typing.clone(),
+        };
+
+        if let Some(v) = call_result.try_as_basic_value().left() {
+            if typing.return_as_pointer() == Some(true) {
+                Ok(LLVMValue::new_variable(v.into_pointer_value(), output))
+            } else {
+                self.allocate_and_store_value(v, "", output)
+            }
+        } else {
+            self.allocate_and_store_value(
+                BasicValueEnum::IntValue(self.context.i64_type().const_int(0, false)),
                 "",
+                Typing::new(true, ast::Type::None),
             )
-            .map_err(|e| CompilerError::LLVM(format!("Could not build and operator: {e}")))?;
+        }
+    }
 
-        Ok(LLVMValue::new_value(
-            result.as_basic_value_enum(),
-            Typing::new(true, ast::Type::Bool),
-        ))
+    // bring both operands to a common numeric type, following bool -> int -> float
+    fn load_numbers(
+        &self,
+        value_left: &LLVMValue<'ctx>,
+        value_right: &LLVMValue<'ctx>,
+        widen_bools: bool,
+    ) -> Result<(BasicValueEnum<'ctx>, BasicValueEnum<'ctx>), CompilerError> {
+        let l = self.load_llvm_value("", value_left)?;
+        let r = self.load_llvm_value("", value_right)?;
+
+        if l.is_float_value() || r.is_float_value() {
+            return Ok((self.to_float(l)?, self.to_float(r)?));
+        }
+
+        match (l, r) {
+            (BasicValueEnum::IntValue(i), BasicValueEnum::IntValue(j)) => {
+                let same_width = i.get_type().get_bit_width() == j.get_type().get_bit_width();
+                if same_width && !(widen_bools && i.get_type().get_bit_width() == 1) {
+                    return Ok((l, r));
+                }
+                Ok((self.to_int(l)?, self.to_int(r)?))
+            }
+            _ => Ok((l, r)),
+        }
     }
 
-    pub fn equal(
+    pub fn to_int(
+        &self,
+        value: BasicValueEnum<'ctx>,
+    ) -> Result<BasicValueEnum<'ctx>, CompilerError> {
+        match value {
+            BasicValueEnum::IntValue(i) if i.get_type().get_bit_width() == 1 => self
+                .builder
+                .build_int_z_extend(i, self.context.i64_type(), "")
+                .map(|v| v.as_basic_value_enum())
+                .map_err(|e| CompilerError::LLVM(format!("Could not convert bool to int: {e}"))),
+            v => Ok(v),
+        }
+    }
+
+    fn to_float(&self, value: BasicValueEnum<'ctx>) -> Result<BasicValueEnum<'ctx>, CompilerError> {
+        match value {
+            BasicValueEnum::IntValue(i) if i.get_type().get_bit_width() == 1 => self
+                .builder
+                .build_unsigned_int_to_float(i, self.context.f64_type(), "")
+                .map(|v| v.as_basic_value_enum())
+                .map_err(|e| CompilerError::LLVM(format!("Could not convert bool to float: {e}"))),
+            BasicValueEnum::IntValue(i) => self
+                .builder
+                .build_signed_int_to_float(i, self.context.f64_type(), "")
+                .map(|v| v.as_basic_value_enum())
+                .map_err(|e| CompilerError::LLVM(format!("Could not convert int to float: {e}"))),
+            v => Ok(v),
+        }
+    }
+
+    fn number_typing(&self, value: &BasicValueEnum<'ctx>) -> Typing {
+        match value {
+            BasicValueEnum::FloatValue(_) => Typing::new(true, ast::Type::Float),
+            BasicValueEnum::IntValue(i) if i.get_type().get_bit_width() == 1 => {
+                Typing::new(true, ast::Type::Bool)
+            }
+            _ => Typing::new(true, ast::Type::Int),
+        }
+    }
+
+    pub fn add(
         &self,
         value_left: LLVMValue<'ctx>,
         value_right: LLVMValue<'ctx>,
     ) -> Result<LLVMValue<'ctx>, CompilerError> {
-        let l = self.load_llvm_value("", &value_left)?;
-        let r = self.load_llvm_value("", &value_right)?;
-
-        let result = match (l, r) {
+        let result = match self.load_numbers(&value_left, &value_right, true)? {
             (BasicValueEnum::IntValue(i), BasicValueEnum::IntValue(j)) => self
                 .builder
-                .build_int_compare(inkwell::IntPredicate::EQ, i, j, ""),
+                .build_int_add(i, j, "")
+                .map(|v| v.as_basic_value_enum()),
             (BasicValueEnum::FloatValue(i), BasicValueEnum::FloatValue(j)) => self
                 .builder
-                .build_float_compare(inkwell::FloatPredicate::OEQ, i, j, ""),
+                .build_float_add(i, j, "")
+                .map(|v| v.as_basic_value_enum()),
             _ => unimplemented!(),
         }
         .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
 
-        Ok(LLVMValue::new_value(
-            result.as_basic_value_enum(),
-            value_left.get_typing(),
-        ))
+        Ok(LLVMValue::new_value(result, self.number_typing(&result)))
     }
 
-    pub fn greater(
+    pub fn sub(
         &self,
         value_left: LLVMValue<'ctx>,
         value_right: LLVMValue<'ctx>,
     ) -> Result<LLVMValue<'ctx>, CompilerError> {
-        let l = self.load_llvm_value("", &value_left)?;
-        let r = self.load_llvm_value("", &value_right)?;
-
-        let result = match (l, r) {
+        let result = match self.load_numbers(&value_left, &value_right, true)? {
             (BasicValueEnum::IntValue(i), BasicValueEnum::IntValue(j)) => self
                 .builder
-                .build_int_compare(inkwell::IntPredicate::SGT, i, j, ""),
+                .build_int_sub(i, j, "")
+                .map(|v| v.as_basic_value_enum()),
             (BasicValueEnum::FloatValue(i), BasicValueEnum::FloatValue(j)) => self
                 .builder
-                .build_float_compare(inkwell::FloatPredicate::OGT, i, j, ""),
+                .build_float_sub(i, j, "")
+                .map(|v| v.as_basic_value_enum()),
             _ => unimplemented!(),
         }
         .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
 
-        Ok(LLVMValue::new_value(
-            result.as_basic_value_enum(),
-            value_left.get_typing(),
-        ))
+        Ok(LLVMValue::new_value(result, self.number_typing(&result)))
     }
 
-    pub fn greater_or_equal(
+    // true division, `7 / 2` being 3.5
+    pub fn divide(
         &self,
         value_left: LLVMValue<'ctx>,
         value_right: LLVMValue<'ctx>,
     ) -> Result<LLVMValue<'ctx>, CompilerError> {
-        let l = self.load_llvm_value("", &value_left)?;
-        let r = self.load_llvm_value("", &value_right)?;
+        let (l, r) = self.load_numbers(&value_left, &value_right, true)?;
+        let result = self
+            .builder
+            .build_float_div(
+                self.to_float(l)?.into_float_value(),
+                self.to_float(r)?.into_float_value(),
+                "",
+            )
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+            .as_basic_value_enum();
 
-        let result = match (l, r) {
-            (BasicValueEnum::IntValue(i), BasicValueEnum::IntValue(j)) => self
-                .builder
-                .build_int_compare(inkwell::IntPredicate::SGE, i, j, ""),
+        Ok(LLVMValue::new_value(result, self.number_typing(&result)))
+    }
+
+    // rounds towards negative infinity, `-7 // 2` being -4
+    pub fn floor_divide(
+        &self,
+        round: FunctionValue<'ctx>,
+        value_left: LLVMValue<'ctx>,
+        value_right: LLVMValue<'ctx>,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let result = match self.load_numbers(&value_left, &value_right, true)? {
+            (BasicValueEnum::IntValue(i), BasicValueEnum::IntValue(j)) => {
+                let quotient = self
+                    .builder
+                    .build_int_signed_div(i, j, "")
+                    .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+                let remainder = self
+                    .builder
+                    .build_int_signed_rem(i, j, "")
+                    .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+                let adjust = self.signs_differ(remainder, j)?;
+                self.builder
+                    .build_int_sub(
+                        quotient,
+                        self.builder
+                            .build_int_z_extend(adjust, self.context.i64_type(), "")
+                            .map_err(|e| CompilerError::LLVM(format!("{e}")))?,
+                        "",
+                    )
+                    .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+                    .as_basic_value_enum()
+            }
+            (BasicValueEnum::FloatValue(i), BasicValueEnum::FloatValue(j)) => {
+                // same as python, dividing what is left once the remainder is removed
+                let remainder = self.float_modulo(i, j)?;
+                let quotient = self
+                    .builder
+                    .build_float_sub(i, remainder, "")
+                    .and_then(|v| self.builder.build_float_div(v, j, ""))
+                    .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+                let float_type = self.context.f64_type();
+                let is_set = self
+                    .builder
+                    .build_float_compare(
+                        inkwell::FloatPredicate::ONE,
+                        remainder,
+                        float_type.const_zero(),
+                        "",
+                    )
+                    .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+                let adjust = self
+                    .builder
+                    .build_and(is_set, self.float_signs_differ(remainder, j)?, "")
+                    .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+                let offset = self
+                    .builder
+                    .build_select(
+                        adjust,
+                        float_type.const_float(1.0),
+                        float_type.const_zero(),
+                        "",
+                    )
+                    .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+                    .into_float_value();
+                let quotient = self
+                    .builder
+                    .build_float_sub(quotient, offset, "")
+                    .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+                // the quotient is a whole number, give or take rounding errors
+                self.builder
+                    .build_direct_call(round, &[quotient.into()], "")
+                    .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+                    .try_as_basic_value()
+                    .left()
+                    .ok_or_else(|| CompilerError::LLVM("round did not return a value".to_owned()))?
+            }
+            _ => unimplemented!(),
+        };
+
+        Ok(LLVMValue::new_value(result, self.number_typing(&result)))
+    }
+
+    pub fn power(
+        &self,
+        pow: FunctionValue<'ctx>,
+        value_left: LLVMValue<'ctx>,
+        value_right: LLVMValue<'ctx>,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let result = match self.load_numbers(&value_left, &value_right, true)? {
+            (BasicValueEnum::IntValue(i), BasicValueEnum::IntValue(j)) => {
+                self.int_power(i, j)?.as_basic_value_enum()
+            }
             (BasicValueEnum::FloatValue(i), BasicValueEnum::FloatValue(j)) => self
                 .builder
-                .build_float_compare(inkwell::FloatPredicate::OGE, i, j, ""),
+                .build_direct_call(pow, &[i.into(), j.into()], "")
+                .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+                .try_as_basic_value()
+                .left()
+                .ok_or_else(|| CompilerError::LLVM("pow did not return a value".to_owned()))?,
             _ => unimplemented!(),
-        }
-        .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        };
 
-        Ok(LLVMValue::new_value(
-            result.as_basic_value_enum(),
-            value_left.get_typing(),
-        ))
+        Ok(LLVMValue::new_value(result, self.number_typing(&result)))
     }
 
-    pub fn not_equal(
+    // exponentiation by squaring, the exponent being positive
+    fn int_power(
+        &self,
+        base: IntValue<'ctx>,
+        exponent: IntValue<'ctx>,
+    ) -> Result<IntValue<'ctx>, CompilerError> {
+        let i64_type = self.context.i64_type();
+
+        let start_block = self.get_current_block().unwrap();
+        let cond_block = self.create_block("power_cond");
+        let body_block = self.create_block("power_body");
+        let end_block = self.create_block("power_end");
+        self.goto_block(cond_block)?;
+
+        self.set_position_at(cond_block);
+        let result_phi = self
+            .builder
+            .build_phi(i64_type, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        let base_phi = self
+            .builder
+            .build_phi(i64_type, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        let exponent_phi = self
+            .builder
+            .build_phi(i64_type, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        let result = result_phi.as_basic_value().into_int_value();
+        let b = base_phi.as_basic_value().into_int_value();
+        let e = exponent_phi.as_basic_value().into_int_value();
+        let is_done = self
+            .builder
+            .build_int_compare(inkwell::IntPredicate::EQ, e, i64_type.const_zero(), "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        self.builder
+            .build_conditional_branch(is_done, end_block, body_block)
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        self.set_position_at(body_block);
+        let is_odd = self
+            .builder
+            .build_int_truncate(e, self.context.bool_type(), "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        let multiplied = self
+            .builder
+            .build_int_mul(result, b, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        let next_result = self
+            .builder
+            .build_select(is_odd, multiplied, result, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+            .into_int_value();
+        let next_base = self
+            .builder
+            .build_int_mul(b, b, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        let next_exponent = self
+            .builder
+            .build_right_shift(e, i64_type.const_int(1, false), false, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        self.goto_block(cond_block)?;
+
+        result_phi.add_incoming(&[
+            (&i64_type.const_int(1, false), start_block),
+            (&next_result, body_block),
+        ]);
+        base_phi.add_incoming(&[(&base, start_block), (&next_base, body_block)]);
+        exponent_phi.add_incoming(&[(&exponent, start_block), (&next_exponent, body_block)]);
+
+        self.set_position_at(end_block);
+        Ok(result)
+    }
+
+    pub fn multiply(
         &self,
         value_left: LLVMValue<'ctx>,
         value_right: LLVMValue<'ctx>,
     ) -> Result<LLVMValue<'ctx>, CompilerError> {
-        let l = self.load_llvm_value("", &value_left)?;
-        let r = self.load_llvm_value("", &value_right)?;
-
-        let result = match (l, r) {
+        let result = match self.load_numbers(&value_left, &value_right, true)? {
             (BasicValueEnum::IntValue(i), BasicValueEnum::IntValue(j)) => self
                 .builder
-                .build_int_compare(inkwell::IntPredicate::NE, i, j, ""),
+                .build_int_mul(i, j, "")
+                .map(|v| v.as_basic_value_enum()),
             (BasicValueEnum::FloatValue(i), BasicValueEnum::FloatValue(j)) => self
                 .builder
-                .build_float_compare(inkwell::FloatPredicate::ONE, i, j, ""),
+                .build_float_mul(i, j, "")
+                .map(|v| v.as_basic_value_enum()),
             _ => unimplemented!(),
         }
         .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
 
-        Ok(LLVMValue::new_value(
-            result.as_basic_value_enum(),
-            value_left.get_typing(),
-        ))
+        Ok(LLVMValue::new_value(result, self.number_typing(&result)))
     }
 
-    pub fn less(
+    // booleans stay booleans unless combined with an integer, as in python
+    pub fn bitwise(
         &self,
+        operator: &ast::Operator,
         value_left: LLVMValue<'ctx>,
         value_right: LLVMValue<'ctx>,
     ) -> Result<LLVMValue<'ctx>, CompilerError> {
-        let l = self.load_llvm_value("", &value_left)?;
-        let r = self.load_llvm_value("", &value_right)?;
+        let (l, r) = match self.load_numbers(&value_left, &value_right, false)? {
+            (BasicValueEnum::IntValue(i), BasicValueEnum::IntValue(j)) => (i, j),
+            _ => unreachable!(),
+        };
 
-        let result = match (l, r) {
-            (BasicValueEnum::IntValue(i), BasicValueEnum::IntValue(j)) => self
-                .builder
-                .build_int_compare(inkwell::IntPredicate::SLT, i, j, ""),
-            (BasicValueEnum::FloatValue(i), BasicValueEnum::FloatValue(j)) => self
-                .builder
-                .build_float_compare(inkwell::FloatPredicate::OLT, i, j, ""),
-            _ => unimplemented!(),
+        let result = match operator {
+            ast::Operator::BitAnd => self.builder.build_and(l, r, ""),
+            ast::Operator::BitOr => self.builder.build_or(l, r, ""),
+            ast::Operator::BitXor => self.builder.build_xor(l, r, ""),
+            _ => unreachable!(),
         }
-        .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+        .as_basic_value_enum();
+
+        Ok(LLVMValue::new_value(result, self.number_typing(&result)))
+    }
+
+    // the count is known to be positive, shifting by 64 or more leaves no bit set but the sign
+    pub fn shift(
+        &self,
+        operator: &ast::Operator,
+        value_left: LLVMValue<'ctx>,
+        value_right: LLVMValue<'ctx>,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let (l, r) = match self.load_numbers(&value_left, &value_right, true)? {
+            (BasicValueEnum::IntValue(i), BasicValueEnum::IntValue(j)) => (i, j),
+            _ => unreachable!(),
+        };
+
+        let i64_type = self.context.i64_type();
+        let too_far = self
+            .builder
+            .build_int_compare(
+                inkwell::IntPredicate::UGE,
+                r,
+                i64_type.const_int(64, false),
+                "",
+            )
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        let result = match operator {
+            ast::Operator::ShiftLeft => {
+                let shifted = self
+                    .builder
+                    .build_left_shift(l, r, "")
+                    .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+                self.builder
+                    .build_select(too_far, i64_type.const_zero(), shifted, "")
+                    .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+            }
+            ast::Operator::ShiftRight => {
+                let count = self
+                    .builder
+                    .build_select(too_far, i64_type.const_int(63, false), r, "")
+                    .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+                    .into_int_value();
+                self.builder
+                    .build_right_shift(l, count, true, "")
+                    .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+                    .as_basic_value_enum()
+            }
+            _ => unreachable!(),
+        };
+
+        Ok(LLVMValue::new_value(result, self.number_typing(&result)))
+    }
+
+    pub fn invert(&self, expression: LLVMValue<'ctx>) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let value = self.to_int(self.load_llvm_value("", &expression)?)?;
+        let result = self
+            .builder
+            .build_not(value.into_int_value(), "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+            .as_basic_value_enum();
+
+        Ok(LLVMValue::new_value(result, self.number_typing(&result)))
+    }
+
+    pub fn and(
+        &self,
+        value_left: LLVMValue<'ctx>,
+        value_right: LLVMValue<'ctx>,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let result = self
+            .builder
+            .build_and(
+                value_left.get_basic_value_enum().into_int_value(),
+                value_right.get_basic_value_enum().into_int_value(),
+                "",
+            )
+            .map_err(|e| CompilerError::LLVM(format!("Could not build and operator: {e}")))?;
 
         Ok(LLVMValue::new_value(
             result.as_basic_value_enum(),
-            value_left.get_typing(),
+            Typing::new(true, ast::Type::Bool),
         ))
     }
 
-    pub fn less_or_equal(
+    fn compare(
         &self,
         value_left: LLVMValue<'ctx>,
         value_right: LLVMValue<'ctx>,
+        int_predicate: inkwell::IntPredicate,
+        float_predicate: inkwell::FloatPredicate,
     ) -> Result<LLVMValue<'ctx>, CompilerError> {
-        let l = self.load_llvm_value("", &value_left)?;
-        let r = self.load_llvm_value("", &value_right)?;
-
-        let result = match (l, r) {
-            (BasicValueEnum::IntValue(i), BasicValueEnum::IntValue(j)) => self
-                .builder
-                .build_int_compare(inkwell::IntPredicate::SLE, i, j, ""),
-            (BasicValueEnum::FloatValue(i), BasicValueEnum::FloatValue(j)) => self
-                .builder
-                .build_float_compare(inkwell::FloatPredicate::OLE, i, j, ""),
+        let result = match self.load_numbers(&value_left, &value_right, false)? {
+            (BasicValueEnum::IntValue(i), BasicValueEnum::IntValue(j)) => {
+                self.builder.build_int_compare(int_predicate, i, j, "")
+            }
+            (BasicValueEnum::FloatValue(i), BasicValueEnum::FloatValue(j)) => {
+                self.builder.build_float_compare(float_predicate, i, j, "")
+            }
+            // objects are compared by identity
+            (BasicValueEnum::PointerValue(i), BasicValueEnum::PointerValue(j)) => {
+                self.builder.build_int_compare(int_predicate, i, j, "")
+            }
             _ => unimplemented!(),
         }
         .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
 
         Ok(LLVMValue::new_value(
             result.as_basic_value_enum(),
-            value_left.get_typing(),
+            Typing::new(true, ast::Type::Bool),
         ))
     }
 
-    pub fn modulo(
+    pub fn equal(
         &self,
         value_left: LLVMValue<'ctx>,
         value_right: LLVMValue<'ctx>,
     ) -> Result<LLVMValue<'ctx>, CompilerError> {
-        let l = self.load_llvm_value("", &value_left)?;
-        let r = self.load_llvm_value("", &value_right)?;
+        self.compare(
+            value_left,
+            value_right,
+            inkwell::IntPredicate::EQ,
+            inkwell::FloatPredicate::OEQ,
+        )
+    }
 
-        let result = match (l, r) {
-            (BasicValueEnum::IntValue(i), BasicValueEnum::IntValue(j)) => self
-                .builder
-                .build_int_signed_rem(i, j, "")
-                .and_then(|v| Ok(v.as_basic_value_enum())),
-            (BasicValueEnum::FloatValue(i), BasicValueEnum::FloatValue(j)) => self
-                .builder
-                .build_float_rem(i, j, "")
-                .and_then(|v| Ok(v.as_basic_value_enum())),
-            _ => unimplemented!(),
-        }
-        .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+    pub fn greater(
+        &self,
+        value_left: LLVMValue<'ctx>,
+        value_right: LLVMValue<'ctx>,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        self.compare(
+            value_left,
+            value_right,
+            inkwell::IntPredicate::SGT,
+            inkwell::FloatPredicate::OGT,
+        )
+    }
 
-        Ok(LLVMValue::new_value(result, value_left.get_typing()))
+    pub fn greater_or_equal(
+        &self,
+        value_left: LLVMValue<'ctx>,
+        value_right: LLVMValue<'ctx>,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        self.compare(
+            value_left,
+            value_right,
+            inkwell::IntPredicate::SGE,
+            inkwell::FloatPredicate::OGE,
+        )
+    }
+
+    pub fn not_equal(
+        &self,
+        value_left: LLVMValue<'ctx>,
+        value_right: LLVMValue<'ctx>,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        self.compare(
+            value_left,
+            value_right,
+            inkwell::IntPredicate::NE,
+            inkwell::FloatPredicate::UNE,
+        )
+    }
+
+    pub fn less(
+        &self,
+        value_left: LLVMValue<'ctx>,
+        value_right: LLVMValue<'ctx>,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        self.compare(
+            value_left,
+            value_right,
+            inkwell::IntPredicate::SLT,
+            inkwell::FloatPredicate::OLT,
+        )
+    }
+
+    pub fn less_or_equal(
+        &self,
+        value_left: LLVMValue<'ctx>,
+        value_right: LLVMValue<'ctx>,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        self.compare(
+            value_left,
+            value_right,
+            inkwell::IntPredicate::SLE,
+            inkwell::FloatPredicate::OLE,
+        )
+    }
+
+    pub fn concat_strings(
+        &self,
+        strlen: FunctionValue<'ctx>,
+        value_left: LLVMValue<'ctx>,
+        value_right: LLVMValue<'ctx>,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let l = self.load_llvm_value("", &value_left)?.into_pointer_value();
+        let r = self.load_llvm_value("", &value_right)?.into_pointer_value();
+
+        let l_len = self.strlen(strlen, l)?;
+        let r_len = self.strlen(strlen, r)?;
+
+        let size = self
+            .builder
+            .build_int_add(l_len, r_len, "")
+            .and_then(|s| {
+                self.builder
+                    .build_int_add(s, self.context.i64_type().const_int(1, false), "")
+            })
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        let result = self
+            .builder
+            .build_array_malloc(self.context.i8_type(), size, "")
+            .map_err(|e| CompilerError::LLVM(format!("Could not allocate string: {e}")))?;
+
+        self.builder
+            .build_memcpy(result, 1, l, 1, l_len)
+            .map_err(|e| CompilerError::LLVM(format!("Could not copy string: {e}")))?;
+
+        let end = unsafe {
+            self.builder
+                .build_in_bounds_gep(self.context.i8_type(), result, &[l_len], "")
+                .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+        };
+
+        // copy the right string along with its null terminator
+        let r_size = self
+            .builder
+            .build_int_add(r_len, self.context.i64_type().const_int(1, false), "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        self.builder
+            .build_memcpy(end, 1, r, 1, r_size)
+            .map_err(|e| CompilerError::LLVM(format!("Could not copy string: {e}")))?;
+
+        Ok(LLVMValue::new_value(
+            result.as_basic_value_enum(),
+            Typing::new(true, ast::Type::String),
+        ))
+    }
+
+    // formats the values into a new string, the way printf would print them
+    pub fn format_string(
+        &self,
+        snprintf: FunctionValue<'ctx>,
+        format: &str,
+        values: &[BasicValueEnum<'ctx>],
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let i64_type = self.context.i64_type();
+        let format = self.global_string("format_string", format)?;
+
+        let mut args = vec![
+            self.get_new_ptr().const_null().into(),
+            i64_type.const_zero().into(),
+            format.as_pointer_value().into(),
+        ];
+        args.extend(values.iter().map(|v| BasicMetadataValueEnum::from(*v)));
+
+        // a first call with an empty buffer gives the length of the string
+        let length = self
+            .builder
+            .build_direct_call(snprintf, &args, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| CompilerError::LLVM("snprintf did not return a value".to_owned()))?
+            .into_int_value();
+        let size = self
+            .builder
+            .build_int_s_extend(length, i64_type, "")
+            .and_then(|l| {
+                self.builder
+                    .build_int_add(l, i64_type.const_int(1, false), "")
+            })
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        let result = self
+            .builder
+            .build_array_malloc(self.context.i8_type(), size, "")
+            .map_err(|e| CompilerError::LLVM(format!("Could not allocate string: {e}")))?;
+        args[0] = result.into();
+        args[1] = size.into();
+        self.builder
+            .build_direct_call(snprintf, &args, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        Ok(LLVMValue::new_value(
+            result.as_basic_value_enum(),
+            Typing::new(true, ast::Type::String),
+        ))
+    }
+
+    // the printf directive of a replacement field, along with the value it prints. Fields
+    // printf cannot pad are formatted on their own first, then padded
+    pub fn format_field(
+        &self,
+        snprintf: FunctionValue<'ctx>,
+        strlen: FunctionValue<'ctx>,
+        value: LLVMValue<'ctx>,
+        spec: &ast::FormatSpec,
+    ) -> Result<(String, BasicValueEnum<'ctx>), CompilerError> {
+        let r#type = value.get_typing().r#type.get_concrete_type();
+        let value = self.load_llvm_value("", &value)?;
+        let is_float_kind = spec.kind.is_some_and(|k| "eEfFgG%".contains(k));
+
+        let (conversion, value) = match r#type {
+            ast::Type::String => ("s".to_owned(), value),
+            ast::Type::None => (
+                "s".to_owned(),
+                self.global_string("none", "None")?
+                    .as_pointer_value()
+                    .as_basic_value_enum(),
+            ),
+            ast::Type::Bool if spec.kind.is_none() => (
+                "s".to_owned(),
+                self.builder
+                    .build_select(
+                        value.into_int_value(),
+                        self.global_string("true", "True")?.as_pointer_value(),
+                        self.global_string("false", "False")?.as_pointer_value(),
+                        "",
+                    )
+                    .map_err(|e| CompilerError::LLVM(format!("{e}")))?,
+            ),
+            _ if spec.kind == Some('%') => (
+                "f%%".to_owned(),
+                self.builder
+                    .build_float_mul(
+                        self.to_float(value)?.into_float_value(),
+                        self.context.f64_type().const_float(100.0),
+                        "",
+                    )
+                    .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+                    .as_basic_value_enum(),
+            ),
+            _ if is_float_kind => (spec.kind.unwrap().to_string(), self.to_float(value)?),
+            ast::Type::Float => ("f".to_owned(), value),
+            _ => (
+                format!("l{}", spec.kind.unwrap_or('d')),
+                self.to_int(value)?,
+            ),
+        };
+
+        let is_text = conversion == "s";
+        let fill = match spec.fill {
+            None if spec.zero && is_text => '0',
+            fill => fill.unwrap_or(' '),
+        };
+        let align = spec.align.unwrap_or(if is_text { '<' } else { '>' });
+
+        let mut flags = String::new();
+        if let Some(sign @ ('+' | ' ')) = spec.sign {
+            flags.push(sign);
+        }
+        if spec.zero && !is_text {
+            flags.push('0');
+        }
+        let precision = spec.precision.map(|p| format!(".{p}")).unwrap_or_default();
+
+        let Some(width) = spec.width else {
+            return Ok((format!("%{flags}{precision}{conversion}"), value));
+        };
+
+        if fill == ' ' && align != '^' && spec.kind != Some('%') {
+            if align == '<' {
+                flags.insert(0, '-');
+            }
+            return Ok((format!("%{flags}{width}{precision}{conversion}"), value));
+        }
+
+        let formatted = self.format_string(
+            snprintf,
+            &format!("%{flags}{precision}{conversion}"),
+            &[value],
+        )?;
+        let padded = self.pad_string(
+            strlen,
+            formatted.as_value().into_pointer_value(),
+            width,
+            fill,
+            align,
+        )?;
+
+        Ok(("%s".to_owned(), padded.as_basic_value_enum()))
+    }
+
+    // pads a string up to `width` characters, on the side opposite to its alignment
+    fn pad_string(
+        &self,
+        strlen: FunctionValue<'ctx>,
+        string: PointerValue<'ctx>,
+        width: usize,
+        fill: char,
+        align: char,
+    ) -> Result<PointerValue<'ctx>, CompilerError> {
+        let map_err = |e| CompilerError::LLVM(format!("{e}"));
+        let i64_type = self.context.i64_type();
+        let width = i64_type.const_int(width as u64, false);
+
+        let length = self.strlen(strlen, string)?;
+        let is_short = self
+            .builder
+            .build_int_compare(inkwell::IntPredicate::ULT, length, width, "")
+            .map_err(map_err)?;
+        let missing = self
+            .builder
+            .build_int_sub(width, length, "")
+            .map_err(map_err)?;
+        let padding = self
+            .builder
+            .build_select(is_short, missing, i64_type.const_zero(), "")
+            .map_err(map_err)?
+            .into_int_value();
+        let before = match align {
+            '<' => i64_type.const_zero(),
+            '^' => self
+                .builder
+                .build_int_unsigned_div(padding, i64_type.const_int(2, false), "")
+                .map_err(map_err)?,
+            _ => padding,
+        };
+
+        let total = self
+            .builder
+            .build_int_add(length, padding, "")
+            .map_err(map_err)?;
+        let size = self
+            .builder
+            .build_int_add(total, i64_type.const_int(1, false), "")
+            .map_err(map_err)?;
+        let result = self
+            .builder
+            .build_array_malloc(self.context.i8_type(), size, "")
+            .map_err(|e| CompilerError::LLVM(format!("Could not allocate string: {e}")))?;
+
+        self.builder
+            .build_memset(
+                result,
+                1,
+                self.context.i8_type().const_int(fill as u64, false),
+                total,
+            )
+            .map_err(map_err)?;
+        let start = unsafe {
+            self.builder
+                .build_in_bounds_gep(self.context.i8_type(), result, &[before], "")
+                .map_err(map_err)?
+        };
+        self.builder
+            .build_memcpy(start, 1, string, 1, length)
+            .map_err(map_err)?;
+        let end = unsafe {
+            self.builder
+                .build_in_bounds_gep(self.context.i8_type(), result, &[total], "")
+                .map_err(map_err)?
+        };
+        self.store_value(
+            end,
+            self.context.i8_type().const_zero().as_basic_value_enum(),
+        )?;
+
+        Ok(result)
+    }
+
+    pub fn compare_strings(
+        &self,
+        strcmp: FunctionValue<'ctx>,
+        value_left: LLVMValue<'ctx>,
+        value_right: LLVMValue<'ctx>,
+        predicate: inkwell::IntPredicate,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let l = self.load_llvm_value("", &value_left)?;
+        let r = self.load_llvm_value("", &value_right)?;
+
+        let order = self
+            .builder
+            .build_direct_call(strcmp, &[l.into(), r.into()], "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| CompilerError::LLVM("strcmp did not return a value".to_owned()))?
+            .into_int_value();
+
+        let result = self
+            .builder
+            .build_int_compare(predicate, order, order.get_type().const_zero(), "")
+            .map_err(|e| CompilerError::LLVM(format!("Could not compare strings: {e}")))?;
+
+        Ok(LLVMValue::new_value(
+            result.as_basic_value_enum(),
+            Typing::new(true, ast::Type::Bool),
+        ))
+    }
+
+    pub fn string_length(
+        &self,
+        strlen: FunctionValue<'ctx>,
+        value: LLVMValue<'ctx>,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let s = self.load_llvm_value("", &value)?.into_pointer_value();
+        let length = self.strlen(strlen, s)?;
+
+        Ok(LLVMValue::new_value(
+            length.as_basic_value_enum(),
+            Typing::new(true, ast::Type::Int),
+        ))
+    }
+
+    fn strlen(
+        &self,
+        strlen: FunctionValue<'ctx>,
+        value: PointerValue<'ctx>,
+    ) -> Result<IntValue<'ctx>, CompilerError> {
+        Ok(self
+            .builder
+            .build_direct_call(strlen, &[value.into()], "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| CompilerError::LLVM("strlen did not return a value".to_owned()))?
+            .into_int_value())
+    }
+
+    // a list is a pointer to a { length, capacity, items } header allocated on the heap
+    fn list_type(&self) -> StructType<'ctx> {
+        self.context.struct_type(
+            &[
+                self.context.i64_type().into(),
+                self.context.i64_type().into(),
+                self.context.ptr_type(AddressSpace::default()).into(),
+            ],
+            false,
+        )
+    }
+
+    fn get_item_type(&self, item_type: &ast::Type) -> BasicTypeEnum<'ctx> {
+        self.get_llvm_type(&Typing::new(true, item_type.clone()))
+    }
+
+    fn cast_number(
+        &self,
+        value: BasicValueEnum<'ctx>,
+        r#type: &ast::Type,
+    ) -> Result<BasicValueEnum<'ctx>, CompilerError> {
+        match r#type {
+            ast::Type::Float => self.to_float(value),
+            ast::Type::Int => self.to_int(value),
+            _ => Ok(value),
+        }
+    }
+
+    fn struct_field(
+        &self,
+        struct_type: StructType<'ctx>,
+        ptr: PointerValue<'ctx>,
+        field: u32,
+    ) -> Result<PointerValue<'ctx>, CompilerError> {
+        self.builder
+            .build_struct_gep(struct_type, ptr, field, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))
+    }
+
+    fn load_struct_field(
+        &self,
+        struct_type: StructType<'ctx>,
+        ptr: PointerValue<'ctx>,
+        field: u32,
+    ) -> Result<BasicValueEnum<'ctx>, CompilerError> {
+        let field_type = struct_type
+            .get_field_type_at_index(field)
+            .ok_or_else(|| CompilerError::LLVM(format!("No struct field {field}")))?;
+
+        self.builder
+            .build_load(field_type, self.struct_field(struct_type, ptr, field)?, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))
+    }
+
+    fn list_field(
+        &self,
+        list: PointerValue<'ctx>,
+        field: u32,
+    ) -> Result<PointerValue<'ctx>, CompilerError> {
+        self.struct_field(self.list_type(), list, field)
+    }
+
+    fn load_list_field(
+        &self,
+        list: PointerValue<'ctx>,
+        field: u32,
+    ) -> Result<BasicValueEnum<'ctx>, CompilerError> {
+        self.load_struct_field(self.list_type(), list, field)
+    }
+
+    fn array_item_pointer(
+        &self,
+        items: PointerValue<'ctx>,
+        index: IntValue<'ctx>,
+        item_type: &ast::Type,
+    ) -> Result<PointerValue<'ctx>, CompilerError> {
+        unsafe {
+            self.builder
+                .build_in_bounds_gep(self.get_item_type(item_type), items, &[index], "")
+                .map_err(|e| CompilerError::LLVM(format!("{e}")))
+        }
+    }
+
+    fn load_array_item(
+        &self,
+        items: PointerValue<'ctx>,
+        index: IntValue<'ctx>,
+        item_type: &ast::Type,
+    ) -> Result<BasicValueEnum<'ctx>, CompilerError> {
+        self.builder
+            .build_load(
+                self.get_item_type(item_type),
+                self.array_item_pointer(items, index, item_type)?,
+                "",
+            )
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))
+    }
+
+    fn list_item_pointer(
+        &self,
+        list: PointerValue<'ctx>,
+        index: IntValue<'ctx>,
+        item_type: &ast::Type,
+    ) -> Result<PointerValue<'ctx>, CompilerError> {
+        let items = self.load_list_field(list, 2)?.into_pointer_value();
+        self.array_item_pointer(items, index, item_type)
+    }
+
+    pub fn new_list(
+        &self,
+        items: Vec<BasicValueEnum<'ctx>>,
+        item_type: &ast::Type,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let list = self
+            .builder
+            .build_malloc(self.list_type(), "list")
+            .map_err(|e| CompilerError::LLVM(format!("Could not allocate list: {e}")))?;
+
+        let length = self.context.i64_type().const_int(items.len() as u64, false);
+
+        let data = if items.is_empty() {
+            self.context.ptr_type(AddressSpace::default()).const_null()
+        } else {
+            self.builder
+                .build_array_malloc(self.get_item_type(item_type), length, "")
+                .map_err(|e| CompilerError::LLVM(format!("Could not allocate list: {e}")))?
+        };
+
+        self.store_value(self.list_field(list, 0)?, length.as_basic_value_enum())?;
+        self.store_value(self.list_field(list, 1)?, length.as_basic_value_enum())?;
+        self.store_value(self.list_field(list, 2)?, data.as_basic_value_enum())?;
+
+        for (i, item) in items.into_iter().enumerate() {
+            let index = self.context.i64_type().const_int(i as u64, false);
+            self.set_list_item(list, index, item, item_type)?;
+        }
+
+        Ok(LLVMValue::new_value(
+            list.as_basic_value_enum(),
+            Typing::new(true, ast::Type::List(Box::new(item_type.clone()))),
+        ))
+    }
+
+    // a range goes from start towards stop, whatever the sign of its step
+    pub fn range_has_next(
+        &self,
+        current: IntValue<'ctx>,
+        stop: IntValue<'ctx>,
+        step: IntValue<'ctx>,
+    ) -> Result<IntValue<'ctx>, CompilerError> {
+        let compare = |predicate, left, right| {
+            self.builder
+                .build_int_compare(predicate, left, right, "")
+                .map_err(|e| CompilerError::LLVM(format!("{e}")))
+        };
+
+        let is_increasing = compare(
+            inkwell::IntPredicate::SGT,
+            step,
+            step.get_type().const_zero(),
+        )?;
+        let below = compare(inkwell::IntPredicate::SLT, current, stop)?;
+        let above = compare(inkwell::IntPredicate::SGT, current, stop)?;
+
+        Ok(self
+            .builder
+            .build_select(is_increasing, below, above, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+            .into_int_value())
+    }
+
+    pub fn new_range(
+        &self,
+        start: IntValue<'ctx>,
+        stop: IntValue<'ctx>,
+        step: IntValue<'ctx>,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let i64_type = self.context.i64_type();
+        let map_err = |e| CompilerError::LLVM(format!("{e}"));
+
+        // length = (distance - 1) / |step| + 1, or 0 when stop is not ahead of start
+        let is_increasing = self
+            .builder
+            .build_int_compare(inkwell::IntPredicate::SGT, step, i64_type.const_zero(), "")
+            .map_err(map_err)?;
+        let forward = self
+            .builder
+            .build_int_sub(stop, start, "")
+            .map_err(map_err)?;
+        let backward = self
+            .builder
+            .build_int_sub(start, stop, "")
+            .map_err(map_err)?;
+        let distance = self
+            .builder
+            .build_select(is_increasing, forward, backward, "")
+            .map_err(map_err)?
+            .into_int_value();
+        let negated_step = self.builder.build_int_neg(step, "").map_err(map_err)?;
+        let step_size = self
+            .builder
+            .build_select(is_increasing, step, negated_step, "")
+            .map_err(map_err)?
+            .into_int_value();
+
+        let one = i64_type.const_int(1, false);
+        let length = self
+            .builder
+            .build_int_sub(distance, one, "")
+            .map_err(map_err)?;
+        let length = self
+            .builder
+            .build_int_signed_div(length, step_size, "")
+            .map_err(map_err)?;
+        let length = self
+            .builder
+            .build_int_add(length, one, "")
+            .map_err(map_err)?;
+        let is_empty = self
+            .builder
+            .build_int_compare(
+                inkwell::IntPredicate::SLE,
+                distance,
+                i64_type.const_zero(),
+                "",
+            )
+            .map_err(map_err)?;
+        let length = self
+            .builder
+            .build_select(is_empty, i64_type.const_zero(), length, "")
+            .map_err(map_err)?
+            .into_int_value();
+
+        let items = self
+            .builder
+            .build_array_malloc(i64_type, length, "")
+            .map_err(|e| CompilerError::LLVM(format!("Could not allocate list: {e}")))?;
+        self.for_each_index(length, |i| {
+            let offset = self.builder.build_int_mul(i, step, "").map_err(map_err)?;
+            let value = self
+                .builder
+                .build_int_add(start, offset, "")
+                .map_err(map_err)?;
+            self.store_value(
+                self.array_item_pointer(items, i, &ast::Type::Int)?,
+                value.as_basic_value_enum(),
+            )
+        })?;
+
+        self.list_with_data(items, length, &ast::Type::Int)
+    }
+
+    pub fn list_length(&self, list: PointerValue<'ctx>) -> Result<IntValue<'ctx>, CompilerError> {
+        Ok(self.load_list_field(list, 0)?.into_int_value())
+    }
+
+    // returns the index with negative values counted from the end, and whether it is in bounds
+    pub fn list_index(
+        &self,
+        list: PointerValue<'ctx>,
+        index: BasicValueEnum<'ctx>,
+    ) -> Result<(IntValue<'ctx>, IntValue<'ctx>), CompilerError> {
+        let index = self.to_int(index)?.into_int_value();
+        let length = self.list_length(list)?;
+
+        let is_negative = self
+            .builder
+            .build_int_compare(
+                inkwell::IntPredicate::SLT,
+                index,
+                index.get_type().const_zero(),
+                "",
+            )
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        let from_end = self
+            .builder
+            .build_int_add(index, length, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        let index = self
+            .builder
+            .build_select(is_negative, from_end, index, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+            .into_int_value();
+
+        // a negative index becomes a huge unsigned one
+        let in_bounds = self
+            .builder
+            .build_int_compare(inkwell::IntPredicate::ULT, index, length, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        Ok((index, in_bounds))
+    }
+
+    pub fn get_list_item(
+        &self,
+        list: PointerValue<'ctx>,
+        index: IntValue<'ctx>,
+        item_type: &ast::Type,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let item = self
+            .builder
+            .build_load(
+                self.get_item_type(item_type),
+                self.list_item_pointer(list, index, item_type)?,
+                "",
+            )
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        Ok(LLVMValue::new_value(
+            item,
+            Typing::new(true, item_type.clone()),
+        ))
+    }
+
+    pub fn set_list_item(
+        &self,
+        list: PointerValue<'ctx>,
+        index: IntValue<'ctx>,
+        value: BasicValueEnum<'ctx>,
+        item_type: &ast::Type,
+    ) -> Result<(), CompilerError> {
+        let value = self.cast_number(value, item_type)?;
+        self.store_value(self.list_item_pointer(list, index, item_type)?, value)
+    }
+
+    pub fn append_list_item(
+        &self,
+        realloc: FunctionValue<'ctx>,
+        list: PointerValue<'ctx>,
+        value: BasicValueEnum<'ctx>,
+        item_type: &ast::Type,
+    ) -> Result<(), CompilerError> {
+        let length = self.list_length(list)?;
+        let capacity = self.load_list_field(list, 1)?.into_int_value();
+
+        let grow_block = self.create_block("list_grow");
+        let append_block = self.create_block("list_append");
+
+        let is_full = self
+            .builder
+            .build_int_compare(inkwell::IntPredicate::EQ, length, capacity, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        self.builder
+            .build_conditional_branch(is_full, grow_block, append_block)
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        self.set_position_at(grow_block);
+        let new_capacity = self
+            .builder
+            .build_int_mul(capacity, self.context.i64_type().const_int(2, false), "")
+            .and_then(|c| {
+                self.builder
+                    .build_int_add(c, self.context.i64_type().const_int(4, false), "")
+            })
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        let item_size = self
+            .get_item_type(item_type)
+            .size_of()
+            .ok_or_else(|| CompilerError::LLVM(format!("Unsized list item: {item_type}")))?;
+        let size = self
+            .builder
+            .build_int_mul(new_capacity, item_size, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        let items = self.load_list_field(list, 2)?;
+        let new_items = self
+            .builder
+            .build_direct_call(realloc, &[items.into(), size.into()], "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| CompilerError::LLVM("realloc did not return a value".to_owned()))?;
+        self.store_value(
+            self.list_field(list, 1)?,
+            new_capacity.as_basic_value_enum(),
+        )?;
+        self.store_value(self.list_field(list, 2)?, new_items)?;
+        self.goto_block(append_block)?;
+
+        self.set_position_at(append_block);
+        self.set_list_item(list, length, value, item_type)?;
+        let new_length = self
+            .builder
+            .build_int_add(length, self.context.i64_type().const_int(1, false), "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        self.store_value(self.list_field(list, 0)?, new_length.as_basic_value_enum())
+    }
+
+    pub fn lists_equal<F>(
+        &self,
+        value_left: LLVMValue<'ctx>,
+        value_right: LLVMValue<'ctx>,
+        item_type: &ast::Type,
+        mut items_equal: F,
+    ) -> Result<LLVMValue<'ctx>, CompilerError>
+    where
+        F: FnMut(LLVMValue<'ctx>, LLVMValue<'ctx>) -> Result<LLVMValue<'ctx>, CompilerError>,
+    {
+        let left = self.load_llvm_value("", &value_left)?.into_pointer_value();
+        let right = self.load_llvm_value("", &value_right)?.into_pointer_value();
+
+        let length = self.list_length(left)?;
+        let same_length = self
+            .builder
+            .build_int_compare(
+                inkwell::IntPredicate::EQ,
+                length,
+                self.list_length(right)?,
+                "",
+            )
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        let start_block = self.get_current_block().unwrap();
+        let cond_block = self.create_block("list_eq_cond");
+        let body_block = self.create_block("list_eq_body");
+        let equal_block = self.create_block("list_eq_equal");
+        let end_block = self.create_block("list_eq_end");
+
+        self.builder
+            .build_conditional_branch(same_length, cond_block, end_block)
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        self.set_position_at(cond_block);
+        let index = self
+            .builder
+            .build_phi(self.context.i64_type(), "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        index.add_incoming(&[(&self.context.i64_type().const_zero(), start_block)]);
+        let i = index.as_basic_value().into_int_value();
+        let has_next = self
+            .builder
+            .build_int_compare(inkwell::IntPredicate::ULT, i, length, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        self.builder
+            .build_conditional_branch(has_next, body_block, equal_block)
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        // lists whose items are unknown are always empty
+        self.set_position_at(body_block);
+        let body_end_block;
+        if *item_type == ast::Type::Unknown {
+            body_end_block = body_block;
+            self.goto_block(equal_block)?;
+        } else {
+            let is_equal = items_equal(
+                self.get_list_item(left, i, item_type)?,
+                self.get_list_item(right, i, item_type)?,
+            )?;
+            let next_index = self
+                .builder
+                .build_int_add(i, self.context.i64_type().const_int(1, false), "")
+                .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+            body_end_block = self.get_current_block().unwrap();
+            self.builder
+                .build_conditional_branch(
+                    self.load_llvm_value("", &is_equal)?.into_int_value(),
+                    cond_block,
+                    end_block,
+                )
+                .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+            index.add_incoming(&[(&next_index, body_end_block)]);
+        }
+
+        self.set_position_at(equal_block);
+        self.goto_block(end_block)?;
+
+        self.set_position_at(end_block);
+        let result = self
+            .builder
+            .build_phi(self.context.bool_type(), "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        let false_value = self.context.bool_type().const_zero();
+        let true_value = self.context.bool_type().const_int(1, false);
+        result.add_incoming(&[(&false_value, start_block), (&true_value, equal_block)]);
+        if *item_type != ast::Type::Unknown {
+            result.add_incoming(&[(&false_value, body_end_block)]);
+        }
+
+        Ok(LLVMValue::new_value(
+            result.as_basic_value(),
+            Typing::new(true, ast::Type::Bool),
+        ))
+    }
+
+    // the caller is responsible for checking the list is not empty
+    pub fn pop_list_item(
+        &self,
+        list: PointerValue<'ctx>,
+        item_type: &ast::Type,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let length = self.list_length(list)?;
+        let last = self
+            .builder
+            .build_int_sub(length, self.context.i64_type().const_int(1, false), "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        let item = self.get_list_item(list, last, item_type)?;
+        self.store_value(self.list_field(list, 0)?, last.as_basic_value_enum())?;
+
+        Ok(item)
+    }
+
+    pub fn list_contains<F>(
+        &self,
+        list: LLVMValue<'ctx>,
+        item_type: &ast::Type,
+        mut is_searched: F,
+    ) -> Result<LLVMValue<'ctx>, CompilerError>
+    where
+        F: FnMut(LLVMValue<'ctx>) -> Result<LLVMValue<'ctx>, CompilerError>,
+    {
+        // lists whose items are unknown are always empty
+        if *item_type == ast::Type::Unknown {
+            return Ok(LLVMValue::new_value(
+                self.value_bool(false),
+                Typing::new(true, ast::Type::Bool),
+            ));
+        }
+
+        let list = self.load_llvm_value("", &list)?.into_pointer_value();
+        let found = self.any_index(self.list_length(list)?, |i| {
+            let is_equal = is_searched(self.get_list_item(list, i, item_type)?)?;
+            Ok(self.load_llvm_value("", &is_equal)?.into_int_value())
+        })?;
+
+        Ok(LLVMValue::new_value(
+            found.as_basic_value_enum(),
+            Typing::new(true, ast::Type::Bool),
+        ))
+    }
+
+    // copies a list, and each of its items through `clone_item`
+    pub fn clone_list<F>(
+        &self,
+        list: PointerValue<'ctx>,
+        item_type: &ast::Type,
+        mut clone_item: F,
+    ) -> Result<PointerValue<'ctx>, CompilerError>
+    where
+        F: FnMut(LLVMValue<'ctx>) -> Result<LLVMValue<'ctx>, CompilerError>,
+    {
+        let length = self.list_length(list)?;
+        let items = self.load_list_field(list, 2)?.into_pointer_value();
+        let data = self
+            .builder
+            .build_array_malloc(self.get_item_type(item_type), length, "")
+            .map_err(|e| CompilerError::LLVM(format!("Could not allocate list: {e}")))?;
+
+        self.for_each_index(length, |i| {
+            let item = clone_item(LLVMValue::new_value(
+                self.load_array_item(items, i, item_type)?,
+                Typing::new(true, item_type.clone()),
+            ))?;
+            self.store_value(
+                self.array_item_pointer(data, i, item_type)?,
+                self.load_llvm_value("", &item)?,
+            )
+        })?;
+
+        Ok(self
+            .list_with_data(data, length, item_type)?
+            .as_value()
+            .into_pointer_value())
+    }
+
+    // copies an array of items into a new list
+    fn list_from_array(
+        &self,
+        items: PointerValue<'ctx>,
+        length: IntValue<'ctx>,
+        item_type: &ast::Type,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let data = self
+            .builder
+            .build_array_malloc(self.get_item_type(item_type), length, "")
+            .map_err(|e| CompilerError::LLVM(format!("Could not allocate list: {e}")))?;
+
+        let item_size = self
+            .get_item_type(item_type)
+            .size_of()
+            .ok_or_else(|| CompilerError::LLVM(format!("Unsized list item: {item_type}")))?;
+        let size = self
+            .builder
+            .build_int_mul(length, item_size, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        self.builder
+            .build_memcpy(data, 1, items, 1, size)
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        self.list_with_data(data, length, item_type)
+    }
+
+    // the list takes ownership of the given array
+    fn list_with_data(
+        &self,
+        data: PointerValue<'ctx>,
+        length: IntValue<'ctx>,
+        item_type: &ast::Type,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let list = self
+            .builder
+            .build_malloc(self.list_type(), "list")
+            .map_err(|e| CompilerError::LLVM(format!("Could not allocate list: {e}")))?;
+
+        self.store_value(self.list_field(list, 0)?, length.as_basic_value_enum())?;
+        self.store_value(self.list_field(list, 1)?, length.as_basic_value_enum())?;
+        self.store_value(self.list_field(list, 2)?, data.as_basic_value_enum())?;
+
+        Ok(LLVMValue::new_value(
+            list.as_basic_value_enum(),
+            Typing::new(true, ast::Type::List(Box::new(item_type.clone()))),
+        ))
+    }
+
+    // emits a loop running `body` for every index below `length`
+    fn for_each_index<F>(&self, length: IntValue<'ctx>, mut body: F) -> Result<(), CompilerError>
+    where
+        F: FnMut(IntValue<'ctx>) -> Result<(), CompilerError>,
+    {
+        let start_block = self.get_current_block().unwrap();
+        let cond_block = self.create_block("for_each_cond");
+        let body_block = self.create_block("for_each_body");
+        let end_block = self.create_block("for_each_end");
+        self.goto_block(cond_block)?;
+
+        self.set_position_at(cond_block);
+        let index = self
+            .builder
+            .build_phi(self.context.i64_type(), "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        let i = index.as_basic_value().into_int_value();
+        let has_next = self
+            .builder
+            .build_int_compare(inkwell::IntPredicate::ULT, i, length, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        self.builder
+            .build_conditional_branch(has_next, body_block, end_block)
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        self.set_position_at(body_block);
+        body(i)?;
+        let next_index = self
+            .builder
+            .build_int_add(i, self.context.i64_type().const_int(1, false), "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        let body_end_block = self.get_current_block().unwrap();
+        self.goto_block(cond_block)?;
+
+        index.add_incoming(&[
+            (&self.context.i64_type().const_zero(), start_block),
+            (&next_index, body_end_block),
+        ]);
+
+        self.set_position_at(end_block);
+        Ok(())
+    }
+
+    // emits a loop stopping at the first index matching `predicate`, returns whether one did
+    fn any_index<F>(
+        &self,
+        length: IntValue<'ctx>,
+        mut predicate: F,
+    ) -> Result<IntValue<'ctx>, CompilerError>
+    where
+        F: FnMut(IntValue<'ctx>) -> Result<IntValue<'ctx>, CompilerError>,
+    {
+        let start_block = self.get_current_block().unwrap();
+        let cond_block = self.create_block("any_cond");
+        let body_block = self.create_block("any_body");
+        let end_block = self.create_block("any_end");
+        self.goto_block(cond_block)?;
+
+        self.set_position_at(cond_block);
+        let index = self
+            .builder
+            .build_phi(self.context.i64_type(), "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        let i = index.as_basic_value().into_int_value();
+        let has_next = self
+            .builder
+            .build_int_compare(inkwell::IntPredicate::ULT, i, length, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        self.builder
+            .build_conditional_branch(has_next, body_block, end_block)
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        self.set_position_at(body_block);
+        let matched = predicate(i)?;
+        let next_index = self
+            .builder
+            .build_int_add(i, self.context.i64_type().const_int(1, false), "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        let body_end_block = self.get_current_block().unwrap();
+        self.builder
+            .build_conditional_branch(matched, end_block, cond_block)
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        index.add_incoming(&[
+            (&self.context.i64_type().const_zero(), start_block),
+            (&next_index, body_end_block),
+        ]);
+
+        self.set_position_at(end_block);
+        let result = self
+            .builder
+            .build_phi(self.context.bool_type(), "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        result.add_incoming(&[
+            (&self.context.bool_type().const_zero(), cond_block),
+            (
+                &self.context.bool_type().const_int(1, false),
+                body_end_block,
+            ),
+        ]);
+
+        Ok(result.as_basic_value().into_int_value())
+    }
+
+    // a dict is a pointer to a { length, capacity, keys, values, table capacity, table } header
+    // allocated on the heap. Entries are stored in insertion order, the table maps the key
+    // hashes to their entry index plus one, zero marking an empty slot.
+    fn dict_type(&self) -> StructType<'ctx> {
+        let i64_type = self.context.i64_type().into();
+        let ptr_type = self.context.ptr_type(AddressSpace::default()).into();
+
+        self.context.struct_type(
+            &[i64_type, i64_type, ptr_type, ptr_type, i64_type, ptr_type],
+            false,
+        )
+    }
+
+    fn dict_field(
+        &self,
+        dict: PointerValue<'ctx>,
+        field: u32,
+    ) -> Result<PointerValue<'ctx>, CompilerError> {
+        self.struct_field(self.dict_type(), dict, field)
+    }
+
+    fn load_dict_field(
+        &self,
+        dict: PointerValue<'ctx>,
+        field: u32,
+    ) -> Result<BasicValueEnum<'ctx>, CompilerError> {
+        self.load_struct_field(self.dict_type(), dict, field)
+    }
+
+    fn dict_table_slot(
+        &self,
+        table: PointerValue<'ctx>,
+        slot: IntValue<'ctx>,
+    ) -> Result<PointerValue<'ctx>, CompilerError> {
+        self.array_item_pointer(table, slot, &ast::Type::Int)
+    }
+
+    pub fn new_dict(
+        &self,
+        calloc: FunctionValue<'ctx>,
+    ) -> Result<PointerValue<'ctx>, CompilerError> {
+        let dict = self
+            .builder
+            .build_malloc(self.dict_type(), "dict")
+            .map_err(|e| CompilerError::LLVM(format!("Could not allocate dict: {e}")))?;
+
+        let zero = self.context.i64_type().const_zero().as_basic_value_enum();
+        let null = self.get_new_ptr().const_null().as_basic_value_enum();
+        let table_capacity = self.context.i64_type().const_int(8, false);
+        let table = self.allocate_table(calloc, table_capacity)?;
+
+        self.store_value(self.dict_field(dict, 0)?, zero)?;
+        self.store_value(self.dict_field(dict, 1)?, zero)?;
+        self.store_value(self.dict_field(dict, 2)?, null)?;
+        self.store_value(self.dict_field(dict, 3)?, null)?;
+        self.store_value(
+            self.dict_field(dict, 4)?,
+            table_capacity.as_basic_value_enum(),
+        )?;
+        self.store_value(self.dict_field(dict, 5)?, table)?;
+
+        Ok(dict)
+    }
+
+    fn allocate_table(
+        &self,
+        calloc: FunctionValue<'ctx>,
+        capacity: IntValue<'ctx>,
+    ) -> Result<BasicValueEnum<'ctx>, CompilerError> {
+        let slot_size = self.context.i64_type().const_int(8, false);
+
+        self.builder
+            .build_direct_call(calloc, &[capacity.into(), slot_size.into()], "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| CompilerError::LLVM("calloc did not return a value".to_owned()))
+    }
+
+    pub fn dict_length(&self, dict: PointerValue<'ctx>) -> Result<IntValue<'ctx>, CompilerError> {
+        Ok(self.load_dict_field(dict, 0)?.into_int_value())
+    }
+
+    fn hash_key(
+        &self,
+        key: BasicValueEnum<'ctx>,
+        key_type: &ast::Type,
+    ) -> Result<IntValue<'ctx>, CompilerError> {
+        let i64_type = self.context.i64_type();
+
+        let bits = match key_type {
+            ast::Type::String => return self.hash_string(key.into_pointer_value()),
+            // 0.0 and -0.0 are equal so they must share their hash
+            ast::Type::Float => {
+                let bits = self
+                    .builder
+                    .build_bit_cast(key, i64_type, "")
+                    .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+                    .into_int_value();
+                let is_zero = self
+                    .builder
+                    .build_float_compare(
+                        inkwell::FloatPredicate::OEQ,
+                        key.into_float_value(),
+                        self.value_float(0.0).into_float_value(),
+                        "",
+                    )
+                    .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+                self.builder
+                    .build_select(is_zero, i64_type.const_zero(), bits, "")
+                    .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+                    .into_int_value()
+            }
+            _ => self
+                .builder
+                .build_int_z_extend_or_bit_cast(key.into_int_value(), i64_type, "")
+                .map_err(|e| CompilerError::LLVM(format!("{e}")))?,
+        };
+
+        // fibonacci hashing spreads consecutive integers over the whole table
+        let hash = self
+            .builder
+            .build_int_mul(bits, i64_type.const_int(0x9E3779B97F4A7C15, false), "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        let high_bits = self
+            .builder
+            .build_right_shift(hash, i64_type.const_int(32, false), false, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        self.builder
+            .build_xor(hash, high_bits, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))
+    }
+
+    // FNV-1a
+    fn hash_string(&self, string: PointerValue<'ctx>) -> Result<IntValue<'ctx>, CompilerError> {
+        let i64_type = self.context.i64_type();
+        let i8_type = self.context.i8_type();
+
+        let start_block = self.get_current_block().unwrap();
+        let cond_block = self.create_block("hash_cond");
+        let body_block = self.create_block("hash_body");
+        let end_block = self.create_block("hash_end");
+        self.goto_block(cond_block)?;
+
+        self.set_position_at(cond_block);
+        let index = self
+            .builder
+            .build_phi(i64_type, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        let hash = self
+            .builder
+            .build_phi(i64_type, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        let i = index.as_basic_value().into_int_value();
+        let h = hash.as_basic_value().into_int_value();
+        let char_ptr = unsafe {
+            self.builder
+                .build_in_bounds_gep(i8_type, string, &[i], "")
+                .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+        };
+        let c = self
+            .builder
+            .build_load(i8_type, char_ptr, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+            .into_int_value();
+        let is_end = self
+            .builder
+            .build_int_compare(inkwell::IntPredicate::EQ, c, i8_type.const_zero(), "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        self.builder
+            .build_conditional_branch(is_end, end_block, body_block)
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        self.set_position_at(body_block);
+        let c = self
+            .builder
+            .build_int_z_extend(c, i64_type, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        let next_hash = self
+            .builder
+            .build_xor(h, c, "")
+            .and_then(|h| {
+                self.builder
+                    .build_int_mul(h, i64_type.const_int(0x100000001b3, false), "")
+            })
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        let next_index = self
+            .builder
+            .build_int_add(i, i64_type.const_int(1, false), "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        self.goto_block(cond_block)?;
+
+        index.add_incoming(&[
+            (&i64_type.const_zero(), start_block),
+            (&next_index, body_block),
+        ]);
+        hash.add_incoming(&[
+            (&i64_type.const_int(0xcbf29ce484222325, false), start_block),
+            (&next_hash, body_block),
+        ]);
+
+        self.set_position_at(end_block);
+        Ok(h)
+    }
+
+    fn keys_equal(
+        &self,
+        strcmp: FunctionValue<'ctx>,
+        left: BasicValueEnum<'ctx>,
+        right: BasicValueEnum<'ctx>,
+        key_type: &ast::Type,
+    ) -> Result<IntValue<'ctx>, CompilerError> {
+        match key_type {
+            ast::Type::String => {
+                let typing = Typing::new(true, ast::Type::String);
+                Ok(self
+                    .compare_strings(
+                        strcmp,
+                        LLVMValue::new_value(left, typing.clone()),
+                        LLVMValue::new_value(right, typing),
+                        inkwell::IntPredicate::EQ,
+                    )?
+                    .as_value()
+                    .into_int_value())
+            }
+            ast::Type::Float => self
+                .builder
+                .build_float_compare(
+                    inkwell::FloatPredicate::OEQ,
+                    left.into_float_value(),
+                    right.into_float_value(),
+                    "",
+                )
+                .map_err(|e| CompilerError::LLVM(format!("{e}"))),
+            _ => self
+                .builder
+                .build_int_compare(
+                    inkwell::IntPredicate::EQ,
+                    left.into_int_value(),
+                    right.into_int_value(),
+                    "",
+                )
+                .map_err(|e| CompilerError::LLVM(format!("{e}"))),
+        }
+    }
+
+    // generates, once per type, the function deep-copying a value of that type. The function
+    // exists before `copy` builds its body, so values of a recursive type can be copied through it
+    pub fn clone_function<F>(
+        &self,
+        module: &Module<'ctx>,
+        r#type: &ast::Type,
+        copy: F,
+    ) -> Result<FunctionValue<'ctx>, CompilerError>
+    where
+        F: FnOnce(PointerValue<'ctx>) -> Result<PointerValue<'ctx>, CompilerError>,
+    {
+        let name = format!("vif_clone_{type}");
+        if let Some(function) = module.get_function(&name) {
+            return Ok(function);
+        }
+
+        let previous_block = self.get_current_block();
+        let function = module.add_function(
+            &name,
+            self.get_new_ptr()
+                .fn_type(&[self.get_new_ptr().into()], false),
+            Some(Linkage::Private),
+        );
+        self.set_position_at(self.context.append_basic_block(function, "entry"));
+
+        let original = function.get_nth_param(0).unwrap().into_pointer_value();
+        let copied = copy(original)?;
+        self.builder
+            .build_return(Some(&copied))
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        if let Some(block) = previous_block {
+            self.set_position_at(block);
+        }
+
+        Ok(function)
+    }
+
+    pub fn call_clone_function(
+        &self,
+        clone_function: FunctionValue<'ctx>,
+        value: BasicValueEnum<'ctx>,
+    ) -> Result<BasicValueEnum<'ctx>, CompilerError> {
+        self.builder
+            .build_direct_call(clone_function, &[value.into()], "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| CompilerError::LLVM("clone did not return a value".to_owned()))
+    }
+
+    // generates, once per key type, the function returning the table slot holding a key,
+    // or the empty slot where it should be inserted
+    pub fn dict_slot_function(
+        &self,
+        module: &Module<'ctx>,
+        strcmp: FunctionValue<'ctx>,
+        key_type: &ast::Type,
+    ) -> Result<FunctionValue<'ctx>, CompilerError> {
+        let name = format!("vif_dict_slot_{key_type}");
+        if let Some(function) = module.get_function(&name) {
+            return Ok(function);
+        }
+
+        let previous_block = self.get_current_block();
+        let i64_type = self.context.i64_type();
+        let function = module.add_function(
+            &name,
+            i64_type.fn_type(
+                &[
+                    self.get_new_ptr().into(),
+                    self.get_item_type(key_type).into(),
+                ],
+                false,
+            ),
+            Some(Linkage::Private),
+        );
+        self.set_position_at(self.context.append_basic_block(function, "entry"));
+
+        let dict = function.get_nth_param(0).unwrap().into_pointer_value();
+        let key = function.get_nth_param(1).unwrap();
+
+        let hash = self.hash_key(key, key_type)?;
+        let keys = self.load_dict_field(dict, 2)?.into_pointer_value();
+        let table = self.load_dict_field(dict, 5)?.into_pointer_value();
+        let mask = self
+            .builder
+            .build_int_sub(
+                self.load_dict_field(dict, 4)?.into_int_value(),
+                i64_type.const_int(1, false),
+                "",
+            )
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        let start_slot = self
+            .builder
+            .build_and(hash, mask, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        let start_block = self.get_current_block().unwrap();
+        let probe_block = self.create_block("probe");
+        let check_block = self.create_block("probe_check");
+        let next_block = self.create_block("probe_next");
+        let found_block = self.create_block("probe_found");
+        self.goto_block(probe_block)?;
+
+        self.set_position_at(probe_block);
+        let slot = self
+            .builder
+            .build_phi(i64_type, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        let s = slot.as_basic_value().into_int_value();
+        let entry = self
+            .builder
+            .build_load(i64_type, self.dict_table_slot(table, s)?, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+            .into_int_value();
+        let is_empty = self
+            .builder
+            .build_int_compare(inkwell::IntPredicate::EQ, entry, i64_type.const_zero(), "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        self.builder
+            .build_conditional_branch(is_empty, found_block, check_block)
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        self.set_position_at(check_block);
+        let position = self
+            .builder
+            .build_int_sub(entry, i64_type.const_int(1, false), "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        let existing_key = self.load_array_item(keys, position, key_type)?;
+        let is_equal = self.keys_equal(strcmp, existing_key, key, key_type)?;
+        self.builder
+            .build_conditional_branch(is_equal, found_block, next_block)
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        self.set_position_at(next_block);
+        let next_slot = self
+            .builder
+            .build_int_add(s, i64_type.const_int(1, false), "")
+            .and_then(|s| self.builder.build_and(s, mask, ""))
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        self.goto_block(probe_block)?;
+
+        slot.add_incoming(&[(&start_slot, start_block), (&next_slot, next_block)]);
+
+        self.set_position_at(found_block);
+        self.builder
+            .build_return(Some(&s))
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        if let Some(block) = previous_block {
+            self.set_position_at(block);
+        }
+
+        Ok(function)
+    }
+
+    // generates, once per key and value types, the function inserting or replacing an entry
+    pub fn dict_set_function(
+        &self,
+        module: &Module<'ctx>,
+        realloc: FunctionValue<'ctx>,
+        calloc: FunctionValue<'ctx>,
+        slot_function: FunctionValue<'ctx>,
+        key_type: &ast::Type,
+        value_type: &ast::Type,
+    ) -> Result<FunctionValue<'ctx>, CompilerError> {
+        let name = format!("vif_dict_set_{key_type}_{value_type}");
+        if let Some(function) = module.get_function(&name) {
+            return Ok(function);
+        }
+
+        let previous_block = self.get_current_block();
+        let i64_type = self.context.i64_type();
+        let one = i64_type.const_int(1, false);
+        let function = module.add_function(
+            &name,
+            self.context.void_type().fn_type(
+                &[
+                    self.get_new_ptr().into(),
+                    self.get_item_type(key_type).into(),
+                    self.get_item_type(value_type).into(),
+                ],
+                false,
+            ),
+            Some(Linkage::Private),
+        );
+        self.set_position_at(self.context.append_basic_block(function, "entry"));
+
+        let dict = function.get_nth_param(0).unwrap().into_pointer_value();
+        let key = function.get_nth_param(1).unwrap();
+        let value = function.get_nth_param(2).unwrap();
+
+        let update_block = self.create_block("dict_update");
+        let insert_block = self.create_block("dict_insert");
+        let grow_block = self.create_block("dict_grow");
+        let append_block = self.create_block("dict_append");
+        let rehash_block = self.create_block("dict_rehash");
+        let end_block = self.create_block("dict_end");
+
+        let slot = self.call_dict_slot(slot_function, dict, key)?;
+        let table = self.load_dict_field(dict, 5)?.into_pointer_value();
+        let slot_ptr = self.dict_table_slot(table, slot)?;
+        let entry = self
+            .builder
+            .build_load(i64_type, slot_ptr, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+            .into_int_value();
+        let is_new = self
+            .builder
+            .build_int_compare(inkwell::IntPredicate::EQ, entry, i64_type.const_zero(), "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        self.builder
+            .build_conditional_branch(is_new, insert_block, update_block)
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        // existing key, only the value changes
+        self.set_position_at(update_block);
+        let position = self
+            .builder
+            .build_int_sub(entry, one, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        let values = self.load_dict_field(dict, 3)?.into_pointer_value();
+        self.store_value(
+            self.array_item_pointer(values, position, value_type)?,
+            value,
+        )?;
+        self.goto_block(end_block)?;
+
+        self.set_position_at(insert_block);
+        let length = self.dict_length(dict)?;
+        let capacity = self.load_dict_field(dict, 1)?.into_int_value();
+        let is_full = self
+            .builder
+            .build_int_compare(inkwell::IntPredicate::EQ, length, capacity, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        self.builder
+            .build_conditional_branch(is_full, grow_block, append_block)
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        self.set_position_at(grow_block);
+        let new_capacity = self
+            .builder
+            .build_int_mul(capacity, i64_type.const_int(2, false), "")
+            .and_then(|c| {
+                self.builder
+                    .build_int_add(c, i64_type.const_int(4, false), "")
+            })
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        for (field, item_type) in [(2, key_type), (3, value_type)] {
+            let item_size = self
+                .get_item_type(item_type)
+                .size_of()
+                .ok_or_else(|| CompilerError::LLVM(format!("Unsized dict item: {item_type}")))?;
+            let size = self
+                .builder
+                .build_int_mul(new_capacity, item_size, "")
+                .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+            let items = self.load_dict_field(dict, field)?;
+            let new_items = self
+                .builder
+                .build_direct_call(realloc, &[items.into(), size.into()], "")
+                .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+                .try_as_basic_value()
+                .left()
+                .ok_or_else(|| CompilerError::LLVM("realloc did not return a value".to_owned()))?;
+            self.store_value(self.dict_field(dict, field)?, new_items)?;
+        }
+        self.store_value(
+            self.dict_field(dict, 1)?,
+            new_capacity.as_basic_value_enum(),
+        )?;
+        self.goto_block(append_block)?;
+
+        self.set_position_at(append_block);
+        let keys = self.load_dict_field(dict, 2)?.into_pointer_value();
+        let values = self.load_dict_field(dict, 3)?.into_pointer_value();
+        self.store_value(self.array_item_pointer(keys, length, key_type)?, key)?;
+        self.store_value(self.array_item_pointer(values, length, value_type)?, value)?;
+        let new_length = self
+            .builder
+            .build_int_add(length, one, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        self.store_value(slot_ptr, new_length.as_basic_value_enum())?;
+        self.store_value(self.dict_field(dict, 0)?, new_length.as_basic_value_enum())?;
+
+        // keep the table at most half full so probing stays short
+        let table_capacity = self.load_dict_field(dict, 4)?.into_int_value();
+        let needs_rehash = self
+            .builder
+            .build_int_mul(new_length, i64_type.const_int(2, false), "")
+            .and_then(|l| {
+                self.builder
+                    .build_int_compare(inkwell::IntPredicate::UGT, l, table_capacity, "")
+            })
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        self.builder
+            .build_conditional_branch(needs_rehash, rehash_block, end_block)
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        self.set_position_at(rehash_block);
+        let new_table_capacity = self
+            .builder
+            .build_int_mul(table_capacity, i64_type.const_int(2, false), "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        let new_table = self.allocate_table(calloc, new_table_capacity)?;
+        self.builder
+            .build_free(table)
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        self.store_value(
+            self.dict_field(dict, 4)?,
+            new_table_capacity.as_basic_value_enum(),
+        )?;
+        self.store_value(self.dict_field(dict, 5)?, new_table)?;
+        self.for_each_index(new_length, |i| {
+            let key = self.load_array_item(keys, i, key_type)?;
+            let slot = self.call_dict_slot(slot_function, dict, key)?;
+            let entry = self
+                .builder
+                .build_int_add(i, one, "")
+                .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+            self.store_value(
+                self.dict_table_slot(new_table.into_pointer_value(), slot)?,
+                entry.as_basic_value_enum(),
+            )
+        })?;
+        self.goto_block(end_block)?;
+
+        self.set_position_at(end_block);
+        self.builder
+            .build_return(None)
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        if let Some(block) = previous_block {
+            self.set_position_at(block);
+        }
+
+        Ok(function)
+    }
+
+    fn call_dict_slot(
+        &self,
+        slot_function: FunctionValue<'ctx>,
+        dict: PointerValue<'ctx>,
+        key: BasicValueEnum<'ctx>,
+    ) -> Result<IntValue<'ctx>, CompilerError> {
+        Ok(self
+            .builder
+            .build_direct_call(slot_function, &[dict.into(), key.into()], "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| CompilerError::LLVM("dict slot did not return a value".to_owned()))?
+            .into_int_value())
+    }
+
+    // returns the index of the key entry plus one, zero when the key is missing
+    pub fn dict_entry(
+        &self,
+        slot_function: FunctionValue<'ctx>,
+        dict: PointerValue<'ctx>,
+        key: BasicValueEnum<'ctx>,
+        key_type: &ast::Type,
+    ) -> Result<IntValue<'ctx>, CompilerError> {
+        let key = self.cast_number(key, key_type)?;
+        let slot = self.call_dict_slot(slot_function, dict, key)?;
+        let table = self.load_dict_field(dict, 5)?.into_pointer_value();
+
+        Ok(self
+            .builder
+            .build_load(
+                self.context.i64_type(),
+                self.dict_table_slot(table, slot)?,
+                "",
+            )
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+            .into_int_value())
+    }
+
+    pub fn has_dict_entry(&self, entry: IntValue<'ctx>) -> Result<IntValue<'ctx>, CompilerError> {
+        self.builder
+            .build_int_compare(
+                inkwell::IntPredicate::NE,
+                entry,
+                entry.get_type().const_zero(),
+                "",
+            )
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))
+    }
+
+    // the caller is responsible for checking the entry exists
+    pub fn get_dict_value(
+        &self,
+        dict: PointerValue<'ctx>,
+        entry: IntValue<'ctx>,
+        value_type: &ast::Type,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let position = self
+            .builder
+            .build_int_sub(entry, self.context.i64_type().const_int(1, false), "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        let values = self.load_dict_field(dict, 3)?.into_pointer_value();
+
+        Ok(LLVMValue::new_value(
+            self.load_array_item(values, position, value_type)?,
+            Typing::new(true, value_type.clone()),
+        ))
+    }
+
+    pub fn set_dict_item(
+        &self,
+        set_function: FunctionValue<'ctx>,
+        dict: PointerValue<'ctx>,
+        key: BasicValueEnum<'ctx>,
+        value: BasicValueEnum<'ctx>,
+        key_type: &ast::Type,
+        value_type: &ast::Type,
+    ) -> Result<(), CompilerError> {
+        let key = self.cast_number(key, key_type)?;
+        let value = self.cast_number(value, value_type)?;
+
+        self.builder
+            .build_direct_call(set_function, &[dict.into(), key.into(), value.into()], "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        Ok(())
+    }
+
+    pub fn dict_keys(
+        &self,
+        dict: PointerValue<'ctx>,
+        key_type: &ast::Type,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let keys = self.load_dict_field(dict, 2)?.into_pointer_value();
+        self.list_from_array(keys, self.dict_length(dict)?, key_type)
+    }
+
+    pub fn dict_values(
+        &self,
+        dict: PointerValue<'ctx>,
+        value_type: &ast::Type,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let values = self.load_dict_field(dict, 3)?.into_pointer_value();
+        self.list_from_array(values, self.dict_length(dict)?, value_type)
+    }
+
+    pub fn dict_items(
+        &self,
+        dict: PointerValue<'ctx>,
+        key_type: &ast::Type,
+        value_type: &ast::Type,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let item_types = vec![key_type.clone(), value_type.clone()];
+        let length = self.dict_length(dict)?;
+        let keys = self.load_dict_field(dict, 2)?.into_pointer_value();
+        let values = self.load_dict_field(dict, 3)?.into_pointer_value();
+
+        let items = self
+            .builder
+            .build_array_malloc(self.get_new_ptr(), length, "")
+            .map_err(|e| CompilerError::LLVM(format!("Could not allocate list: {e}")))?;
+        self.for_each_index(length, |i| {
+            let tuple = self.new_tuple(
+                vec![
+                    self.load_array_item(keys, i, key_type)?,
+                    self.load_array_item(values, i, value_type)?,
+                ],
+                &item_types,
+            )?;
+            self.store_value(
+                self.array_item_pointer(items, i, &ast::Type::Tuple(item_types.clone()))?,
+                tuple.as_value(),
+            )
+        })?;
+
+        let list = self.list_from_array(items, length, &ast::Type::Tuple(item_types.clone()))?;
+        self.builder
+            .build_free(items)
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        Ok(list)
+    }
+
+    // copies a dict, and each of its values through `clone_value`. Keys are hashable, so they
+    // cannot change and are shared with the original dict
+    pub fn clone_dict<F>(
+        &self,
+        calloc: FunctionValue<'ctx>,
+        set_function: FunctionValue<'ctx>,
+        dict: PointerValue<'ctx>,
+        key_type: &ast::Type,
+        value_type: &ast::Type,
+        mut clone_value: F,
+    ) -> Result<PointerValue<'ctx>, CompilerError>
+    where
+        F: FnMut(LLVMValue<'ctx>) -> Result<LLVMValue<'ctx>, CompilerError>,
+    {
+        let copy = self.new_dict(calloc)?;
+        let keys = self.load_dict_field(dict, 2)?.into_pointer_value();
+        let values = self.load_dict_field(dict, 3)?.into_pointer_value();
+
+        self.for_each_index(self.dict_length(dict)?, |i| {
+            let value = clone_value(LLVMValue::new_value(
+                self.load_array_item(values, i, value_type)?,
+                Typing::new(true, value_type.clone()),
+            ))?;
+            self.set_dict_item(
+                set_function,
+                copy,
+                self.load_array_item(keys, i, key_type)?,
+                self.load_llvm_value("", &value)?,
+                key_type,
+                value_type,
+            )
+        })?;
+
+        Ok(copy)
+    }
+
+    pub fn dicts_equal<F>(
+        &self,
+        slot_function: Option<FunctionValue<'ctx>>,
+        value_left: LLVMValue<'ctx>,
+        value_right: LLVMValue<'ctx>,
+        key_type: &ast::Type,
+        value_type: &ast::Type,
+        mut values_equal: F,
+    ) -> Result<LLVMValue<'ctx>, CompilerError>
+    where
+        F: FnMut(LLVMValue<'ctx>, LLVMValue<'ctx>) -> Result<LLVMValue<'ctx>, CompilerError>,
+    {
+        let left = self.load_llvm_value("", &value_left)?.into_pointer_value();
+        let right = self.load_llvm_value("", &value_right)?.into_pointer_value();
+
+        let length = self.dict_length(left)?;
+        let same_length = self
+            .builder
+            .build_int_compare(
+                inkwell::IntPredicate::EQ,
+                length,
+                self.dict_length(right)?,
+                "",
+            )
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        // dicts whose keys are unknown are always empty
+        let Some(slot_function) = slot_function else {
+            return Ok(LLVMValue::new_value(
+                same_length.as_basic_value_enum(),
+                Typing::new(true, ast::Type::Bool),
+            ));
+        };
+
+        let start_block = self.get_current_block().unwrap();
+        let entries_block = self.create_block("dict_eq_entries");
+        let end_block = self.create_block("dict_eq_end");
+        self.builder
+            .build_conditional_branch(same_length, entries_block, end_block)
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        // every key of the left dict must map to an equal value in the right one
+        self.set_position_at(entries_block);
+        let keys = self.load_dict_field(left, 2)?.into_pointer_value();
+        let values = self.load_dict_field(left, 3)?.into_pointer_value();
+        let has_difference = self.any_index(length, |i| {
+            let key = self.load_array_item(keys, i, key_type)?;
+            let entry = self.dict_entry(slot_function, right, key, key_type)?;
+            let is_missing = self
+                .builder
+                .build_int_compare(
+                    inkwell::IntPredicate::EQ,
+                    entry,
+                    entry.get_type().const_zero(),
+                    "",
+                )
+                .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+            // read the first entry of the right dict instead of a missing one
+            let entry = self
+                .builder
+                .build_select(is_missing, entry.get_type().const_int(1, false), entry, "")
+                .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+                .into_int_value();
+            let is_equal = values_equal(
+                LLVMValue::new_value(
+                    self.load_array_item(values, i, value_type)?,
+                    Typing::new(true, value_type.clone()),
+                ),
+                self.get_dict_value(right, entry, value_type)?,
+            )?;
+            let is_different = self.create_not(is_equal)?.as_value().into_int_value();
+
+            self.builder
+                .build_or(is_missing, is_different, "")
+                .map_err(|e| CompilerError::LLVM(format!("{e}")))
+        })?;
+        let is_equal = self
+            .builder
+            .build_not(has_difference, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        let entries_end_block = self.get_current_block().unwrap();
+        self.goto_block(end_block)?;
+
+        self.set_position_at(end_block);
+        let result = self
+            .builder
+            .build_phi(self.context.bool_type(), "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        result.add_incoming(&[
+            (&self.context.bool_type().const_zero(), start_block),
+            (&is_equal, entries_end_block),
+        ]);
+
+        Ok(LLVMValue::new_value(
+            result.as_basic_value(),
+            Typing::new(true, ast::Type::Bool),
+        ))
+    }
+
+    // tuples and objects are pointers to a struct of their items allocated on the heap
+    fn record_type(&self, item_types: &[ast::Type]) -> StructType<'ctx> {
+        let fields = item_types
+            .iter()
+            .map(|t| self.get_item_type(t))
+            .collect::<Vec<BasicTypeEnum>>();
+
+        self.context.struct_type(&fields, false)
+    }
+
+    pub fn new_tuple(
+        &self,
+        items: Vec<BasicValueEnum<'ctx>>,
+        item_types: &[ast::Type],
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let tuple_type = self.record_type(item_types);
+        let tuple = self
+            .builder
+            .build_malloc(tuple_type, "tuple")
+            .map_err(|e| CompilerError::LLVM(format!("Could not allocate tuple: {e}")))?;
+
+        for (i, (item, item_type)) in items.into_iter().zip(item_types.iter()).enumerate() {
+            let item = self.cast_number(item, item_type)?;
+            self.store_value(self.struct_field(tuple_type, tuple, i as u32)?, item)?;
+        }
+
+        Ok(LLVMValue::new_value(
+            tuple.as_basic_value_enum(),
+            Typing::new(true, ast::Type::Tuple(item_types.to_vec())),
+        ))
+    }
+
+    pub fn get_tuple_item(
+        &self,
+        tuple: PointerValue<'ctx>,
+        position: usize,
+        item_types: &[ast::Type],
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let item = self.load_struct_field(self.record_type(item_types), tuple, position as u32)?;
+
+        Ok(LLVMValue::new_value(
+            item,
+            Typing::new(true, item_types[position].clone()),
+        ))
+    }
+
+    // copies a tuple or an object, and each of its items through `clone_item`
+    pub fn clone_record<F>(
+        &self,
+        record: PointerValue<'ctx>,
+        item_types: &[ast::Type],
+        mut clone_item: F,
+    ) -> Result<PointerValue<'ctx>, CompilerError>
+    where
+        F: FnMut(LLVMValue<'ctx>, &ast::Type) -> Result<LLVMValue<'ctx>, CompilerError>,
+    {
+        let record_type = self.record_type(item_types);
+        let copy = self
+            .builder
+            .build_malloc(record_type, "record")
+            .map_err(|e| CompilerError::LLVM(format!("Could not allocate record: {e}")))?;
+
+        for (i, item_type) in item_types.iter().enumerate() {
+            let item = clone_item(self.get_tuple_item(record, i, item_types)?, item_type)?;
+            self.store_value(
+                self.struct_field(record_type, copy, i as u32)?,
+                self.load_llvm_value("", &item)?,
+            )?;
+        }
+
+        Ok(copy)
+    }
+
+    // attributes are set by __init__, right after the allocation
+    pub fn new_object(
+        &self,
+        attribute_types: &[ast::Type],
+    ) -> Result<PointerValue<'ctx>, CompilerError> {
+        self.builder
+            .build_malloc(self.record_type(attribute_types), "object")
+            .map_err(|e| CompilerError::LLVM(format!("Could not allocate object: {e}")))
+    }
+
+    pub fn get_attribute(
+        &self,
+        object: PointerValue<'ctx>,
+        position: usize,
+        attribute_types: &[ast::Type],
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        self.get_tuple_item(object, position, attribute_types)
+    }
+
+    pub fn set_attribute(
+        &self,
+        object: PointerValue<'ctx>,
+        position: usize,
+        value: BasicValueEnum<'ctx>,
+        attribute_types: &[ast::Type],
+    ) -> Result<(), CompilerError> {
+        let value = self.cast_number(value, &attribute_types[position])?;
+        self.store_value(
+            self.struct_field(self.record_type(attribute_types), object, position as u32)?,
+            value,
+        )
+    }
+
+    pub fn string_contains(
+        &self,
+        strstr: FunctionValue<'ctx>,
+        string: LLVMValue<'ctx>,
+        substring: LLVMValue<'ctx>,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let string = self.load_llvm_value("", &string)?;
+        let substring = self.load_llvm_value("", &substring)?;
+
+        let found = self
+            .builder
+            .build_direct_call(strstr, &[string.into(), substring.into()], "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| CompilerError::LLVM("strstr did not return a value".to_owned()))?
+            .into_pointer_value();
+        let result = self
+            .builder
+            .build_is_not_null(found, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        Ok(LLVMValue::new_value(
+            result.as_basic_value_enum(),
+            Typing::new(true, ast::Type::Bool),
+        ))
+    }
+
+    // takes the sign of the divisor, `-7 % 3` being 2
+    pub fn modulo(
+        &self,
+        value_left: LLVMValue<'ctx>,
+        value_right: LLVMValue<'ctx>,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let result = match self.load_numbers(&value_left, &value_right, true)? {
+            (BasicValueEnum::IntValue(i), BasicValueEnum::IntValue(j)) => {
+                let remainder = self
+                    .builder
+                    .build_int_signed_rem(i, j, "")
+                    .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+                let adjust = self.signs_differ(remainder, j)?;
+                let offset = self
+                    .builder
+                    .build_select(adjust, j, self.context.i64_type().const_zero(), "")
+                    .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+                    .into_int_value();
+                self.builder
+                    .build_int_add(remainder, offset, "")
+                    .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+                    .as_basic_value_enum()
+            }
+            (BasicValueEnum::FloatValue(i), BasicValueEnum::FloatValue(j)) => {
+                let remainder = self.float_modulo(i, j)?;
+                let float_type = self.context.f64_type();
+                let is_set = self
+                    .builder
+                    .build_float_compare(
+                        inkwell::FloatPredicate::ONE,
+                        remainder,
+                        float_type.const_zero(),
+                        "",
+                    )
+                    .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+                let signs_differ = self.float_signs_differ(remainder, j)?;
+                let adjust = self
+                    .builder
+                    .build_and(is_set, signs_differ, "")
+                    .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+                let offset = self
+                    .builder
+                    .build_select(adjust, j, float_type.const_zero(), "")
+                    .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+                    .into_float_value();
+                self.builder
+                    .build_float_add(remainder, offset, "")
+                    .map_err(|e| CompilerError::LLVM(format!("{e}")))?
+                    .as_basic_value_enum()
+            }
+            _ => unimplemented!(),
+        };
+
+        Ok(LLVMValue::new_value(result, self.number_typing(&result)))
+    }
+
+    // the truncated remainder, taking the sign of the dividend
+    fn float_modulo(
+        &self,
+        left: FloatValue<'ctx>,
+        right: FloatValue<'ctx>,
+    ) -> Result<FloatValue<'ctx>, CompilerError> {
+        self.builder
+            .build_float_rem(left, right, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))
+    }
+
+    // whether a truncated remainder must be moved towards the divisor sign, being
+    // neither zero nor of the same sign
+    fn signs_differ(
+        &self,
+        remainder: IntValue<'ctx>,
+        divisor: IntValue<'ctx>,
+    ) -> Result<IntValue<'ctx>, CompilerError> {
+        let i64_type = self.context.i64_type();
+        let is_set = self
+            .builder
+            .build_int_compare(
+                inkwell::IntPredicate::NE,
+                remainder,
+                i64_type.const_zero(),
+                "",
+            )
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        let is_negative = self
+            .builder
+            .build_xor(remainder, divisor, "")
+            .and_then(|v| {
+                self.builder.build_int_compare(
+                    inkwell::IntPredicate::SLT,
+                    v,
+                    i64_type.const_zero(),
+                    "",
+                )
+            })
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        self.builder
+            .build_and(is_set, is_negative, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))
+    }
+
+    fn float_signs_differ(
+        &self,
+        left: FloatValue<'ctx>,
+        right: FloatValue<'ctx>,
+    ) -> Result<IntValue<'ctx>, CompilerError> {
+        let zero = self.context.f64_type().const_zero();
+        let left_negative = self
+            .builder
+            .build_float_compare(inkwell::FloatPredicate::OLT, left, zero, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+        let right_negative = self
+            .builder
+            .build_float_compare(inkwell::FloatPredicate::OLT, right, zero, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))?;
+
+        self.builder
+            .build_xor(left_negative, right_negative, "")
+            .map_err(|e| CompilerError::LLVM(format!("{e}")))
     }
 }
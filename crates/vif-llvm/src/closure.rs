@@ -0,0 +1,244 @@
+use std::collections::HashSet;
+use vif_objects::ast;
+
+// Names a function refers to without declaring them, including the ones its nested
+// functions refer to. The enclosing function's variables among them are captured
+pub fn free_variables(function: &ast::Function) -> Vec<String> {
+    let mut scope = Scope::default();
+
+    for param in function.params.iter() {
+        if let Some(default) = &param.default {
+            scope.expression(default);
+        }
+        scope.declare(&param.name);
+    }
+
+    scope.statements(&function.body);
+    scope.free
+}
+
+// Variables of a function that its nested functions capture. They must outlive the
+// function, as closures can be returned
+pub fn captured_variables(function: &ast::Function) -> HashSet<String> {
+    let mut captured = HashSet::new();
+    for stmt in function.body.iter() {
+        nested_captures(stmt, &mut captured);
+    }
+    captured
+}
+
+// Variables a function declares, its parameters included, not the nested functions
+pub fn declared_variables(function: &ast::Function) -> HashSet<String> {
+    let mut declared = function.params.iter().map(|p| p.name.clone()).collect();
+    for stmt in function.body.iter() {
+        declared_names(stmt, &mut declared);
+    }
+    declared
+}
+
+fn declared_names(stmt: &ast::Stmt, declared: &mut HashSet<String>) {
+    match stmt {
+        ast::Stmt::Var(v) => {
+            declared.insert(v.name.clone());
+        }
+        ast::Stmt::Block(b) => b.iter().for_each(|s| declared_names(s, declared)),
+        ast::Stmt::Condition(c) => {
+            declared_names(&c.then, declared);
+            if let Some(r#else) = &c.r#else {
+                declared_names(r#else, declared);
+            }
+        }
+        ast::Stmt::While(w) => declared_names(&w.body, declared),
+        ast::Stmt::For(f) => {
+            declared.insert(f.name.clone());
+            declared_names(&f.body, declared);
+            if let Some(r#else) = &f.r#else {
+                declared_names(r#else, declared);
+            }
+        }
+        ast::Stmt::Try(t) => {
+            declared_names(&t.body, declared);
+            for handler in t.handlers.iter() {
+                if let Some(name) = &handler.name {
+                    declared.insert(name.clone());
+                }
+                declared_names(&handler.body, declared);
+            }
+            if let Some(finally) = &t.finally {
+                declared_names(finally, declared);
+            }
+        }
+        _ => (),
+    }
+}
+
+fn nested_captures(stmt: &ast::Stmt, captured: &mut HashSet<String>) {
+    match stmt {
+        ast::Stmt::Function(f) => captured.extend(free_variables(f)),
+        ast::Stmt::Block(b) => b.iter().for_each(|s| nested_captures(s, captured)),
+        ast::Stmt::Condition(c) => {
+            nested_captures(&c.then, captured);
+            if let Some(r#else) = &c.r#else {
+                nested_captures(r#else, captured);
+            }
+        }
+        ast::Stmt::While(w) => nested_captures(&w.body, captured),
+        ast::Stmt::For(f) => {
+            nested_captures(&f.body, captured);
+            if let Some(r#else) = &f.r#else {
+                nested_captures(r#else, captured);
+            }
+        }
+        ast::Stmt::Try(t) => {
+            nested_captures(&t.body, captured);
+            for handler in t.handlers.iter() {
+                nested_captures(&handler.body, captured);
+            }
+            if let Some(finally) = &t.finally {
+                nested_captures(finally, captured);
+            }
+        }
+        _ => (),
+    }
+}
+
+#[derive(Default)]
+struct Scope {
+    declared: HashSet<String>,
+    free: Vec<String>,
+}
+
+impl Scope {
+    fn declare(&mut self, name: &str) {
+        self.declared.insert(name.to_owned());
+    }
+
+    fn refer(&mut self, name: &str) {
+        if !self.declared.contains(name) && !self.free.iter().any(|f| f == name) {
+            self.free.push(name.to_owned());
+        }
+    }
+
+    fn statements(&mut self, stmts: &[ast::Stmt]) {
+        for stmt in stmts.iter() {
+            self.statement(stmt);
+        }
+    }
+
+    fn statement(&mut self, stmt: &ast::Stmt) {
+        match stmt {
+            ast::Stmt::Expression(e) => self.expression(e),
+            ast::Stmt::Var(v) => {
+                self.expression(&v.value);
+                self.declare(&v.name);
+            }
+            ast::Stmt::Function(f) => {
+                self.declare(&f.name);
+                for name in free_variables(f) {
+                    self.refer(&name);
+                }
+            }
+            // methods cannot capture anything
+            ast::Stmt::Class(c) => self.declare(&c.name),
+            ast::Stmt::Block(b) => self.statements(b),
+            ast::Stmt::Condition(c) => {
+                self.expression(&c.expr);
+                self.statement(&c.then);
+                if let Some(r#else) = &c.r#else {
+                    self.statement(r#else);
+                }
+            }
+            ast::Stmt::While(w) => {
+                self.expression(&w.condition);
+                self.statement(&w.body);
+            }
+            ast::Stmt::For(f) => {
+                self.expression(&f.iterable);
+                self.declare(&f.name);
+                self.statement(&f.body);
+                if let Some(r#else) = &f.r#else {
+                    self.statement(r#else);
+                }
+            }
+            ast::Stmt::Return(r) => self.expression(&r.value),
+            ast::Stmt::Assert(a) => self.expression(&a.value),
+            ast::Stmt::Raise(r) => {
+                if let Some(message) = &r.message {
+                    self.expression(message);
+                }
+            }
+            ast::Stmt::Try(t) => {
+                self.statement(&t.body);
+                for handler in t.handlers.iter() {
+                    if let Some(name) = &handler.name {
+                        self.declare(name);
+                    }
+                    self.statement(&handler.body);
+                }
+                if let Some(finally) = &t.finally {
+                    self.statement(finally);
+                }
+            }
+            ast::Stmt::Import(_) => (),
+        }
+    }
+
+    fn expression(&mut self, expr: &ast::Expr) {
+        match &expr.body {
+            ast::ExprBody::Binary(b) => {
+                self.expression(&b.left);
+                self.expression(&b.right);
+            }
+            ast::ExprBody::Comparison(c) => {
+                self.expression(&c.left);
+                c.comparisons.iter().for_each(|(_, e)| self.expression(e));
+            }
+            ast::ExprBody::Logical(l) => {
+                self.expression(&l.left);
+                self.expression(&l.right);
+            }
+            ast::ExprBody::Unary(u) => self.expression(&u.right),
+            ast::ExprBody::Grouping(g) => self.expression(&g.expr),
+            ast::ExprBody::Value(ast::Value::Variable(v)) => self.refer(v),
+            ast::ExprBody::Value(_) | ast::ExprBody::LoopKeyword(_) => (),
+            ast::ExprBody::Assign(a) => {
+                self.expression(&a.value);
+                self.refer(&a.name);
+            }
+            ast::ExprBody::AugmentedAssign(a) => {
+                self.expression(&a.target);
+                self.expression(&a.value);
+            }
+            ast::ExprBody::Call(c) => {
+                self.expression(&c.callee);
+                c.arguments.iter().for_each(|a| self.expression(a));
+                c.keywords.iter().for_each(|(_, a)| self.expression(a));
+            }
+            ast::ExprBody::List(l) => l.items.iter().for_each(|i| self.expression(i)),
+            ast::ExprBody::Tuple(t) => t.items.iter().for_each(|i| self.expression(i)),
+            ast::ExprBody::FormatString(f) => f.parts.iter().for_each(|p| {
+                if let ast::FormatPart::Value(value, _) = p {
+                    self.expression(value);
+                }
+            }),
+            ast::ExprBody::Dict(d) => d.items.iter().for_each(|(k, v)| {
+                self.expression(k);
+                self.expression(v);
+            }),
+            ast::ExprBody::GetItem(g) => {
+                self.expression(&g.object);
+                self.expression(&g.index);
+            }
+            ast::ExprBody::SetItem(s) => {
+                self.expression(&s.object);
+                self.expression(&s.index);
+                self.expression(&s.value);
+            }
+            ast::ExprBody::GetAttr(g) => self.expression(&g.object),
+            ast::ExprBody::SetAttr(s) => {
+                self.expression(&s.object);
+                self.expression(&s.value);
+            }
+        }
+    }
+}
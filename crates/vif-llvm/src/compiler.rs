@@ -1,4 +1,7 @@
 use crate::builder::Builder;
+use crate::closure::captured_variables;
+use crate::closure::declared_variables;
+use crate::closure::free_variables;
 use crate::error::CompilerError;
 
 use inkwell;
@@ -10,13 +13,19 @@ use inkwell::targets::Target;
 use inkwell::targets::TargetMachine;
 use inkwell::values::BasicMetadataValueEnum;
 use inkwell::values::BasicValue;
+use inkwell::values::IntValue;
+use inkwell::values::PointerValue;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use vif_objects::ast::Typing;
 
 use crate::builder::LLVMValue;
 use vif_loader::log;
 use vif_objects::ast;
 use vif_objects::op_code::ItemReference;
+use vif_objects::span::Span;
+
+const ERROR_GLOBAL: &str = "vif.error";
 
 #[derive(Debug, Clone)]
 struct Variables<'ctx> {
@@ -37,6 +46,10 @@ impl<'ctx> Variables<'ctx> {
     fn get(&self, var_name: String) -> Option<&LLVMValue<'ctx>> {
         self.data.get(&var_name)
     }
+
+    fn remove(&mut self, var_name: &str) {
+        self.data.remove(var_name);
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -60,6 +73,27 @@ impl<'ctx, 'function> Functions<'ctx> {
     }
 }
 
+#[derive(Debug, Clone)]
+struct Classes {
+    data: HashMap<String, Vec<ast::Attribute>>,
+}
+
+impl Classes {
+    fn new() -> Self {
+        Self {
+            data: HashMap::new(),
+        }
+    }
+
+    fn add(&mut self, class_name: String, attributes: Vec<ast::Attribute>) {
+        self.data.insert(class_name, attributes);
+    }
+
+    fn get(&self, class_name: &str) -> Option<&Vec<ast::Attribute>> {
+        self.data.get(class_name)
+    }
+}
+
 #[derive(Debug, Clone)]
 struct LoopContext<'ctx> {
     cond: inkwell::basic_block::BasicBlock<'ctx>,
@@ -75,21 +109,64 @@ impl<'ctx> LoopContext<'ctx> {
     }
 }
 
+// where a finally block goes once it has run
+#[derive(Debug, Clone, Copy)]
+enum Leave {
+    Normally,
+    Raising,
+    Returning,
+    Breaking,
+    Continuing,
+}
+
+#[derive(Debug, Clone)]
+struct FinallyContext<'ctx> {
+    block: BasicBlock<'ctx>,
+    // how the try statement is left, see Leave
+    state: PointerValue<'ctx>,
+    // the value returned through the finally block
+    returned: PointerValue<'ctx>,
+    // the error raised through the finally block
+    error: PointerValue<'ctx>,
+    // number of loops the try statement is in
+    loops: usize,
+}
+
 #[derive(Debug, Clone)]
 pub struct CompilerContext<'ctx> {
     return_as_pointer: bool,
+    // what the current function returns, returned numbers widen to it
+    output: ast::Type,
     loop_context: Vec<LoopContext<'ctx>>,
+    // where a raised error goes: the innermost except blocks, or out of the function
+    raised: Option<BasicBlock<'ctx>>,
+    // errors handled by the enclosing except blocks, a bare raise gives the last one back
+    handled: Vec<PointerValue<'ctx>>,
+    // finally blocks of the current function the code is in
+    finally: Vec<FinallyContext<'ctx>>,
     variables: Variables<'ctx>,
+    // variables of the current function captured by its closures
+    captured: HashSet<String>,
+    // nested functions declared before being compiled, so they can be called from anywhere
+    declared_ahead: HashSet<String>,
     functions: Functions<'ctx>,
+    classes: Classes,
 }
 
 impl<'ctx> CompilerContext<'ctx> {
     pub fn new() -> Self {
         Self {
             return_as_pointer: false,
+            output: ast::Type::None,
             variables: Variables::new(),
+            captured: HashSet::new(),
+            declared_ahead: HashSet::new(),
             functions: Functions::new(),
+            classes: Classes::new(),
             loop_context: Vec::new(),
+            raised: None,
+            handled: Vec::new(),
+            finally: Vec::new(),
         }
     }
 }
@@ -98,16 +175,18 @@ pub struct Compiler<'ctx> {
     context: &'ctx inkwell::context::Context,
     module: inkwell::module::Module<'ctx>,
     llvm_builder: Builder<'ctx>,
+    content: &'ctx str,
 }
 
 impl<'ctx> Compiler<'ctx> {
-    pub fn new(context: &'ctx inkwell::context::Context) -> Self {
+    pub fn new(context: &'ctx inkwell::context::Context, content: &'ctx str) -> Self {
         let builder = Builder::new(context);
 
         let compiler = Compiler {
             context,
             module: context.create_module("vif"),
             llvm_builder: builder,
+            content,
         };
 
         compiler
@@ -123,6 +202,105 @@ impl<'ctx> Compiler<'ctx> {
         );
         let print = self.module.add_function("printf", print_type, None);
 
+        let exit_type = self
+            .context
+            .void_type()
+            .fn_type(&[self.context.i32_type().into()], false);
+        self.module.add_function("exit", exit_type, None);
+
+        let strlen_type = self.context.i64_type().fn_type(
+            &[self
+                .context
+                .ptr_type(inkwell::AddressSpace::default())
+                .into()],
+            false,
+        );
+        self.module.add_function("strlen", strlen_type, None);
+
+        let realloc_type = self
+            .context
+            .ptr_type(inkwell::AddressSpace::default())
+            .fn_type(
+                &[
+                    self.context
+                        .ptr_type(inkwell::AddressSpace::default())
+                        .into(),
+                    self.context.i64_type().into(),
+                ],
+                false,
+            );
+        self.module.add_function("realloc", realloc_type, None);
+
+        let strcmp_type = self.context.i32_type().fn_type(
+            &[
+                self.context
+                    .ptr_type(inkwell::AddressSpace::default())
+                    .into(),
+                self.context
+                    .ptr_type(inkwell::AddressSpace::default())
+                    .into(),
+            ],
+            false,
+        );
+        self.module.add_function("strcmp", strcmp_type, None);
+
+        let calloc_type = self
+            .context
+            .ptr_type(inkwell::AddressSpace::default())
+            .fn_type(
+                &[
+                    self.context.i64_type().into(),
+                    self.context.i64_type().into(),
+                ],
+                false,
+            );
+        self.module.add_function("calloc", calloc_type, None);
+
+        let strstr_type = self
+            .context
+            .ptr_type(inkwell::AddressSpace::default())
+            .fn_type(
+                &[
+                    self.context
+                        .ptr_type(inkwell::AddressSpace::default())
+                        .into(),
+                    self.context
+                        .ptr_type(inkwell::AddressSpace::default())
+                        .into(),
+                ],
+                false,
+            );
+        self.module.add_function("strstr", strstr_type, None);
+
+        let snprintf_type = self.context.i32_type().fn_type(
+            &[
+                self.context
+                    .ptr_type(inkwell::AddressSpace::default())
+                    .into(),
+                self.context.i64_type().into(),
+                self.context
+                    .ptr_type(inkwell::AddressSpace::default())
+                    .into(),
+            ],
+            true,
+        );
+        self.module.add_function("snprintf", snprintf_type, None);
+
+        let f64_type = self.context.f64_type();
+        self.module.add_function(
+            "pow",
+            f64_type.fn_type(&[f64_type.into(), f64_type.into()], false),
+            None,
+        );
+        self.module
+            .add_function("round", f64_type.fn_type(&[f64_type.into()], false), None);
+
+        // the error being raised, null when there is none
+        let error = self
+            .module
+            .add_global(self.llvm_builder.get_new_ptr(), None, ERROR_GLOBAL);
+        error.set_initializer(&self.llvm_builder.get_new_ptr().const_null());
+
         context.functions.add(
             "print".to_owned(),
             LLVMValue::new_function(
@@ -139,6 +317,7 @@ impl<'ctx> Compiler<'ctx> {
         );
     }
 
+    // the root function is the program's entry point, only reached from here
     pub fn compile(
         &self,
         function: &ast::Function,
@@ -146,27 +325,150 @@ impl<'ctx> Compiler<'ctx> {
     ) -> Result<BasicBlock<'ctx>, CompilerError> {
         let function_value = self
             .llvm_builder
-            .declare_user_function(function, &self.module);
+            .declare_main_function(function, &self.module);
+
+        self.compile_body(function, &function_value, &[], context)
+    }
+
+    fn compile_function(
+        &self,
+        function: &ast::Function,
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<BasicBlock<'ctx>, CompilerError> {
+        let function_value = match context.declared_ahead.contains(&function.name) {
+            true => context
+                .functions
+                .get(function.name.clone())
+                .unwrap()
+                .clone(),
+            false => self
+                .llvm_builder
+                .declare_user_function(function, &self.module),
+        };
 
         context
             .functions
             .add(function.name.to_owned(), function_value.clone());
 
+        self.compile_body(function, &function_value, &[], context)
+    }
+
+    // Nested functions capturing nothing are declared before the body is compiled, so
+    // that they can call each other whatever their order. The ones calling a closure
+    // capture it, and are declared when met like any closure
+    fn declare_ahead(&self, function: &ast::Function, context: &mut CompilerContext<'ctx>) {
+        let nested = function
+            .body
+            .iter()
+            .filter_map(|s| match s {
+                ast::Stmt::Function(f) => Some(f),
+                _ => None,
+            })
+            .collect::<Vec<&ast::Function>>();
+        let variables = declared_variables(function);
+
+        // a function declared twice is only known after each declaration
+        let mut ahead = nested
+            .iter()
+            .copied()
+            .filter(|f| nested.iter().filter(|n| n.name == f.name).count() == 1)
+            .collect::<Vec<&ast::Function>>();
+        loop {
+            let names = ahead.iter().map(|f| &f.name).collect::<HashSet<&String>>();
+            let is_local = |name: &String| {
+                variables.contains(name)
+                    || context.variables.get(name.clone()).is_some()
+                    || nested.iter().any(|f| &f.name == name)
+            };
+
+            let previous = ahead.len();
+            ahead.retain(|f| {
+                free_variables(f)
+                    .iter()
+                    .all(|name| names.contains(name) || !is_local(name))
+            });
+            if ahead.len() == previous {
+                break;
+            }
+        }
+
+        context.declared_ahead.clear();
+        for f in ahead {
+            let function_value = self.llvm_builder.declare_user_function(f, &self.module);
+            context.functions.add(f.name.clone(), function_value);
+            context.declared_ahead.insert(f.name.clone());
+        }
+    }
+
+    // `captures` are the names of the variables found in the function's environment
+    fn compile_body(
+        &self,
+        function: &ast::Function,
+        function_value: &LLVMValue<'ctx>,
+        captures: &[(String, Typing)],
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<BasicBlock<'ctx>, CompilerError> {
+        let entry_block = self
+            .llvm_builder
+            .create_function_block(function_value, "entry");
+
+        // errors the body does not handle make the function return, and stop the program
+        // once they reach the root function, the only one with nowhere to send them
+        let raised = self.llvm_builder.create_block("raised");
+        self.llvm_builder.set_position_at(raised);
+        match context.raised {
+            None => self.uncaught_error()?,
+            Some(_) => self.llvm_builder.return_default()?,
+        }
+        self.llvm_builder.set_position_at(entry_block);
+        context.raised = Some(raised);
+        context.handled.clear();
+        context.finally.clear();
+
+        context.captured = captured_variables(function);
+        context.output = match &function.typing.r#type {
+            ast::Type::Callable(c) => c.output.r#type.get_concrete_type(),
+            _ => ast::Type::Unknown,
+        };
+
+        if let Some(environment) = function_value.get_function_value().get_environment() {
+            let variables = self
+                .llvm_builder
+                .get_captured_variables(environment, captures.len())?;
+            for ((name, typing), variable) in captures.iter().zip(variables) {
+                context.variables.add(
+                    name.to_owned(),
+                    LLVMValue::new_variable(variable, typing.clone()),
+                );
+            }
+        }
+
         for (value, param) in function_value
             .get_function_value()
             .get_function_parameters()
-            .iter()
+            .into_iter()
             .zip(function.params.iter())
         {
-            context.variables.add(
-                param.name.to_owned(),
-                LLVMValue::new_variable(value.clone(), param.typing.clone()),
-            );
+            let value = match &param.default {
+                Some(default) => self.default_argument(value, default, context)?,
+                None => value,
+            };
+
+            // mutable parameters are captured by reference, so that the caller sees the
+            // updates. Others are copied, their argument may not outlive the call
+            let variable = LLVMValue::new_variable(value, param.typing.clone());
+            let variable = match context.captured.contains(&param.name) && !param.typing.mutable {
+                true => self.llvm_builder.malloc_and_store_value(
+                    self.llvm_builder.load_llvm_value("", &variable)?,
+                    param.typing.clone(),
+                )?,
+                false => variable,
+            };
+
+            context.variables.add(param.name.to_owned(), variable);
         }
 
-        let entry_block = self
-            .llvm_builder
-            .create_function_block(&function_value, "entry");
+        self.declare_ahead(function, context);
 
         for token in function.body.iter() {
             self.statement(token, context)?;
@@ -175,7 +477,49 @@ impl<'ctx> Compiler<'ctx> {
         Ok(entry_block)
     }
 
+    // a missing argument is received as null, the default value is then evaluated
+    fn default_argument(
+        &self,
+        argument: PointerValue<'ctx>,
+        default: &Box<ast::Expr>,
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<PointerValue<'ctx>, CompilerError> {
+        let is_missing = self.llvm_builder.is_null(argument)?;
+
+        let current_block = self.llvm_builder.get_current_block().unwrap();
+        let default_block = self.llvm_builder.create_block("default");
+        let end_block = self.llvm_builder.create_block("end");
+        self.llvm_builder
+            .create_branche(is_missing, default_block, end_block)?;
+
+        self.llvm_builder.set_position_at(default_block);
+        // the function might return its parameter, so it cannot live on its stack
+        let value = match self.expression(default, context)? {
+            v @ LLVMValue::Variable(_) => v,
+            v => self.llvm_builder.malloc_and_store_value(
+                self.llvm_builder.load_llvm_value("", &v)?,
+                v.get_typing(),
+            )?,
+        };
+        let value = value.get_basic_value_enum().into_pointer_value();
+        let default_block = self.llvm_builder.get_current_block().unwrap();
+        self.llvm_builder.goto_block(end_block)?;
+
+        self.llvm_builder.set_position_at(end_block);
+        self.llvm_builder
+            .pointer_phi(&[(argument, current_block), (value, default_block)])
+    }
+
     pub fn add_return_main_function(&self) -> Result<(), CompilerError> {
+        // the program might end on a raise
+        if self
+            .llvm_builder
+            .get_current_block()
+            .is_some_and(|b| b.get_terminator().is_some())
+        {
+            return Ok(());
+        }
+
         self.llvm_builder.return_statement(&LLVMValue::new_value(
             self.llvm_builder.value_int(1),
             ast::Typing::new(true, ast::Type::Int),
@@ -189,6 +533,16 @@ impl<'ctx> Compiler<'ctx> {
         ))
     }
 
+    // functions returning a value never reach their end, the typer made sure of it
+    fn add_implicit_return(&self, function: &ast::Function) -> Result<(), CompilerError> {
+        match &function.typing.r#type {
+            ast::Type::Callable(c) if !matches!(c.output.r#type, ast::Type::None) => {
+                self.llvm_builder.unreachable()
+            }
+            _ => self.add_return_none(),
+        }
+    }
+
     pub fn as_string(&self) -> String {
         self.module.print_to_string().to_string()
     }
@@ -257,11 +611,17 @@ impl<'ctx> Compiler<'ctx> {
             ast::Stmt::Expression(expr) => self.expression_statement(expr, context)?,
             ast::Stmt::Return(ret) => self.return_statement(ret, context)?,
             ast::Stmt::Function(func) => self.function_declaration(func, context)?,
+            ast::Stmt::Class(class) => self.class_declaration(class, context)?,
             ast::Stmt::Var(var) => self.var_declaration(var, context)?,
             ast::Stmt::Condition(cond) => self.if_statement(cond, context)?,
             ast::Stmt::Block(blocks) => self.block(blocks, context)?,
             ast::Stmt::While(whi) => self.while_statement(whi, context)?,
-            ast::Stmt::Assert(_) => unimplemented!(), // TODO!!! self.assert_statement(ass),
+            ast::Stmt::For(r#for) => self.for_statement(r#for, context)?,
+            ast::Stmt::Assert(ass) => self.assert_statement(ass, context)?,
+            ast::Stmt::Raise(raise) => self.raise_statement(raise, context)?,
+            ast::Stmt::Try(r#try) => self.try_statement(r#try, context)?,
+            // imported modules are already part of the program
+            ast::Stmt::Import(_) => (),
         };
 
         Ok(())
@@ -273,29 +633,81 @@ impl<'ctx> Compiler<'ctx> {
         context: &mut CompilerContext<'ctx>,
     ) -> Result<(), CompilerError> {
         let value = self.expression(&token.value, context)?;
+        let value = self
+            .llvm_builder
+            .convert(value, &Typing::new(true, context.output.clone()))?;
         if context.return_as_pointer && value.is_value() {
             let temp_var = self.llvm_builder.allocate_and_store_value(
                 value.as_value(),
                 "",
                 value.get_typing(),
             )?;
-            self.llvm_builder.return_statement(&temp_var)
+            self.return_value(&temp_var, context)
         } else if !context.return_as_pointer && value.is_variable() {
             let temp_var = LLVMValue::new_value(
                 self.llvm_builder.load_llvm_value("", &value)?,
                 value.get_typing(),
             );
-            self.llvm_builder.return_statement(&temp_var)
+            self.return_value(&temp_var, context)
         } else {
-            self.llvm_builder.return_statement(&value)
+            self.return_value(&value, context)
+        }
+    }
+
+    // the enclosing finally blocks run before the function returns
+    fn return_value(
+        &self,
+        value: &LLVMValue<'ctx>,
+        context: &CompilerContext<'ctx>,
+    ) -> Result<(), CompilerError> {
+        match context.finally.last() {
+            Some(finally) => {
+                self.llvm_builder
+                    .store_value(finally.returned, value.get_basic_value_enum())?;
+                self.leave_through(finally, Leave::Returning)
+            }
+            None => self.llvm_builder.return_statement(value),
         }
     }
 
+    fn leave_through(
+        &self,
+        finally: &FinallyContext<'ctx>,
+        leave: Leave,
+    ) -> Result<(), CompilerError> {
+        if self
+            .llvm_builder
+            .get_current_block()
+            .is_some_and(|b| b.get_terminator().is_some())
+        {
+            return Ok(());
+        }
+
+        self.llvm_builder
+            .store_value(finally.state, self.llvm_builder.value_int(leave as i64))?;
+        self.llvm_builder.goto_block(finally.block)
+    }
+
     pub fn call(
         &self,
         token: &ast::Call,
         context: &mut CompilerContext<'ctx>,
     ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        if let ast::ExprBody::Value(ast::Value::Variable(name)) = &token.callee.body {
+            if context.variables.get(name.to_owned()).is_none()
+                && context.functions.get(name.to_owned()).is_none()
+            {
+                if context.classes.get(name).is_some() {
+                    return self.new_object(name, token, context);
+                }
+                return self.builtin_call(name, token, context);
+            }
+        }
+
+        if let ast::ExprBody::GetAttr(attr) = &token.callee.body {
+            return self.method_call(attr, token, context);
+        }
+
         let function_value = self.expression(&token.callee, context)?;
 
         let mut args;
@@ -327,26 +739,287 @@ impl<'ctx> Compiler<'ctx> {
                 BasicMetadataValueEnum::PointerValue(s_fmt.as_pointer_value()),
             )
         } else {
-            args = token
-                .arguments
-                .iter()
-                .map(|e| self.expression(e, context).unwrap())
-                .map(|e| match e {
-                    LLVMValue::RawValue(_) => self
-                        .llvm_builder
-                        .allocate_and_store_value(e.as_value(), "", e.get_typing())
-                        .unwrap(),
-                    o => o,
-                })
-                .map(|e| BasicMetadataValueEnum::from(e.get_variable().get_basic_value_enum()))
-                .collect::<Vec<BasicMetadataValueEnum>>();
+            args = self.arguments(token, &function_value.get_typing(), 0, context)?;
         }
 
-        self.llvm_builder.call(
-            function_value.get_function_value(),
-            &args,
-            function_value.get_name().as_str(),
-        )
+        match function_value.get_name().as_str() {
+            "printf" => self.llvm_builder.call(&function_value, &args),
+            _ => self.call_function(&function_value, &args, context),
+        }
+    }
+
+    // functions receive all of their arguments as pointers, in the order of their parameters.
+    // The `skip` first parameters are passed separately, e.g self for methods
+    fn arguments(
+        &self,
+        token: &ast::Call,
+        typing: &Typing,
+        skip: usize,
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<Vec<BasicMetadataValueEnum<'ctx>>, CompilerError> {
+        let given = token
+            .arguments
+            .iter()
+            .chain(token.keywords.iter().map(|(_, value)| value))
+            .collect::<Vec<&Box<ast::Expr>>>();
+
+        // arguments are evaluated in the order they are written
+        let mut values = Vec::new();
+        for argument in given.iter() {
+            let value = self.expression(argument, context)?;
+            values.push(self.argument(value)?.get_variable().get_basic_value_enum());
+        }
+
+        let ordered = match &typing.r#type {
+            ast::Type::Callable(c) => c
+                .order_arguments(token, skip)
+                .map_err(|e| CompilerError::Unknown(format!("{e:?}")))?,
+            _ => given.iter().map(|a| Some(a.as_ref())).collect(),
+        };
+
+        Ok(ordered
+            .into_iter()
+            .map(|argument| match argument {
+                Some(a) => values[given
+                    .iter()
+                    .position(|g| std::ptr::eq(g.as_ref(), a))
+                    .unwrap()]
+                .into(),
+                // left to its default value
+                None => self.llvm_builder.get_new_ptr().const_null().into(),
+            })
+            .collect())
+    }
+
+    fn argument(&self, value: LLVMValue<'ctx>) -> Result<LLVMValue<'ctx>, CompilerError> {
+        match value {
+            v @ LLVMValue::Variable(_) => Ok(v),
+            v => self.llvm_builder.allocate_and_store_value(
+                self.llvm_builder.load_llvm_value("", &v)?,
+                "",
+                v.get_typing(),
+            ),
+        }
+    }
+
+    fn new_object(
+        &self,
+        class_name: &str,
+        token: &ast::Call,
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let attribute_types = attribute_types(context.classes.get(class_name).unwrap());
+        let object = self.llvm_builder.new_object(&attribute_types)?;
+        let object = LLVMValue::new_value(
+            object.as_basic_value_enum(),
+            Typing::new(true, ast::Type::Object(class_name.to_owned())),
+        );
+
+        if let Some(init) = context.functions.get(format!("{class_name}.__init__")) {
+            let init = init.clone();
+            let this = self.llvm_builder.allocate_and_store_value(
+                object.as_value(),
+                "self",
+                object.get_typing(),
+            )?;
+
+            let mut args = vec![this.get_variable().get_basic_value_enum().into()];
+            args.extend(self.arguments(token, &init.get_typing(), 1, context)?);
+
+            self.call_function(&init, &args, context)?;
+        }
+
+        Ok(object)
+    }
+
+    fn method_call(
+        &self,
+        attr: &ast::GetAttr,
+        token: &ast::Call,
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let method_name = format!("{}.{}", class_name(&attr.object), attr.name);
+        let method = self.get_function(&method_name, context)?;
+
+        let this = self.expression(&attr.object, context)?;
+        let mut args = vec![self.argument(this)?.get_basic_value_enum().into()];
+        args.extend(self.arguments(token, &method.get_typing(), 1, context)?);
+
+        self.call_function(&method, &args, context)
+    }
+
+    fn builtin_call(
+        &self,
+        name: &str,
+        token: &ast::Call,
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        if name == "range" {
+            let (start, stop, step) = self.range_arguments(token, context)?;
+            return self.llvm_builder.new_range(start, stop, step);
+        }
+
+        let value = self.expression(&token.arguments[0], context)?;
+
+        match name {
+            "len" => match value.get_typing().r#type.get_concrete_type() {
+                ast::Type::List(_) => {
+                    let list = self.llvm_builder.load_llvm_value("", &value)?;
+                    let length = self.llvm_builder.list_length(list.into_pointer_value())?;
+                    Ok(LLVMValue::new_value(
+                        length.as_basic_value_enum(),
+                        Typing::new(true, ast::Type::Int),
+                    ))
+                }
+                ast::Type::Dict(_, _) => {
+                    let dict = self.llvm_builder.load_llvm_value("", &value)?;
+                    let length = self.llvm_builder.dict_length(dict.into_pointer_value())?;
+                    Ok(LLVMValue::new_value(
+                        length.as_basic_value_enum(),
+                        Typing::new(true, ast::Type::Int),
+                    ))
+                }
+                ast::Type::Tuple(t) => Ok(LLVMValue::new_value(
+                    self.llvm_builder.value_int(t.len() as i64),
+                    Typing::new(true, ast::Type::Int),
+                )),
+                _ => self
+                    .llvm_builder
+                    .string_length(self.get_builtin("strlen")?, value),
+            },
+            "append" => {
+                let list = self.llvm_builder.load_llvm_value("", &value)?;
+                let item = self.expression(&token.arguments[1], context)?;
+                self.llvm_builder.append_list_item(
+                    self.get_builtin("realloc")?,
+                    list.into_pointer_value(),
+                    self.llvm_builder.load_llvm_value("", &item)?,
+                    &list_item_type(&token.arguments[0]),
+                )?;
+
+                Ok(LLVMValue::new_value(
+                    self.llvm_builder.value_bool(false),
+                    ast::Typing::new(true, ast::Type::None),
+                ))
+            }
+            "pop" => {
+                let list = self
+                    .llvm_builder
+                    .load_llvm_value("", &value)?
+                    .into_pointer_value();
+                let (_, not_empty) = self
+                    .llvm_builder
+                    .list_index(list, self.llvm_builder.value_int(0))?;
+                self.runtime_check(
+                    not_empty,
+                    &token.callee.span,
+                    ("IndexError", "pop from empty list"),
+                    context,
+                )?;
+
+                self.llvm_builder
+                    .pop_list_item(list, &list_item_type(&token.arguments[0]))
+            }
+            "keys" | "values" | "items" => {
+                let (key_type, value_type) = dict_types(&token.arguments[0]);
+
+                // dicts whose keys are unknown are always empty
+                if key_type == ast::Type::Unknown {
+                    return self.llvm_builder.new_list(Vec::new(), &ast::Type::Unknown);
+                }
+
+                let dict = self
+                    .llvm_builder
+                    .load_llvm_value("", &value)?
+                    .into_pointer_value();
+                match name {
+                    "keys" => self.llvm_builder.dict_keys(dict, &key_type),
+                    "values" => self.llvm_builder.dict_values(dict, &value_type),
+                    _ => self.llvm_builder.dict_items(dict, &key_type, &value_type),
+                }
+            }
+            "clone" => {
+                let r#type = token.arguments[0].typing.r#type.get_concrete_type();
+                self.clone_value(value, &r#type, context)
+            }
+            _ => Err(CompilerError::Unknown(format!(
+                "Function {} not found",
+                name
+            ))),
+        }
+    }
+
+    // scalars are copied by value, strings and functions never change so they are shared,
+    // collections and objects are copied along with everything they hold
+    fn clone_value(
+        &self,
+        value: LLVMValue<'ctx>,
+        r#type: &ast::Type,
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let value = self.llvm_builder.load_llvm_value("", &value)?;
+        let copy = match r#type {
+            // collections whose items are unknown are always empty
+            ast::Type::List(item_type) if **item_type == ast::Type::Unknown => {
+                return self.llvm_builder.new_list(Vec::new(), item_type);
+            }
+            ast::Type::Dict(key_type, _) if **key_type == ast::Type::Unknown => self
+                .llvm_builder
+                .new_dict(self.get_builtin("calloc")?)?
+                .as_basic_value_enum(),
+            ast::Type::List(_)
+            | ast::Type::Dict(_, _)
+            | ast::Type::Tuple(_)
+            | ast::Type::Object(_) => self
+                .llvm_builder
+                .call_clone_function(self.clone_function(r#type, context)?, value)?,
+            _ => value,
+        };
+
+        Ok(LLVMValue::new_value(
+            copy,
+            Typing::new(true, r#type.clone()),
+        ))
+    }
+
+    fn clone_function(
+        &self,
+        r#type: &ast::Type,
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<inkwell::values::FunctionValue<'ctx>, CompilerError> {
+        self.llvm_builder
+            .clone_function(&self.module, r#type, |original| match r#type {
+                ast::Type::List(item_type) => {
+                    self.llvm_builder.clone_list(original, item_type, |item| {
+                        self.clone_value(item, item_type, context)
+                    })
+                }
+                ast::Type::Dict(key_type, value_type) => self.llvm_builder.clone_dict(
+                    self.get_builtin("calloc")?,
+                    self.dict_set_function(key_type, value_type)?,
+                    original,
+                    key_type,
+                    value_type,
+                    |value| self.clone_value(value, value_type, context),
+                ),
+                ast::Type::Tuple(item_types) => {
+                    self.llvm_builder
+                        .clone_record(original, item_types, |item, item_type| {
+                            self.clone_value(item, item_type, context)
+                        })
+                }
+                ast::Type::Object(name) => {
+                    let attributes = context.classes.get(name).ok_or_else(|| {
+                        CompilerError::Unknown(format!("Class {} not found", name))
+                    })?;
+                    let attribute_types = attribute_types(attributes);
+                    self.llvm_builder
+                        .clone_record(original, &attribute_types, |item, item_type| {
+                            self.clone_value(item, item_type, context)
+                        })
+                }
+                t => unreachable!("Not a collection: {t}"),
+            })
     }
 
     fn if_statement(
@@ -386,66 +1059,636 @@ impl<'ctx> Compiler<'ctx> {
         Ok(())
     }
 
-    // fn assert_statement(
-    //     &self,
-    //     token: &ast::Assert,
-    //     store: &mut Store<'ctx>,
-    // ) -> Result<LLVMValue<'ctx>, CompilerError> {
-    //     log::debug!("Starting assert statement");
-    //     self.expression(&token.value)?;
-    //     self.emit_op_code(OpCode::AssertTrue(ItemReference::new(Some(
-    //         token.value.span.clone(),
-    //     ))));
-    //     self.emit_op_code(OpCode::Pop);
-    //     Ok(())
-    // }
+    fn assert_statement(
+        &self,
+        token: &ast::Assert,
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<(), CompilerError> {
+        log::debug!("Starting assert statement");
 
-    fn while_statement(
+        let value = self.expression(&token.value, context)?;
+        let truthy = self.llvm_builder.is_truthy(value)?;
+
+        self.runtime_check(
+            truthy.as_value().into_int_value(),
+            &token.value.span,
+            ("AssertionError", ""),
+            context,
+        )
+    }
+
+    fn raise_statement(
         &self,
-        token: &ast::While,
+        token: &ast::Raise,
         context: &mut CompilerContext<'ctx>,
     ) -> Result<(), CompilerError> {
-        log::debug!("Starting while statement");
+        let kind = match &token.kind {
+            Some(kind) => kind,
+            // the error keeps the line it was first raised from
+            None => {
+                let error = *context.handled.last().ok_or_else(|| {
+                    CompilerError::SyntaxError(format!("No error to raise again"))
+                })?;
+                self.store_error(error)?;
+                return self.llvm_builder.goto_block(context.raised.unwrap());
+            }
+        };
 
-        let cond_block = self.llvm_builder.create_block("cond");
-        let end_block = self.llvm_builder.create_block("end");
-        let loop_block = self.llvm_builder.create_block("loop");
+        let message = match &token.message {
+            Some(message) => {
+                let message = self.expression(message, context)?;
+                self.llvm_builder.load_llvm_value("", &message)?
+            }
+            None => self
+                .llvm_builder
+                .global_string("error_message", "")?
+                .as_pointer_value()
+                .as_basic_value_enum(),
+        };
 
-        context
-            .loop_context
-            .push(LoopContext::new(cond_block.clone(), end_block.clone()));
+        self.raise_error(kind, message, &token.span, context)
+    }
 
-        self.llvm_builder.goto_block(cond_block)?;
+    // `try` runs its body with the except blocks as the place errors go to. The finally
+    // block is run on every way out, `state` telling where to go next
+    fn try_statement(
+        &self,
+        token: &ast::Try,
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<(), CompilerError> {
+        let outer_raised = context.raised.unwrap();
+        let end_block = self.llvm_builder.create_block("try_end");
+        let except_block = self.llvm_builder.create_block("except");
+
+        let finally = match token.finally {
+            Some(_) => {
+                let state = self
+                    .llvm_builder
+                    .allocate_variable("state", Typing::new(true, ast::Type::Int))?
+                    .get_basic_value_enum()
+                    .into_pointer_value();
+                let error = self
+                    .llvm_builder
+                    .allocate_variable("error", Typing::new(true, ast::Type::String))?
+                    .get_basic_value_enum()
+                    .into_pointer_value();
+                let finally = FinallyContext {
+                    block: self.llvm_builder.create_block("finally"),
+                    state,
+                    returned: self.llvm_builder.allocate_return_value()?,
+                    error,
+                    loops: context.loop_context.len(),
+                };
+                context.finally.push(finally.clone());
+                Some(finally)
+            }
+            None => None,
+        };
 
-        self.llvm_builder.set_position_at(cond_block);
-        let cond = self.expression(&token.condition, context)?;
-        self.llvm_builder
-            .create_branche(cond, loop_block, end_block)?;
+        // errors raised by the except blocks go through the finally block
+        let handlers_raised = match &finally {
+            Some(finally) => {
+                let raised = self.llvm_builder.create_block("finally_raised");
+                let current_block = self.llvm_builder.get_current_block().unwrap();
 
-        self.llvm_builder.set_position_at(loop_block);
+                self.llvm_builder.set_position_at(raised);
+                let error = self.load_error()?;
+                self.llvm_builder
+                    .store_value(finally.error, error.as_basic_value_enum())?;
+                self.store_error(self.llvm_builder.get_new_ptr().const_null())?;
+                self.leave_through(finally, Leave::Raising)?;
+
+                self.llvm_builder.set_position_at(current_block);
+                raised
+            }
+            None => outer_raised,
+        };
+
+        context.raised = Some(except_block);
         self.statement(&token.body, context)?;
-        self.llvm_builder.goto_block(cond_block)?;
+        self.leave(&finally, end_block)?;
 
-        self.llvm_builder.set_position_at(end_block);
+        context.raised = Some(handlers_raised);
+        self.llvm_builder.set_position_at(except_block);
+        let error = self.load_error()?;
+        let kind = self
+            .llvm_builder
+            .get_tuple_item(error, 0, &error_record())?
+            .as_value()
+            .into_int_value();
+
+        for handler in token.handlers.iter() {
+            let handler_block = self.llvm_builder.create_block("handler");
+            let next_block = self.llvm_builder.create_block("next_handler");
+
+            match handler.kinds.iter().any(|k| k == "Exception") || handler.kinds.is_empty() {
+                true => self.llvm_builder.goto_block(handler_block)?,
+                false => {
+                    let cases = handler
+                        .kinds
+                        .iter()
+                        .map(|k| (error_kind(k) as u64, handler_block))
+                        .collect::<Vec<(u64, BasicBlock<'ctx>)>>();
+                    self.llvm_builder.switch(kind, next_block, &cases)?;
+                }
+            }
 
-        _ = context.loop_context.pop();
+            self.llvm_builder.set_position_at(handler_block);
+            self.store_error(self.llvm_builder.get_new_ptr().const_null())?;
+            if let Some(name) = &handler.name {
+                let message = self
+                    .llvm_builder
+                    .get_tuple_item(error, 2, &error_record())?;
+                let variable = match context.captured.contains(name) {
+                    true => self
+                        .llvm_builder
+                        .malloc_and_store_value(message.as_value(), message.get_typing())?,
+                    false => self.llvm_builder.allocate_and_store_value(
+                        message.as_value(),
+                        name,
+                        message.get_typing(),
+                    )?,
+                };
+                context.variables.add(name.clone(), variable);
+            }
+
+            context.handled.push(error);
+            self.statement(&handler.body, context)?;
+            context.handled.pop();
+            self.leave(&finally, end_block)?;
+
+            self.llvm_builder.set_position_at(next_block);
+        }
+
+        // no except block handles the error
+        self.llvm_builder.goto_block(handlers_raised)?;
+        context.raised = Some(outer_raised);
+
+        if let Some(finally) = finally {
+            context.finally.pop();
+            self.finally_statement(
+                token.finally.as_ref().unwrap(),
+                &finally,
+                end_block,
+                context,
+            )?;
+        }
 
+        self.llvm_builder.set_position_at(end_block);
         Ok(())
     }
 
-    fn function_declaration(
+    // leaves the body of a try statement, or one of its except blocks, once it ran fully
+    fn leave(
         &self,
-        token: &ast::Function,
-        context: &mut CompilerContext<'ctx>,
+        finally: &Option<FinallyContext<'ctx>>,
+        end_block: BasicBlock<'ctx>,
     ) -> Result<(), CompilerError> {
-        log::debug!("Starting function declaration");
-
+        match finally {
+            Some(finally) => self.leave_through(finally, Leave::Normally),
+            None => self.llvm_builder.goto_block(end_block),
+        }
+    }
+
+    fn finally_statement(
+        &self,
+        token: &ast::Stmt,
+        finally: &FinallyContext<'ctx>,
+        end_block: BasicBlock<'ctx>,
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<(), CompilerError> {
+        self.llvm_builder.set_position_at(finally.block);
+        self.statement(token, context)?;
+
+        if self
+            .llvm_builder
+            .get_current_block()
+            .is_some_and(|b| b.get_terminator().is_some())
+        {
+            return Ok(());
+        }
+
+        let state = self
+            .llvm_builder
+            .load_llvm_value(
+                "",
+                &LLVMValue::new_variable(finally.state, Typing::new(true, ast::Type::Int)),
+            )?
+            .into_int_value();
+
+        let raising_block = self.llvm_builder.create_block("finally_raising");
+        let returning_block = self.llvm_builder.create_block("finally_returning");
+        let mut cases = vec![
+            (Leave::Raising as u64, raising_block),
+            (Leave::Returning as u64, returning_block),
+        ];
+        if let Some(r#loop) = context.loop_context.last() {
+            if finally.loops == context.loop_context.len() {
+                cases.push((Leave::Breaking as u64, r#loop.end));
+                cases.push((Leave::Continuing as u64, r#loop.cond));
+            }
+        }
+        self.llvm_builder.switch(state, end_block, &cases)?;
+
+        // the error raised before the finally block goes on
+        self.llvm_builder.set_position_at(raising_block);
+        let error = self.llvm_builder.load_pointer(finally.error)?;
+        self.store_error(error)?;
+        self.llvm_builder.goto_block(context.raised.unwrap())?;
+
+        self.llvm_builder.set_position_at(returning_block);
+        let returned = self.llvm_builder.load_return_value(finally.returned)?;
+        self.return_value(
+            &LLVMValue::new_value(returned, Typing::new(true, ast::Type::Unknown)),
+            context,
+        )
+    }
+
+    // raises an error when the condition is false
+    fn runtime_check(
+        &self,
+        condition: inkwell::values::IntValue<'ctx>,
+        span: &Span,
+        (kind, message): (&str, &str),
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<(), CompilerError> {
+        let failed_block = self.llvm_builder.create_block("check_failed");
+        let end_block = self.llvm_builder.create_block("check_end");
+
+        self.llvm_builder.create_branche(
+            LLVMValue::new_value(
+                condition.as_basic_value_enum(),
+                Typing::new(true, ast::Type::Bool),
+            ),
+            end_block,
+            failed_block,
+        )?;
+
+        self.llvm_builder.set_position_at(failed_block);
+        let message = self.llvm_builder.global_string("error_message", message)?;
+        self.raise_error(
+            kind,
+            message.as_pointer_value().as_basic_value_enum(),
+            span,
+            context,
+        )?;
+
+        self.llvm_builder.set_position_at(end_block);
+
+        Ok(())
+    }
+
+    // the error is kept aside until an except block handles it, the functions it goes
+    // through return right away
+    fn raise_error(
+        &self,
+        kind: &str,
+        message: inkwell::values::BasicValueEnum<'ctx>,
+        span: &Span,
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<(), CompilerError> {
+        let name = self.llvm_builder.global_string("error_kind", kind)?;
+        let location = self
+            .llvm_builder
+            .global_string("error_location", &span.format(self.content, ""))?;
+
+        let error = self.llvm_builder.new_tuple(
+            vec![
+                self.llvm_builder.value_int(error_kind(kind) as i64),
+                name.as_pointer_value().as_basic_value_enum(),
+                message,
+                location.as_pointer_value().as_basic_value_enum(),
+            ],
+            &error_record(),
+        )?;
+        self.store_error(error.as_value().into_pointer_value())?;
+
+        self.llvm_builder.goto_block(context.raised.unwrap())
+    }
+
+    // called functions might have raised an error
+    fn check_raised(&self, context: &mut CompilerContext<'ctx>) -> Result<(), CompilerError> {
+        let no_error = self.llvm_builder.is_null(self.load_error()?)?;
+        let next_block = self.llvm_builder.create_block("no_error");
+
+        self.llvm_builder
+            .create_branche(no_error, next_block, context.raised.unwrap())?;
+        self.llvm_builder.set_position_at(next_block);
+
+        Ok(())
+    }
+
+    fn call_function(
+        &self,
+        function: &LLVMValue<'ctx>,
+        args: &[BasicMetadataValueEnum<'ctx>],
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let value = self.llvm_builder.call(function, args)?;
+        self.check_raised(context)?;
+        Ok(value)
+    }
+
+    fn load_error(&self) -> Result<PointerValue<'ctx>, CompilerError> {
+        self.llvm_builder.load_pointer(self.error_global())
+    }
+
+    fn store_error(&self, error: PointerValue<'ctx>) -> Result<(), CompilerError> {
+        self.llvm_builder
+            .store_value(self.error_global(), error.as_basic_value_enum())
+    }
+
+    fn error_global(&self) -> PointerValue<'ctx> {
+        self.module
+            .get_global(ERROR_GLOBAL)
+            .unwrap()
+            .as_pointer_value()
+    }
+
+    // prints the error with the line raising it, and stops the program
+    fn uncaught_error(&self) -> Result<(), CompilerError> {
+        let error = self.load_error()?;
+        let field = |position| -> Result<_, CompilerError> {
+            Ok(self
+                .llvm_builder
+                .get_tuple_item(error, position, &error_record())?
+                .as_value())
+        };
+
+        self.llvm_builder.exit_with_error(
+            self.get_builtin("printf")?,
+            self.get_builtin("exit")?,
+            self.get_builtin("strlen")?,
+            field(3)?,
+            field(1)?,
+            field(2)?,
+        )
+    }
+
+    fn get_builtin(
+        &self,
+        name: &str,
+    ) -> Result<inkwell::values::FunctionValue<'ctx>, CompilerError> {
+        self.module
+            .get_function(name)
+            .ok_or_else(|| CompilerError::Unknown(format!("{name} is not declared")))
+    }
+
+    fn while_statement(
+        &self,
+        token: &ast::While,
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<(), CompilerError> {
+        log::debug!("Starting while statement");
+
+        let cond_block = self.llvm_builder.create_block("cond");
+        let end_block = self.llvm_builder.create_block("end");
+        let loop_block = self.llvm_builder.create_block("loop");
+
+        context
+            .loop_context
+            .push(LoopContext::new(cond_block.clone(), end_block.clone()));
+
+        self.llvm_builder.goto_block(cond_block)?;
+
+        self.llvm_builder.set_position_at(cond_block);
+        let cond = self.expression(&token.condition, context)?;
+        self.llvm_builder
+            .create_branche(cond, loop_block, end_block)?;
+
+        self.llvm_builder.set_position_at(loop_block);
+        self.statement(&token.body, context)?;
+        self.llvm_builder.goto_block(cond_block)?;
+
+        self.llvm_builder.set_position_at(end_block);
+
+        _ = context.loop_context.pop();
+
+        Ok(())
+    }
+
+    fn for_statement(
+        &self,
+        token: &ast::For,
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<(), CompilerError> {
+        log::debug!("Starting for statement");
+
+        let cond_block = self.llvm_builder.create_block("for_cond");
+        let loop_block = self.llvm_builder.create_block("for_loop");
+        let step_block = self.llvm_builder.create_block("for_step");
+        let else_block = self.llvm_builder.create_block("for_else");
+        let end_block = self.llvm_builder.create_block("end");
+
+        // ranges are iterated over without building their list
+        let (range, list) = match &token.iterable.body {
+            ast::ExprBody::Call(call) if self.is_builtin(&call.callee, "range", context) => {
+                (Some(self.range_arguments(call, context)?), None)
+            }
+            _ => {
+                let iterable = self.expression(&token.iterable, context)?;
+                let iterable = self
+                    .llvm_builder
+                    .load_llvm_value("", &iterable)?
+                    .into_pointer_value();
+
+                // an iterable whose items are unknown is always empty
+                let list = match token.iterable.typing.r#type.get_concrete_type() {
+                    _ if token.typing.r#type == ast::Type::Unknown => None,
+                    ast::Type::Dict(_, _) => Some(
+                        self.llvm_builder
+                            .dict_keys(iterable, &token.typing.r#type)?
+                            .as_value()
+                            .into_pointer_value(),
+                    ),
+                    _ => Some(iterable),
+                };
+                (None, list)
+            }
+        };
+
+        let (start, step) = match range {
+            Some((start, _, step)) => (start, step),
+            None => (
+                self.llvm_builder.value_int(0).into_int_value(),
+                self.llvm_builder.value_int(1).into_int_value(),
+            ),
+        };
+        let counter = self.llvm_builder.allocate_and_store_value(
+            start.as_basic_value_enum(),
+            "counter",
+            Typing::new(true, ast::Type::Int),
+        )?;
+
+        let variable = match range.is_some() || list.is_some() {
+            true if context.captured.contains(&token.name) => Some(
+                self.llvm_builder
+                    .malloc_variable(&token.name, token.typing.clone())?,
+            ),
+            true => Some(
+                self.llvm_builder
+                    .allocate_variable(&token.name, token.typing.clone())?,
+            ),
+            false => None,
+        };
+        if let Some(variable) = &variable {
+            context
+                .variables
+                .add(token.name.to_owned(), variable.clone());
+        }
+
+        self.llvm_builder.goto_block(cond_block)?;
+        self.llvm_builder.set_position_at(cond_block);
+
+        let current = self
+            .llvm_builder
+            .load_llvm_value("", &counter)?
+            .into_int_value();
+        let has_next = match (range, list) {
+            (Some((_, stop, step)), _) => self.llvm_builder.range_has_next(current, stop, step)?,
+            (None, Some(list)) => {
+                // the length is read again on each iteration as the list may change
+                let (_, in_bounds) = self
+                    .llvm_builder
+                    .list_index(list, current.as_basic_value_enum())?;
+                in_bounds
+            }
+            (None, None) => self.llvm_builder.value_bool(false).into_int_value(),
+        };
+        self.llvm_builder.create_branche(
+            LLVMValue::new_value(
+                has_next.as_basic_value_enum(),
+                Typing::new(true, ast::Type::Bool),
+            ),
+            loop_block,
+            else_block,
+        )?;
+
+        self.llvm_builder.set_position_at(loop_block);
+        if let Some(variable) = &variable {
+            let value = match list {
+                Some(list) => self
+                    .llvm_builder
+                    .get_list_item(list, current, &token.typing.r#type)?
+                    .as_value(),
+                None => current.as_basic_value_enum(),
+            };
+
+            self.llvm_builder
+                .store_value(variable.as_pointer(), value)?;
+
+            context
+                .loop_context
+                .push(LoopContext::new(step_block, end_block));
+            self.statement(&token.body, context)?;
+            _ = context.loop_context.pop();
+        }
+        self.llvm_builder.goto_block(step_block)?;
+
+        self.llvm_builder.set_position_at(step_block);
+        let next = self.llvm_builder.add(
+            LLVMValue::new_value(
+                current.as_basic_value_enum(),
+                Typing::new(true, ast::Type::Int),
+            ),
+            LLVMValue::new_value(
+                step.as_basic_value_enum(),
+                Typing::new(true, ast::Type::Int),
+            ),
+        )?;
+        self.llvm_builder
+            .store_value(counter.as_pointer(), next.as_value())?;
+        self.llvm_builder.goto_block(cond_block)?;
+
+        // break jumps straight to the end, skipping the else clause
+        self.llvm_builder.set_position_at(else_block);
+        if let Some(r#else) = &token.r#else {
+            self.statement(r#else, context)?;
+        }
+        self.llvm_builder.goto_block(end_block)?;
+
+        self.llvm_builder.set_position_at(end_block);
+
+        Ok(())
+    }
+
+    // range(stop), range(start, stop) or range(start, stop, step)
+    fn range_arguments(
+        &self,
+        token: &ast::Call,
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<(IntValue<'ctx>, IntValue<'ctx>, IntValue<'ctx>), CompilerError> {
+        let mut arguments = Vec::new();
+        for argument in token.arguments.iter() {
+            let value = self.expression(argument, context)?;
+            let value = self.llvm_builder.load_llvm_value("", &value)?;
+            arguments.push(self.llvm_builder.to_int(value)?.into_int_value());
+        }
+
+        let zero = self.llvm_builder.value_int(0).into_int_value();
+        let one = self.llvm_builder.value_int(1).into_int_value();
+
+        let (start, stop, step) = match arguments.as_slice() {
+            [stop] => (zero, *stop, one),
+            [start, stop] => (*start, *stop, one),
+            [start, stop, step] => (*start, *stop, *step),
+            _ => unreachable!("range takes 1 to 3 arguments"),
+        };
+
+        let step_is_not_zero = self.llvm_builder.not_equal(
+            LLVMValue::new_value(
+                step.as_basic_value_enum(),
+                Typing::new(true, ast::Type::Int),
+            ),
+            LLVMValue::new_value(
+                zero.as_basic_value_enum(),
+                Typing::new(true, ast::Type::Int),
+            ),
+        )?;
+        self.runtime_check(
+            step_is_not_zero.as_value().into_int_value(),
+            &token.callee.span,
+            ("ValueError", "range() arg 3 must not be zero"),
+            context,
+        )?;
+
+        Ok((start, stop, step))
+    }
+
+    // builtins are only used when not shadowed by a variable or a function
+    fn is_builtin(
+        &self,
+        callee: &ast::Expr,
+        builtin: &str,
+        context: &CompilerContext<'ctx>,
+    ) -> bool {
+        match &callee.body {
+            ast::ExprBody::Value(ast::Value::Variable(name)) => {
+                name == builtin
+                    && context.variables.get(name.to_owned()).is_none()
+                    && context.functions.get(name.to_owned()).is_none()
+            }
+            _ => false,
+        }
+    }
+
+    fn function_declaration(
+        &self,
+        token: &ast::Function,
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<(), CompilerError> {
+        log::debug!("Starting function declaration");
+
         let previous_block = self.llvm_builder.get_current_block().unwrap();
 
-        if token.name != "main" {
-            let mut new_context = context.clone();
-            new_context.return_as_pointer = token.typing.return_as_pointer().unwrap();
-            self.compile(token, &mut new_context)?;
+        // the variables of the enclosing function only exist through the environment
+        let mut new_context = context.clone();
+        new_context.variables = Variables::new();
+        new_context.return_as_pointer = token.typing.return_as_pointer().unwrap();
+
+        let captures = self.captures(token, context);
+        if captures.is_empty() {
+            self.compile_function(token, &mut new_context)?;
             context.functions.add(
                 token.name.clone(),
                 new_context
@@ -454,19 +1697,124 @@ impl<'ctx> Compiler<'ctx> {
                     .unwrap()
                     .clone(),
             );
+            context.variables.remove(&token.name);
         } else {
-            self.compile(token, context)?;
+            self.closure_declaration(token, captures, context, &mut new_context)?;
         }
 
         let last_block = self.llvm_builder.get_current_block().unwrap();
         if let None = last_block.get_terminator() {
-            self.add_return_none()?;
+            self.add_implicit_return(token)?;
         }
 
         self.llvm_builder.set_position_at(previous_block);
         Ok(())
     }
 
+    // variables of the enclosing function the function refers to
+    fn captures(&self, token: &ast::Function, context: &CompilerContext<'ctx>) -> Vec<String> {
+        let free_variables = free_variables(token);
+        let mut captures = free_variables
+            .iter()
+            .filter(|name| {
+                **name != token.name && context.variables.get(name.to_string()).is_some()
+            })
+            .cloned()
+            .collect::<Vec<String>>();
+
+        // a closure calling itself does so through its own variable
+        if !captures.is_empty() && free_variables.contains(&token.name) {
+            captures.push(token.name.clone());
+        }
+
+        captures
+    }
+
+    // a function capturing variables is a closure, stored in a variable of the same name
+    fn closure_declaration(
+        &self,
+        token: &ast::Function,
+        captures: Vec<String>,
+        context: &mut CompilerContext<'ctx>,
+        new_context: &mut CompilerContext<'ctx>,
+    ) -> Result<(), CompilerError> {
+        let function_value = self.llvm_builder.declare_user_function(token, &self.module);
+
+        let variable = match context.captured.contains(&token.name) {
+            true => self
+                .llvm_builder
+                .malloc_variable(&token.name, token.typing.clone())?,
+            false => self
+                .llvm_builder
+                .allocate_variable(&token.name, token.typing.clone())?,
+        };
+        context.variables.add(token.name.clone(), variable.clone());
+
+        let captures = captures
+            .into_iter()
+            .map(|name| {
+                let captured = context.variables.get(name.clone()).unwrap().clone();
+                (name, captured)
+            })
+            .collect::<Vec<(String, LLVMValue<'ctx>)>>();
+
+        let closure = self.llvm_builder.new_closure(
+            &function_value,
+            &captures
+                .iter()
+                .map(|(_, v)| v.as_pointer())
+                .collect::<Vec<PointerValue>>(),
+        )?;
+        self.llvm_builder
+            .store_value(variable.as_pointer(), closure.as_basic_value_enum())?;
+
+        let captures = captures
+            .into_iter()
+            .map(|(name, v)| (name, v.get_typing()))
+            .collect::<Vec<(String, Typing)>>();
+        self.compile_body(token, &function_value, &captures, new_context)?;
+
+        Ok(())
+    }
+
+    fn class_declaration(
+        &self,
+        token: &ast::Class,
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<(), CompilerError> {
+        context
+            .classes
+            .add(token.name.clone(), token.attributes.clone());
+
+        // methods are declared upfront so that they can call each other
+        let methods = token
+            .methods
+            .iter()
+            .map(|m| {
+                let method = self.llvm_builder.declare_user_function(m, &self.module);
+                context.functions.add(m.name.clone(), method.clone());
+                method
+            })
+            .collect::<Vec<LLVMValue>>();
+
+        for (method, method_value) in token.methods.iter().zip(methods.iter()) {
+            let previous_block = self.llvm_builder.get_current_block().unwrap();
+
+            let mut new_context = context.clone();
+            new_context.return_as_pointer = method.typing.return_as_pointer().unwrap();
+            self.compile_body(method, method_value, &[], &mut new_context)?;
+
+            let last_block = self.llvm_builder.get_current_block().unwrap();
+            if let None = last_block.get_terminator() {
+                self.add_implicit_return(method)?;
+            }
+
+            self.llvm_builder.set_position_at(previous_block);
+        }
+
+        Ok(())
+    }
+
     fn block(
         &self,
         token: &Vec<ast::Stmt>,
@@ -485,7 +1833,15 @@ impl<'ctx> Compiler<'ctx> {
         context: &mut CompilerContext<'ctx>,
     ) -> Result<(), CompilerError> {
         let value = self.expression(&token.value, context)?;
-        let var_ptr = self.llvm_builder.declare_variable(token, value)?;
+        let value = self.llvm_builder.convert(value, &token.typing)?;
+        // captured variables live on the heap, closures may outlive the function
+        let var_ptr = match context.captured.contains(&token.name) {
+            true => self.llvm_builder.malloc_and_store_value(
+                self.llvm_builder.load_llvm_value("", &value)?,
+                token.typing.clone(),
+            )?,
+            false => self.llvm_builder.declare_variable(token, value)?,
+        };
         context.variables.add(token.name.to_owned(), var_ptr);
         Ok(())
     }
@@ -510,12 +1866,22 @@ impl<'ctx> Compiler<'ctx> {
                 self.value(t, ItemReference::new(Some(token.span.clone())), context)
             }
             ast::ExprBody::Binary(t) => self.binary(t, context),
+            ast::ExprBody::Comparison(c) => self.comparison(c, context),
             ast::ExprBody::Call(t) => self.call(t, context),
             ast::ExprBody::Assign(t) => self.assign(t, context),
             ast::ExprBody::Grouping(t) => self.grouping(t, context),
             ast::ExprBody::Unary(t) => self.unary(t, context),
             ast::ExprBody::Logical(t) => self.logical(t, context),
             ast::ExprBody::LoopKeyword(t) => self.loop_keyword(t, context),
+            ast::ExprBody::List(t) => self.list(t, &token.typing, context),
+            ast::ExprBody::Dict(t) => self.dict(t, &token.typing, context),
+            ast::ExprBody::Tuple(t) => self.tuple(t, &token.typing, context),
+            ast::ExprBody::FormatString(f) => self.format_string(f, context),
+            ast::ExprBody::GetItem(t) => self.get_item(t, &token.span, context),
+            ast::ExprBody::SetItem(t) => self.set_item(t, &token.span, context),
+            ast::ExprBody::GetAttr(t) => self.get_attr(t, context),
+            ast::ExprBody::SetAttr(t) => self.set_attr(t, context),
+            ast::ExprBody::AugmentedAssign(t) => self.augmented_assign(t, &token.span, context),
         }
     }
 
@@ -554,7 +1920,8 @@ impl<'ctx> Compiler<'ctx> {
         let merge_block = self.llvm_builder.create_block("merge");
 
         let expression1 = self.expression(&token.left, context)?;
-        let value = self.llvm_builder.allocate(expression1.clone())?;
+        let value1 = self.llvm_builder.load_llvm_value("", &expression1)?;
+        let value = self.llvm_builder.allocate(value1)?;
 
         let expression_1_truthy = self.llvm_builder.is_truthy(expression1.clone())?;
 
@@ -562,14 +1929,13 @@ impl<'ctx> Compiler<'ctx> {
             .create_branche(expression_1_truthy, first_block, second_block)?;
 
         self.llvm_builder.set_position_at(first_block);
-        self.llvm_builder
-            .store_value(value, expression1.get_basic_value_enum())?;
+        self.llvm_builder.store_value(value, value1)?;
         self.llvm_builder.goto_block(merge_block)?;
 
         self.llvm_builder.set_position_at(second_block);
         let expression2 = self.expression(&token.right, context)?;
-        self.llvm_builder
-            .store_value(value, expression2.get_basic_value_enum())?;
+        let value2 = self.llvm_builder.load_llvm_value("", &expression2)?;
+        self.llvm_builder.store_value(value, value2)?;
         self.llvm_builder.goto_block(merge_block)?;
 
         self.llvm_builder.set_position_at(merge_block);
@@ -582,6 +1948,36 @@ impl<'ctx> Compiler<'ctx> {
         Ok(LLVMValue::new_value(result, expression1.get_typing()))
     }
 
+    // the whole string is built by a single snprintf call
+    fn format_string(
+        &self,
+        token: &ast::FormatString,
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let mut format = String::new();
+        let mut values = Vec::new();
+
+        for part in token.parts.iter() {
+            match part {
+                ast::FormatPart::Literal(l) => format.push_str(&l.replace('%', "%%")),
+                ast::FormatPart::Value(expr, spec) => {
+                    let value = self.expression(expr, context)?;
+                    let (directive, value) = self.llvm_builder.format_field(
+                        self.get_builtin("snprintf")?,
+                        self.get_builtin("strlen")?,
+                        value,
+                        spec,
+                    )?;
+                    format.push_str(&directive);
+                    values.push(value);
+                }
+            }
+        }
+
+        self.llvm_builder
+            .format_string(self.get_builtin("snprintf")?, &format, &values)
+    }
+
     fn assign(
         &self,
         token: &ast::Assign,
@@ -589,6 +1985,7 @@ impl<'ctx> Compiler<'ctx> {
     ) -> Result<LLVMValue<'ctx>, CompilerError> {
         let expr = self.expression(&token.value, context)?;
         let variable = context.variables.get(token.name.clone()).unwrap();
+        let expr = self.llvm_builder.convert(expr, &variable.get_typing())?;
 
         match &expr {
             LLVMValue::RawValue(_) => self
@@ -596,7 +1993,7 @@ impl<'ctx> Compiler<'ctx> {
                 .store_value(variable.as_pointer(), expr.as_value())?,
             LLVMValue::Variable(_) => self.llvm_builder.store_value(
                 variable.as_pointer(),
-                expr.as_pointer().as_basic_value_enum(),
+                self.llvm_builder.load_llvm_value("", &expr)?,
             )?,
             LLVMValue::Function(_) => self.llvm_builder.store_value(
                 variable.as_pointer(),
@@ -641,23 +2038,444 @@ impl<'ctx> Compiler<'ctx> {
             ast::Value::Variable(s) => self
                 .get_variable(&s, context)
                 .or_else(|_| self.get_function(&s, context)),
-            ast::Value::String(s) => Ok(LLVMValue::new_variable(
-                self.llvm_builder.global_string("", s)?.as_pointer_value(),
+            ast::Value::String(s) => Ok(LLVMValue::new_value(
+                self.llvm_builder
+                    .global_string("", s)?
+                    .as_pointer_value()
+                    .as_basic_value_enum(),
                 Typing::new(false, ast::Type::String),
             )),
         }
     }
 
+    fn list(
+        &self,
+        token: &ast::List,
+        typing: &Typing,
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let mut items = Vec::new();
+        for item in token.items.iter() {
+            let value = self.expression(item, context)?;
+            items.push(self.llvm_builder.load_llvm_value("", &value)?);
+        }
+
+        let item_type = match &typing.r#type {
+            ast::Type::List(t) => t.as_ref(),
+            t => unreachable!("Not a list: {t}"),
+        };
+
+        self.llvm_builder.new_list(items, item_type)
+    }
+
+    fn dict(
+        &self,
+        token: &ast::Dict,
+        typing: &Typing,
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let dict = self.llvm_builder.new_dict(self.get_builtin("calloc")?)?;
+
+        if let ast::Type::Dict(key_type, value_type) = &typing.r#type {
+            for (key, value) in token.items.iter() {
+                let key = self.expression(key, context)?;
+                let value = self.expression(value, context)?;
+                self.llvm_builder.set_dict_item(
+                    self.dict_set_function(key_type, value_type)?,
+                    dict,
+                    self.llvm_builder.load_llvm_value("", &key)?,
+                    self.llvm_builder.load_llvm_value("", &value)?,
+                    key_type,
+                    value_type,
+                )?;
+            }
+        }
+
+        Ok(LLVMValue::new_value(
+            dict.as_basic_value_enum(),
+            typing.clone(),
+        ))
+    }
+
+    fn tuple(
+        &self,
+        token: &ast::Tuple,
+        typing: &Typing,
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let mut items = Vec::new();
+        for item in token.items.iter() {
+            let value = self.expression(item, context)?;
+            items.push(self.llvm_builder.load_llvm_value("", &value)?);
+        }
+
+        let item_types = match &typing.r#type {
+            ast::Type::Tuple(t) => t,
+            t => unreachable!("Not a tuple: {t}"),
+        };
+
+        self.llvm_builder.new_tuple(items, item_types)
+    }
+
+    fn list_index(
+        &self,
+        object: &Box<ast::Expr>,
+        index: &Box<ast::Expr>,
+        span: &Span,
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<
+        (
+            inkwell::values::PointerValue<'ctx>,
+            inkwell::values::IntValue<'ctx>,
+        ),
+        CompilerError,
+    > {
+        let list = self.expression(object, context)?;
+        let list = self
+            .llvm_builder
+            .load_llvm_value("", &list)?
+            .into_pointer_value();
+        let index = self.expression(index, context)?;
+
+        let (index, in_bounds) = self
+            .llvm_builder
+            .list_index(list, self.llvm_builder.load_llvm_value("", &index)?)?;
+        self.runtime_check(
+            in_bounds,
+            span,
+            ("IndexError", "list index out of range"),
+            context,
+        )?;
+
+        Ok((list, index))
+    }
+
+    fn get_item(
+        &self,
+        token: &ast::GetItem,
+        span: &Span,
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        match token.object.typing.r#type.get_concrete_type() {
+            ast::Type::Dict(_, value_type) => {
+                let (dict, entry) = self.dict_entry(&token.object, &token.index, context)?;
+                let has_entry = self.llvm_builder.has_dict_entry(entry)?;
+                self.runtime_check(
+                    has_entry,
+                    span,
+                    ("KeyError", "key not found in dict"),
+                    context,
+                )?;
+
+                self.llvm_builder.get_dict_value(dict, entry, &value_type)
+            }
+            ast::Type::Tuple(item_types) => {
+                let tuple = self.expression(&token.object, context)?;
+                let tuple = self
+                    .llvm_builder
+                    .load_llvm_value("", &tuple)?
+                    .into_pointer_value();
+                let position = token
+                    .index
+                    .as_tuple_position(item_types.len())
+                    .ok_or_else(|| CompilerError::Unknown(format!("Invalid tuple index")))?;
+
+                self.llvm_builder
+                    .get_tuple_item(tuple, position, &item_types)
+            }
+            _ => {
+                let (list, index) = self.list_index(&token.object, &token.index, span, context)?;
+
+                self.llvm_builder
+                    .get_list_item(list, index, &list_item_type(&token.object))
+            }
+        }
+    }
+
+    fn dict_entry(
+        &self,
+        object: &Box<ast::Expr>,
+        key: &Box<ast::Expr>,
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<
+        (
+            inkwell::values::PointerValue<'ctx>,
+            inkwell::values::IntValue<'ctx>,
+        ),
+        CompilerError,
+    > {
+        let (key_type, _) = dict_types(object);
+        let dict = self.expression(object, context)?;
+        let dict = self
+            .llvm_builder
+            .load_llvm_value("", &dict)?
+            .into_pointer_value();
+        let key = self.expression(key, context)?;
+
+        let entry = self.llvm_builder.dict_entry(
+            self.dict_slot_function(&key_type)?,
+            dict,
+            self.llvm_builder.load_llvm_value("", &key)?,
+            &key_type,
+        )?;
+
+        Ok((dict, entry))
+    }
+
+    fn dict_slot_function(
+        &self,
+        key_type: &ast::Type,
+    ) -> Result<inkwell::values::FunctionValue<'ctx>, CompilerError> {
+        self.llvm_builder
+            .dict_slot_function(&self.module, self.get_builtin("strcmp")?, key_type)
+    }
+
+    fn dict_set_function(
+        &self,
+        key_type: &ast::Type,
+        value_type: &ast::Type,
+    ) -> Result<inkwell::values::FunctionValue<'ctx>, CompilerError> {
+        self.llvm_builder.dict_set_function(
+            &self.module,
+            self.get_builtin("realloc")?,
+            self.get_builtin("calloc")?,
+            self.dict_slot_function(key_type)?,
+            key_type,
+            value_type,
+        )
+    }
+
+    fn set_item(
+        &self,
+        token: &ast::SetItem,
+        span: &Span,
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        if let ast::Type::Dict(key_type, value_type) =
+            token.object.typing.r#type.get_concrete_type()
+        {
+            let dict = self.expression(&token.object, context)?;
+            let key = self.expression(&token.index, context)?;
+            let value = self.expression(&token.value, context)?;
+
+            self.llvm_builder.set_dict_item(
+                self.dict_set_function(&key_type, &value_type)?,
+                self.llvm_builder
+                    .load_llvm_value("", &dict)?
+                    .into_pointer_value(),
+                self.llvm_builder.load_llvm_value("", &key)?,
+                self.llvm_builder.load_llvm_value("", &value)?,
+                &key_type,
+                &value_type,
+            )?;
+        } else {
+            let (list, index) = self.list_index(&token.object, &token.index, span, context)?;
+            let value = self.expression(&token.value, context)?;
+
+            self.llvm_builder.set_list_item(
+                list,
+                index,
+                self.llvm_builder.load_llvm_value("", &value)?,
+                &list_item_type(&token.object),
+            )?;
+        }
+
+        // assignment does not produce anything
+        Ok(LLVMValue::new_value(
+            self.llvm_builder.value_bool(false),
+            ast::Typing::new(true, ast::Type::None),
+        ))
+    }
+
+    fn attribute(
+        &self,
+        object: &Box<ast::Expr>,
+        name: &str,
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<(inkwell::values::PointerValue<'ctx>, usize, Vec<ast::Type>), CompilerError> {
+        let class_name = class_name(object);
+        let attributes = context
+            .classes
+            .get(&class_name)
+            .ok_or_else(|| CompilerError::Unknown(format!("Class {} not found", class_name)))?;
+        let position = attributes
+            .iter()
+            .position(|a| a.name == name)
+            .ok_or_else(|| {
+                CompilerError::Unknown(format!("{} has no attribute {}", class_name, name))
+            })?;
+        let attribute_types = attribute_types(attributes);
+
+        let object = self.expression(object, context)?;
+        let object = self
+            .llvm_builder
+            .load_llvm_value("", &object)?
+            .into_pointer_value();
+
+        Ok((object, position, attribute_types))
+    }
+
+    fn get_attr(
+        &self,
+        token: &ast::GetAttr,
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let (object, position, attribute_types) =
+            self.attribute(&token.object, &token.name, context)?;
+
+        self.llvm_builder
+            .get_attribute(object, position, &attribute_types)
+    }
+
+    fn set_attr(
+        &self,
+        token: &ast::SetAttr,
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let (object, position, attribute_types) =
+            self.attribute(&token.object, &token.name, context)?;
+        let value = self.expression(&token.value, context)?;
+
+        self.llvm_builder.set_attribute(
+            object,
+            position,
+            self.llvm_builder.load_llvm_value("", &value)?,
+            &attribute_types,
+        )?;
+
+        // assignment does not produce anything
+        Ok(LLVMValue::new_value(
+            self.llvm_builder.value_bool(false),
+            ast::Typing::new(true, ast::Type::None),
+        ))
+    }
+
+    // the target's object and index are evaluated once, its current value being combined
+    // with the value before being stored back
+    fn augmented_assign(
+        &self,
+        token: &ast::AugmentedAssign,
+        span: &Span,
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        match &token.target.body {
+            ast::ExprBody::GetAttr(attr) => {
+                let (object, position, attribute_types) =
+                    self.attribute(&attr.object, &attr.name, context)?;
+                let current =
+                    self.llvm_builder
+                        .get_attribute(object, position, &attribute_types)?;
+                let value = self.augmented_value(token, current, context)?;
+
+                self.llvm_builder.set_attribute(
+                    object,
+                    position,
+                    self.llvm_builder.load_llvm_value("", &value)?,
+                    &attribute_types,
+                )?;
+            }
+            ast::ExprBody::GetItem(item) => match item.object.typing.r#type.get_concrete_type() {
+                ast::Type::Dict(key_type, value_type) => {
+                    let dict = self.expression(&item.object, context)?;
+                    let dict = self
+                        .llvm_builder
+                        .load_llvm_value("", &dict)?
+                        .into_pointer_value();
+                    let key = self.expression(&item.index, context)?;
+                    let key = self.llvm_builder.load_llvm_value("", &key)?;
+
+                    let entry = self.llvm_builder.dict_entry(
+                        self.dict_slot_function(&key_type)?,
+                        dict,
+                        key,
+                        &key_type,
+                    )?;
+                    let has_entry = self.llvm_builder.has_dict_entry(entry)?;
+                    self.runtime_check(
+                        has_entry,
+                        span,
+                        ("KeyError", "key not found in dict"),
+                        context,
+                    )?;
+                    let current = self.llvm_builder.get_dict_value(dict, entry, &value_type)?;
+                    let value = self.augmented_value(token, current, context)?;
+
+                    // the value may have changed the dict, the key is looked up again
+                    self.llvm_builder.set_dict_item(
+                        self.dict_set_function(&key_type, &value_type)?,
+                        dict,
+                        key,
+                        self.llvm_builder.load_llvm_value("", &value)?,
+                        &key_type,
+                        &value_type,
+                    )?;
+                }
+                _ => {
+                    let (list, index) =
+                        self.list_index(&item.object, &item.index, span, context)?;
+                    let item_type = list_item_type(&item.object);
+                    let current = self.llvm_builder.get_list_item(list, index, &item_type)?;
+                    let value = self.augmented_value(token, current, context)?;
+
+                    self.llvm_builder.set_list_item(
+                        list,
+                        index,
+                        self.llvm_builder.load_llvm_value("", &value)?,
+                        &item_type,
+                    )?;
+                }
+            },
+            _ => unreachable!("augmented assignment to {}", token.target),
+        }
+
+        // assignment does not produce anything
+        Ok(LLVMValue::new_value(
+            self.llvm_builder.value_bool(false),
+            ast::Typing::new(true, ast::Type::None),
+        ))
+    }
+
+    // the current value is read before the value is evaluated, like python does
+    fn augmented_value(
+        &self,
+        token: &ast::AugmentedAssign,
+        current: LLVMValue<'ctx>,
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let current = LLVMValue::new_value(
+            self.llvm_builder.load_llvm_value("", &current)?,
+            current.get_typing(),
+        );
+        let value = self.expression(&token.value, context)?;
+
+        self.binary_values(
+            &token.target,
+            &token.operator,
+            &token.value,
+            current,
+            value,
+            context,
+        )
+    }
+
     fn loop_keyword(
         &self,
         token: &ast::LoopKeyword,
         context: &mut CompilerContext<'ctx>,
     ) -> Result<LLVMValue<'ctx>, CompilerError> {
-        match token {
-            ast::LoopKeyword::Break => self
+        // the finally blocks between the loop and the keyword run first
+        let finally = context
+            .finally
+            .last()
+            .filter(|f| f.loops == context.loop_context.len());
+
+        match (token, finally) {
+            (ast::LoopKeyword::Break, Some(f)) => self.leave_through(f, Leave::Breaking)?,
+            (ast::LoopKeyword::Continue, Some(f)) => self.leave_through(f, Leave::Continuing)?,
+            (ast::LoopKeyword::Break, None) => self
                 .llvm_builder
                 .goto_block(context.loop_context.last().unwrap().end)?,
-            ast::LoopKeyword::Continue => self
+            (ast::LoopKeyword::Continue, None) => self
                 .llvm_builder
                 .goto_block(context.loop_context.last().unwrap().cond)?,
         };
@@ -674,10 +2492,280 @@ impl<'ctx> Compiler<'ctx> {
         token: &ast::Binary,
         context: &mut CompilerContext<'ctx>,
     ) -> Result<LLVMValue<'ctx>, CompilerError> {
-        let reference = ItemReference::new(Some(token.right.span.clone()));
         let value_left = self.expression(&token.left, context)?;
         let value_right = self.expression(&token.right, context)?;
-        self.operator(&token.operator, value_left, value_right, reference, context)
+
+        self.binary_values(
+            &token.left,
+            &token.operator,
+            &token.right,
+            value_left,
+            value_right,
+            context,
+        )
+    }
+
+    // the operands are already evaluated, their expressions only give their types
+    fn binary_values(
+        &self,
+        left: &ast::Expr,
+        operator: &ast::Operator,
+        right: &ast::Expr,
+        value_left: LLVMValue<'ctx>,
+        value_right: LLVMValue<'ctx>,
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let reference = ItemReference::new(Some(right.span.clone()));
+
+        if matches!(operator, ast::Operator::In | ast::Operator::NotIn) {
+            let contained = self.contains(
+                value_left,
+                value_right,
+                &right.typing.r#type.get_concrete_type(),
+                context,
+            )?;
+
+            return match operator {
+                ast::Operator::In => Ok(contained),
+                _ => self.llvm_builder.create_not(contained),
+            };
+        }
+
+        if let Some(r#type) = collection_type(
+            left.typing.r#type.get_concrete_type(),
+            right.typing.r#type.get_concrete_type(),
+        ) {
+            let equal = self.values_equal(value_left, value_right, &r#type, context)?;
+
+            return match operator {
+                ast::Operator::Equal => Ok(equal),
+                ast::Operator::BangEqual => self.llvm_builder.create_not(equal),
+                _ => unreachable!(),
+            };
+        }
+
+        if matches!(
+            operator,
+            ast::Operator::Divide
+                | ast::Operator::FloorDivide
+                | ast::Operator::Modulo
+                | ast::Operator::DevideEqual
+        ) {
+            let not_zero = self.llvm_builder.not_equal(
+                value_right.clone(),
+                LLVMValue::new_value(
+                    self.llvm_builder.value_int(0),
+                    Typing::new(true, ast::Type::Int),
+                ),
+            )?;
+            self.runtime_check(
+                not_zero.as_value().into_int_value(),
+                &right.span,
+                ("ZeroDivisionError", "division by zero"),
+                context,
+            )?;
+        }
+
+        let is_float = |expr: &ast::Expr| expr.typing.r#type == ast::Type::Float;
+        if operator == &ast::Operator::Power && !is_float(left) && !is_float(right) {
+            let is_positive = self.llvm_builder.greater_or_equal(
+                value_right.clone(),
+                LLVMValue::new_value(
+                    self.llvm_builder.value_int(0),
+                    Typing::new(true, ast::Type::Int),
+                ),
+            )?;
+            self.runtime_check(
+                is_positive.as_value().into_int_value(),
+                &right.span,
+                (
+                    "ValueError",
+                    "integers to negative integer powers are not allowed",
+                ),
+                context,
+            )?;
+        }
+
+        if matches!(
+            operator,
+            ast::Operator::ShiftLeft | ast::Operator::ShiftRight
+        ) {
+            let is_positive = self.llvm_builder.greater_or_equal(
+                value_right.clone(),
+                LLVMValue::new_value(
+                    self.llvm_builder.value_int(0),
+                    Typing::new(true, ast::Type::Int),
+                ),
+            )?;
+            self.runtime_check(
+                is_positive.as_value().into_int_value(),
+                &right.span,
+                ("ValueError", "negative shift count"),
+                context,
+            )?;
+        }
+
+        self.operator(operator, value_left, value_right, reference, context)
+    }
+
+    // each operand is evaluated once, and only while the comparisons before it hold
+    fn comparison(
+        &self,
+        token: &ast::Comparison,
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let end_block = self.llvm_builder.create_block("comparison_end");
+        let mut results = Vec::new();
+
+        let mut left = &token.left;
+        let mut value_left = self.expression(left, context)?;
+
+        for (i, (operator, right)) in token.comparisons.iter().enumerate() {
+            let value_right = self.expression(right, context)?;
+            // the next comparison must see the same value
+            let value_right = LLVMValue::new_value(
+                self.llvm_builder.load_llvm_value("", &value_right)?,
+                value_right.get_typing(),
+            );
+
+            let compared = self.binary_values(
+                left,
+                operator,
+                right,
+                value_left,
+                value_right.clone(),
+                context,
+            )?;
+            let compared = self.llvm_builder.is_truthy(compared)?;
+            results.push((
+                compared.as_value().into_int_value(),
+                self.llvm_builder.get_current_block().unwrap(),
+            ));
+
+            if i + 1 == token.comparisons.len() {
+                self.llvm_builder.goto_block(end_block)?;
+            } else {
+                let next_block = self.llvm_builder.create_block("comparison_next");
+                self.llvm_builder
+                    .create_branche(compared, next_block, end_block)?;
+                self.llvm_builder.set_position_at(next_block);
+            }
+
+            left = right;
+            value_left = value_right;
+        }
+
+        self.llvm_builder.set_position_at(end_block);
+        self.llvm_builder.bool_phi(&results)
+    }
+
+    fn values_equal(
+        &self,
+        value_left: LLVMValue<'ctx>,
+        value_right: LLVMValue<'ctx>,
+        r#type: &ast::Type,
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        match r#type {
+            ast::Type::List(item_type) => {
+                self.llvm_builder
+                    .lists_equal(value_left, value_right, item_type, |a, b| {
+                        self.values_equal(a, b, item_type, context)
+                    })
+            }
+            ast::Type::Dict(key_type, value_type) => {
+                let slot_function = match key_type.as_ref() {
+                    ast::Type::Unknown => None,
+                    t => Some(self.dict_slot_function(t)?),
+                };
+                self.llvm_builder.dicts_equal(
+                    slot_function,
+                    value_left,
+                    value_right,
+                    key_type,
+                    value_type,
+                    |a, b| self.values_equal(a, b, value_type, context),
+                )
+            }
+            ast::Type::Tuple(item_types) => {
+                let left = self
+                    .llvm_builder
+                    .load_llvm_value("", &value_left)?
+                    .into_pointer_value();
+                let right = self
+                    .llvm_builder
+                    .load_llvm_value("", &value_right)?
+                    .into_pointer_value();
+
+                let mut equal = LLVMValue::new_value(
+                    self.llvm_builder.value_bool(true),
+                    Typing::new(true, ast::Type::Bool),
+                );
+                for (i, item_type) in item_types.iter().enumerate() {
+                    let item_equal = self.values_equal(
+                        self.llvm_builder.get_tuple_item(left, i, item_types)?,
+                        self.llvm_builder.get_tuple_item(right, i, item_types)?,
+                        item_type,
+                        context,
+                    )?;
+                    equal = self.llvm_builder.and(equal, item_equal)?;
+                }
+
+                Ok(equal)
+            }
+            _ => self.operator(
+                &ast::Operator::Equal,
+                value_left,
+                value_right,
+                ItemReference::new(None),
+                context,
+            ),
+        }
+    }
+
+    fn contains(
+        &self,
+        value: LLVMValue<'ctx>,
+        container: LLVMValue<'ctx>,
+        container_type: &ast::Type,
+        context: &mut CompilerContext<'ctx>,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        match container_type {
+            ast::Type::Dict(key_type, _) => {
+                // dicts whose keys are unknown are always empty
+                if **key_type == ast::Type::Unknown {
+                    return Ok(LLVMValue::new_value(
+                        self.llvm_builder.value_bool(false),
+                        Typing::new(true, ast::Type::Bool),
+                    ));
+                }
+
+                let entry = self.llvm_builder.dict_entry(
+                    self.dict_slot_function(key_type)?,
+                    self.llvm_builder
+                        .load_llvm_value("", &container)?
+                        .into_pointer_value(),
+                    self.llvm_builder.load_llvm_value("", &value)?,
+                    key_type,
+                )?;
+
+                Ok(LLVMValue::new_value(
+                    self.llvm_builder
+                        .has_dict_entry(entry)?
+                        .as_basic_value_enum(),
+                    Typing::new(true, ast::Type::Bool),
+                ))
+            }
+            ast::Type::List(item_type) => {
+                self.llvm_builder
+                    .list_contains(container, item_type, |item| {
+                        self.values_equal(item, value.clone(), item_type, context)
+                    })
+            }
+            _ => self
+                .llvm_builder
+                .string_contains(self.get_builtin("strstr")?, container, value),
+        }
     }
 
     fn operator(
@@ -688,6 +2776,41 @@ impl<'ctx> Compiler<'ctx> {
         reference: ItemReference,
         context: &mut CompilerContext<'ctx>,
     ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let is_object = |v: &LLVMValue<'ctx>| match v.get_typing().r#type.get_concrete_type() {
+            ast::Type::String => Some(ast::Type::String),
+            ast::Type::List(_) => Some(ast::Type::List(Box::new(ast::Type::Unknown))),
+            ast::Type::Dict(_, _) => Some(ast::Type::Dict(
+                Box::new(ast::Type::Unknown),
+                Box::new(ast::Type::Unknown),
+            )),
+            ast::Type::Tuple(_) => Some(ast::Type::Tuple(Vec::new())),
+            ast::Type::Object(_) => Some(ast::Type::Object(String::new())),
+            _ => None,
+        };
+
+        match (is_object(&value_left), is_object(&value_right)) {
+            (Some(ast::Type::String), Some(ast::Type::String)) => {
+                return self.string_operator(token, value_left, value_right)
+            }
+            // objects are compared by identity
+            (Some(ast::Type::Object(_)), Some(ast::Type::Object(_))) => (),
+            // strings and collections never equal a value of another type
+            (Some(_), _) | (_, Some(_)) => {
+                return match token {
+                    ast::Operator::Equal => Ok(LLVMValue::new_value(
+                        self.llvm_builder.value_bool(false),
+                        Typing::new(true, ast::Type::Bool),
+                    )),
+                    ast::Operator::BangEqual => Ok(LLVMValue::new_value(
+                        self.llvm_builder.value_bool(true),
+                        Typing::new(true, ast::Type::Bool),
+                    )),
+                    _ => unreachable!(),
+                }
+            }
+            (None, None) => (),
+        }
+
         match token {
             ast::Operator::Plus => self.llvm_builder.add(value_left, value_right),
             ast::Operator::Minus => self.llvm_builder.sub(value_left, value_right),
@@ -703,6 +2826,20 @@ impl<'ctx> Compiler<'ctx> {
             ast::Operator::LessEqual => self.llvm_builder.less_or_equal(value_left, value_right),
             ast::Operator::Comma => unimplemented!(),
             ast::Operator::Modulo => self.llvm_builder.modulo(value_left, value_right),
+            ast::Operator::FloorDivide => {
+                self.llvm_builder
+                    .floor_divide(self.get_builtin("round")?, value_left, value_right)
+            }
+            ast::Operator::Power => {
+                self.llvm_builder
+                    .power(self.get_builtin("pow")?, value_left, value_right)
+            }
+            ast::Operator::BitAnd | ast::Operator::BitOr | ast::Operator::BitXor => {
+                self.llvm_builder.bitwise(token, value_left, value_right)
+            }
+            ast::Operator::ShiftLeft | ast::Operator::ShiftRight => {
+                self.llvm_builder.shift(token, value_left, value_right)
+            }
             _ => unreachable!(),
 
             // might have to transform them earlier because we don't know the ptr to update here
@@ -750,6 +2887,37 @@ impl<'ctx> Compiler<'ctx> {
         }
     }
 
+    fn string_operator(
+        &self,
+        token: &ast::Operator,
+        value_left: LLVMValue<'ctx>,
+        value_right: LLVMValue<'ctx>,
+    ) -> Result<LLVMValue<'ctx>, CompilerError> {
+        let predicate = match token {
+            ast::Operator::Plus => {
+                return self.llvm_builder.concat_strings(
+                    self.get_builtin("strlen")?,
+                    value_left,
+                    value_right,
+                )
+            }
+            ast::Operator::Equal => inkwell::IntPredicate::EQ,
+            ast::Operator::BangEqual => inkwell::IntPredicate::NE,
+            ast::Operator::Greater => inkwell::IntPredicate::SGT,
+            ast::Operator::GreaterEqual => inkwell::IntPredicate::SGE,
+            ast::Operator::Less => inkwell::IntPredicate::SLT,
+            ast::Operator::LessEqual => inkwell::IntPredicate::SLE,
+            _ => unreachable!(),
+        };
+
+        self.llvm_builder.compare_strings(
+            self.get_builtin("strcmp")?,
+            value_left,
+            value_right,
+            predicate,
+        )
+    }
+
     pub fn get_variable(
         &self,
         var_name: &str,
@@ -791,6 +2959,7 @@ impl<'ctx> Compiler<'ctx> {
         match token.operator {
             ast::UnaryOperator::Minus => self.llvm_builder.create_neg(expr),
             ast::UnaryOperator::Not => self.llvm_builder.create_not(expr),
+            ast::UnaryOperator::Invert => self.llvm_builder.invert(expr),
         }
     }
 
@@ -802,3 +2971,58 @@ impl<'ctx> Compiler<'ctx> {
         self.expression(&token.expr, context)
     }
 }
+
+// kind, name, message and location of an error
+fn error_record() -> [ast::Type; 4] {
+    [
+        ast::Type::Int,
+        ast::Type::String,
+        ast::Type::String,
+        ast::Type::String,
+    ]
+}
+
+fn error_kind(name: &str) -> usize {
+    ast::ERROR_KINDS.iter().position(|k| *k == name).unwrap()
+}
+
+fn list_item_type(list: &ast::Expr) -> ast::Type {
+    match list.typing.r#type.get_concrete_type() {
+        ast::Type::List(t) => *t,
+        t => unreachable!("Not a list: {t}"),
+    }
+}
+
+fn class_name(object: &ast::Expr) -> String {
+    match object.typing.r#type.get_concrete_type() {
+        ast::Type::Object(name) => name,
+        t => unreachable!("Not an object: {t}"),
+    }
+}
+
+fn attribute_types(attributes: &Vec<ast::Attribute>) -> Vec<ast::Type> {
+    attributes.iter().map(|a| a.typing.r#type.clone()).collect()
+}
+
+fn dict_types(dict: &ast::Expr) -> (ast::Type, ast::Type) {
+    match dict.typing.r#type.get_concrete_type() {
+        ast::Type::Dict(k, v) => (*k, *v),
+        t => unreachable!("Not a dict: {t}"),
+    }
+}
+
+// items of an empty collection literal are unknown, use the other side
+fn collection_type(left: ast::Type, right: ast::Type) -> Option<ast::Type> {
+    match (&left, &right) {
+        (ast::Type::List(item_type), ast::Type::List(_))
+        | (ast::Type::Dict(item_type, _), ast::Type::Dict(_, _)) => {
+            Some(if **item_type == ast::Type::Unknown {
+                right
+            } else {
+                left
+            })
+        }
+        (ast::Type::Tuple(l), ast::Type::Tuple(r)) if l.len() == r.len() => Some(left),
+        _ => None,
+    }
+}
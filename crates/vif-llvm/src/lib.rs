@@ -1,4 +1,5 @@
 mod builder;
+mod closure;
 mod compiler;
 mod error;
 
@@ -11,9 +12,10 @@ use inkwell::context::Context;
 
 fn compile<'func, 'ctx>(
     ast_function: &vif_objects::ast::Function,
+    content: &'ctx str,
     context: &'ctx Context,
 ) -> Result<Compiler<'ctx>, CompilerError> {
-    let compiler = Compiler::new(&context);
+    let compiler = Compiler::new(&context, content);
 
     let mut store = CompilerContext::new();
     compiler.add_builtin_functions(&mut store);
@@ -23,28 +25,35 @@ fn compile<'func, 'ctx>(
     Ok(compiler)
 }
 
-pub fn get_llvm_ir(ast_function: &vif_objects::ast::Function) -> Result<String, CompilerError> {
+pub fn get_llvm_ir(
+    ast_function: &vif_objects::ast::Function,
+    content: &str,
+) -> Result<String, CompilerError> {
     let context = inkwell::context::Context::create();
 
-    let compiler = compile(ast_function, &context)?;
+    let compiler = compile(ast_function, content, &context)?;
 
     Ok(compiler.as_string())
 }
 
-pub fn compile_and_execute(ast_function: &vif_objects::ast::Function) -> Result<(), CompilerError> {
+pub fn compile_and_execute(
+    ast_function: &vif_objects::ast::Function,
+    content: &str,
+) -> Result<(), CompilerError> {
     let context = inkwell::context::Context::create();
 
-    let compiler = compile(ast_function, &context)?;
+    let compiler = compile(ast_function, content, &context)?;
 
     compiler.execute()
 }
 
 pub fn compile_and_build_binary(
     ast_function: &vif_objects::ast::Function,
+    content: &str,
 ) -> Result<(), CompilerError> {
     let context = inkwell::context::Context::create();
 
-    let compiler = compile(ast_function, &context)?;
+    let compiler = compile(ast_function, content, &context)?;
 
     compiler.build_binary("here.o")
 }
@@ -22,6 +22,8 @@ pub struct Config {
     pub debug: bool,
     pub log_level: log::LevelFilter,
     pub action: Action,
+    // where modules are looked for when not next to the entrypoint
+    pub search_path: Vec<PathBuf>,
 }
 
 pub fn get_config() -> Config {
@@ -58,9 +60,14 @@ pub fn get_config() -> Config {
             log::LevelFilter::Error
         });
 
+    let search_path = std::env::var_os("VIF_PATH")
+        .map(|paths| std::env::split_paths(&paths).collect())
+        .unwrap_or_default();
+
     return Config {
         debug,
         log_level,
         action,
+        search_path,
     };
 }
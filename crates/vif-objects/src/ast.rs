@@ -14,16 +14,36 @@ pub enum Operator {
     MultiplyEqual,
     BangEqual,
     Modulo,
+    Power,
+    FloorDivide,
+    BitAnd,
+    BitOr,
+    BitXor,
+    ShiftLeft,
+    ShiftRight,
     Greater,
     Less,
     GreaterEqual,
     LessEqual,
+    In,
+    NotIn,
+}
+
+impl Operator {
+    // only defined on integers and booleans
+    pub fn is_bitwise(&self) -> bool {
+        matches!(
+            self,
+            Self::BitAnd | Self::BitOr | Self::BitXor | Self::ShiftLeft | Self::ShiftRight
+        )
+    }
 }
 
 #[derive(Debug, PartialEq)]
 pub enum UnaryOperator {
     Minus,
     Not,
+    Invert,
 }
 
 #[derive(Debug, PartialEq)]
@@ -50,6 +70,14 @@ pub struct Binary {
     pub right: Box<Expr>,
 }
 
+// a chain like `a < b <= c`, each operand being evaluated once and the chain stopping
+// at the first failing comparison
+#[derive(Debug, PartialEq)]
+pub struct Comparison {
+    pub left: Box<Expr>,
+    pub comparisons: Vec<(Operator, Box<Expr>)>,
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Unary {
     pub operator: UnaryOperator,
@@ -77,9 +105,19 @@ pub struct Variable {
 }
 
 impl Variable {
+    // the type is given by the value, once typed
     pub fn new(name: String, value: Box<Expr>, mutable: bool) -> Self {
         Variable {
-            typing: Typing::new(mutable, value.typing.r#type.clone()),
+            typing: Typing::new(mutable, Type::Unknown),
+            name,
+            value,
+        }
+    }
+
+    // the value must fit the annotated type
+    pub fn new_with_typing(name: String, value: Box<Expr>, typing: Typing) -> Self {
+        Variable {
+            typing,
             name,
             value,
         }
@@ -92,10 +130,98 @@ pub struct Assign {
     pub value: Box<Expr>,
 }
 
+// `xs[i] += 1` or `obj.count += 1`, the target's object and index being evaluated once.
+// Variables are simply assigned `x = x + 1`
+#[derive(Debug, PartialEq)]
+pub struct AugmentedAssign {
+    pub target: Box<Expr>,
+    pub operator: Operator,
+    pub value: Box<Expr>,
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Call {
     pub callee: Box<Expr>,
     pub arguments: Vec<Box<Expr>>,
+    // named arguments, always coming after the positional ones
+    pub keywords: Vec<(String, Box<Expr>)>,
+}
+
+impl Call {
+    pub fn count_arguments(&self) -> usize {
+        self.arguments.len() + self.keywords.len()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ArgumentError {
+    Unknown(String),
+    Duplicated(String),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct List {
+    pub items: Vec<Box<Expr>>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Dict {
+    pub items: Vec<(Box<Expr>, Box<Expr>)>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Tuple {
+    pub items: Vec<Box<Expr>>,
+}
+
+// f"total: {count} items ({ratio:.2f})"
+#[derive(Debug, PartialEq)]
+pub struct FormatString {
+    pub parts: Vec<FormatPart>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum FormatPart {
+    Literal(String),
+    Value(Box<Expr>, FormatSpec),
+}
+
+// what follows the colon of a replacement field, [[fill]align][sign][0][width][.precision][type]
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct FormatSpec {
+    pub fill: Option<char>,
+    pub align: Option<char>,
+    pub sign: Option<char>,
+    pub zero: bool,
+    pub width: Option<usize>,
+    pub precision: Option<usize>,
+    pub kind: Option<char>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct GetItem {
+    pub object: Box<Expr>,
+    pub index: Box<Expr>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SetItem {
+    pub object: Box<Expr>,
+    pub index: Box<Expr>,
+    pub value: Box<Expr>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct GetAttr {
+    pub object: Box<Expr>,
+    pub name: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SetAttr {
+    pub object: Box<Expr>,
+    pub name: String,
+    pub value: Box<Expr>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -108,15 +234,64 @@ pub struct Assert {
     pub value: Box<Expr>,
 }
 
+// kinds of the errors raised at runtime, `Exception` stands for all of them
+pub const ERROR_KINDS: [&str; 6] = [
+    "Exception",
+    "AssertionError",
+    "IndexError",
+    "KeyError",
+    "ValueError",
+    "ZeroDivisionError",
+];
+
+// `raise Kind("message")`, or a bare `raise` giving the error being handled back
+#[derive(Debug, PartialEq)]
+pub struct Raise {
+    pub kind: Option<String>,
+    pub message: Option<Box<Expr>>,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Try {
+    pub body: Box<Stmt>,
+    pub handlers: Vec<Except>,
+    pub finally: Option<Box<Stmt>>,
+}
+
+// `except (A, B) as e:` catches the errors of those kinds, `except:` all of them.
+// The name holds the message of the error
+#[derive(Debug, PartialEq)]
+pub struct Except {
+    pub kinds: Vec<String>,
+    pub name: Option<String>,
+    pub body: Box<Stmt>,
+    pub span: Span,
+}
+
+// `import module` has no names, `from module import a, b` has some
+#[derive(Debug, PartialEq)]
+pub struct Import {
+    pub module: String,
+    pub names: Vec<String>,
+    pub span: Span,
+}
+
 #[derive(Debug, Clone, Eq)]
 pub enum Signature {
-    Parameters(Vec<Typing>),
+    // the parameters, the first ones being required and the others having a default value
+    Parameters(Vec<Typing>, usize),
     Infinite,
 }
 
 impl Signature {
     pub fn new_with_params(parameters: Vec<Typing>) -> Self {
-        Signature::Parameters(parameters)
+        let required = parameters.len();
+        Signature::Parameters(parameters, required)
+    }
+
+    pub fn new_with_defaults(parameters: Vec<Typing>, required: usize) -> Self {
+        Signature::Parameters(parameters, required)
     }
 
     pub fn new_with_infinite() -> Self {
@@ -125,7 +300,14 @@ impl Signature {
 
     pub fn get_params(&self) -> Option<&Vec<Typing>> {
         match self {
-            Self::Parameters(p) => Some(p),
+            Self::Parameters(p, _) => Some(p),
+            _ => None,
+        }
+    }
+
+    pub fn get_required(&self) -> Option<usize> {
+        match self {
+            Self::Parameters(_, required) => Some(*required),
             _ => None,
         }
     }
@@ -133,15 +315,16 @@ impl Signature {
 
 impl std::default::Default for Signature {
     fn default() -> Self {
-        Signature::Parameters(Vec::new())
+        Signature::Parameters(Vec::new(), 0)
     }
 }
 
 impl PartialEq for Signature {
     fn eq(&self, other: &Self) -> bool {
         match self {
-            Self::Parameters(p1) => match other {
-                Self::Parameters(p2) => p1 == p2,
+            // defaults do not change what a function accepts once given all of its arguments
+            Self::Parameters(p1, _) => match other {
+                Self::Parameters(p2, _) => p1 == p2,
                 _ => false,
             },
             Self::Infinite => match other {
@@ -158,7 +341,7 @@ impl std::fmt::Display for Signature {
             f,
             "[{}]",
             match self {
-                Signature::Parameters(p) => p
+                Signature::Parameters(p, _) => p
                     .iter()
                     .map(|b| format!("{b}"))
                     .collect::<Vec<String>>()
@@ -174,6 +357,8 @@ pub struct Callable {
     pub signature: Signature,
     pub return_pointer: bool,
     pub output: Typing,
+    // only known for declared functions, not for callable annotations
+    pub names: Vec<String>,
 }
 
 impl PartialEq for Callable {
@@ -188,8 +373,39 @@ impl Callable {
             signature,
             output,
             return_pointer,
+            names: Vec::new(),
         }
     }
+
+    // The arguments of a call in the order of the parameters, None being left to
+    // their default value. The `skip` first parameters are not part of the call,
+    // e.g self for methods
+    pub fn order_arguments<'a>(
+        &self,
+        call: &'a Call,
+        skip: usize,
+    ) -> Result<Vec<Option<&'a Expr>>, ArgumentError> {
+        let params = self.signature.get_params().map_or(0, |p| p.len());
+        let mut arguments: Vec<Option<&Expr>> =
+            call.arguments.iter().map(|a| Some(a.as_ref())).collect();
+        arguments.resize(params.saturating_sub(skip).max(arguments.len()), None);
+
+        for (name, value) in call.keywords.iter() {
+            let position = self
+                .names
+                .iter()
+                .skip(skip)
+                .position(|n| n == name)
+                .ok_or_else(|| ArgumentError::Unknown(name.clone()))?;
+
+            if arguments[position].is_some() {
+                return Err(ArgumentError::Duplicated(name.clone()));
+            }
+            arguments[position] = Some(value.as_ref());
+        }
+
+        Ok(arguments)
+    }
 }
 
 impl std::fmt::Display for Callable {
@@ -213,6 +429,10 @@ pub enum Type {
     Unknown,
     KeyWord,
     Callable(Box<Callable>),
+    List(Box<Type>),
+    Dict(Box<Type>, Box<Type>),
+    Tuple(Vec<Type>),
+    Object(String),
 }
 
 impl std::fmt::Display for Type {
@@ -226,6 +446,17 @@ impl std::fmt::Display for Type {
             Self::Unknown => write!(f, "Unknown"),
             Self::KeyWord => write!(f, "KeyWord"),
             Self::Callable(c) => write!(f, "Callable[{}]", c),
+            Self::List(t) => write!(f, "List[{}]", t),
+            Self::Dict(k, v) => write!(f, "Dict[{}, {}]", k, v),
+            Self::Tuple(t) => write!(
+                f,
+                "Tuple[{}]",
+                t.iter()
+                    .map(|t| format!("{t}"))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            Self::Object(name) => write!(f, "{}", name),
         }
     }
 }
@@ -247,12 +478,39 @@ impl Type {
         }
     }
 
-    pub fn get_concrete_type(&self) -> Self {
+    pub fn is_hashable(&self) -> bool {
         match self {
-            Self::Callable(c) => c.output.r#type.get_concrete_type().clone(),
-            v => v.clone(),
+            Self::Int | Self::Float | Self::Bool | Self::None | Self::String | Self::Unknown => {
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // nothing is left to infer, like the items of an empty list or untyped parameters
+    pub fn is_complete(&self) -> bool {
+        match self {
+            Self::Unknown => false,
+            Self::List(t) => t.is_complete(),
+            Self::Dict(k, v) => k.is_complete() && v.is_complete(),
+            Self::Tuple(types) => types.iter().all(|t| t.is_complete()),
+            Self::Callable(c) => {
+                let params_complete = match &c.signature {
+                    Signature::Parameters(params, _) => {
+                        params.iter().all(|p| p.r#type.is_complete())
+                    }
+                    Signature::Infinite => true,
+                };
+                params_complete && c.output.r#type.is_complete()
+            }
+            _ => true,
         }
     }
+
+    // functions are values of their own, calling them is what gives their output type
+    pub fn get_concrete_type(&self) -> Self {
+        self.clone()
+    }
 }
 
 #[derive(Debug, Clone, Eq)]
@@ -310,6 +568,9 @@ pub enum Number {
 pub struct FunctionParameter {
     pub name: String,
     pub typing: Typing,
+    // evaluated on each call not receiving this argument
+    pub default: Option<Box<Expr>>,
+    pub span: Span,
 }
 
 #[derive(Debug, PartialEq)]
@@ -318,17 +579,50 @@ pub struct Function {
     pub params: Vec<FunctionParameter>,
     pub body: Vec<Stmt>,
     pub typing: Typing,
+    // whether the return type is annotated, rather than inferred from the returns
+    pub declared_output: bool,
+    pub span: Span,
 }
 
 impl Function {
-    pub fn new(name: String, params: Vec<FunctionParameter>, body: Vec<Stmt>) -> Self {
+    pub fn new(name: String, params: Vec<FunctionParameter>, body: Vec<Stmt>, span: Span) -> Self {
         Function {
             name,
             params,
             body,
             typing: Typing::new(false, Type::Unknown),
+            declared_output: false,
+            span,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attribute {
+    pub name: String,
+    pub typing: Typing,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Class {
+    pub name: String,
+    pub methods: Vec<Function>,
+    // filled by the typer from the assignments made in __init__
+    pub attributes: Vec<Attribute>,
+}
+
+impl Class {
+    pub fn new(name: String, methods: Vec<Function>) -> Self {
+        Class {
+            name,
+            methods,
+            attributes: Vec::new(),
         }
     }
+
+    pub fn get_attribute_index(&self, name: &str) -> Option<usize> {
+        self.attributes.iter().position(|a| a.name == name)
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -337,6 +631,16 @@ pub struct While {
     pub body: Box<Stmt>,
 }
 
+// the loop variable is only mutable when declared with `for mut`
+#[derive(Debug, PartialEq)]
+pub struct For {
+    pub name: String,
+    pub typing: Typing,
+    pub iterable: Box<Expr>,
+    pub body: Box<Stmt>,
+    pub r#else: Option<Box<Stmt>>,
+}
+
 #[derive(Debug, PartialEq)]
 pub enum LoopKeyword {
     Continue,
@@ -380,19 +684,50 @@ pub struct Expr {
 #[derive(Debug, PartialEq)]
 pub enum ExprBody {
     Binary(Binary),
+    Comparison(Comparison),
     Unary(Unary),
     Grouping(Grouping),
     Value(Value),
     LoopKeyword(LoopKeyword),
     Assign(Assign),
+    AugmentedAssign(AugmentedAssign),
     Logical(Logical),
     Call(Call),
+    List(List),
+    Dict(Dict),
+    Tuple(Tuple),
+    GetItem(GetItem),
+    SetItem(SetItem),
+    GetAttr(GetAttr),
+    SetAttr(SetAttr),
+    FormatString(FormatString),
 }
 
 impl Expr {
     pub fn new(body: ExprBody, typing: Typing, span: Span) -> Self {
         Expr { body, typing, span }
     }
+
+    pub fn as_integer_literal(&self) -> Option<i64> {
+        match &self.body {
+            ExprBody::Value(Value::Integer(i)) => Some(*i),
+            ExprBody::Unary(Unary {
+                operator: UnaryOperator::Minus,
+                right,
+            }) => right.as_integer_literal().map(|i| -i),
+            ExprBody::Grouping(g) => g.expr.as_integer_literal(),
+            _ => None,
+        }
+    }
+
+    // tuples are indexed by literals, negative ones counting from the end
+    pub fn as_tuple_position(&self, len: usize) -> Option<usize> {
+        let index = self.as_integer_literal()?;
+        let position = if index < 0 { index + len as i64 } else { index };
+        (0..len as i64)
+            .contains(&position)
+            .then_some(position as usize)
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -400,31 +735,93 @@ pub enum Stmt {
     Expression(Box<Expr>),
     Var(Variable),
     Function(Function),
+    Class(Class),
     Block(Vec<Stmt>),
     Condition(Condition),
     While(While),
+    For(For),
     Return(Return),
     Assert(Assert),
+    Import(Import),
+    Raise(Raise),
+    Try(Try),
 }
 
 impl Stmt {
     pub fn get_all_returns(&self) -> Vec<&Return> {
         match self {
-            Self::Function(f) => f
-                .body
-                .iter()
-                .map(|b| b.get_all_returns())
-                .flatten()
-                .collect(),
+            // returns of a nested function belong to it
+            Self::Function(_) => Vec::new(),
+            Self::Class(_) => Vec::new(),
             Self::Block(b) => b.iter().map(|b| b.get_all_returns()).flatten().collect(),
-            Self::Condition(c) => c.then.get_all_returns(),
+            Self::Condition(c) => c
+                .then
+                .get_all_returns()
+                .into_iter()
+                .chain(c.r#else.iter().flat_map(|e| e.get_all_returns()))
+                .collect(),
             Self::While(w) => w.body.get_all_returns(),
+            Self::For(f) => f
+                .body
+                .get_all_returns()
+                .into_iter()
+                .chain(f.r#else.iter().flat_map(|e| e.get_all_returns()))
+                .collect(),
+            Self::Try(t) => t
+                .body
+                .get_all_returns()
+                .into_iter()
+                .chain(t.handlers.iter().flat_map(|h| h.body.get_all_returns()))
+                .chain(t.finally.iter().flat_map(|f| f.get_all_returns()))
+                .collect(),
             Self::Return(r) => vec![r],
+            Self::Raise(_) => Vec::new(),
             Self::Assert(_) => Vec::new(),
+            Self::Import(_) => Vec::new(),
             Self::Expression(_) => Vec::new(),
             Self::Var(_) => Vec::new(),
         }
     }
+
+    // whether running the statement always ends up on a return, or on an error
+    pub fn always_returns(&self) -> bool {
+        match self {
+            Self::Return(_) | Self::Raise(_) => true,
+            Self::Block(b) => b.iter().any(|s| s.always_returns()),
+            Self::Condition(c) => {
+                c.then.always_returns() && c.r#else.as_ref().is_some_and(|e| e.always_returns())
+            }
+            Self::Try(t) => {
+                (t.body.always_returns() && t.handlers.iter().all(|h| h.body.always_returns()))
+                    || t.finally.as_ref().is_some_and(|f| f.always_returns())
+            }
+            // an endless loop only ends on a return or an error
+            Self::While(w) => {
+                matches!(w.condition.body, ExprBody::Value(Value::True)) && !w.body.breaks()
+            }
+            // the else block runs whenever the loop ends without a break
+            Self::For(f) => {
+                f.r#else.as_ref().is_some_and(|e| e.always_returns()) && !f.body.breaks()
+            }
+            _ => false,
+        }
+    }
+
+    // whether the statement can break out of the loop it is in
+    fn breaks(&self) -> bool {
+        match self {
+            Self::Expression(e) => matches!(e.body, ExprBody::LoopKeyword(LoopKeyword::Break)),
+            Self::Block(b) => b.iter().any(|s| s.breaks()),
+            Self::Condition(c) => c.then.breaks() || c.r#else.as_ref().is_some_and(|e| e.breaks()),
+            Self::Try(t) => {
+                t.body.breaks()
+                    || t.handlers.iter().any(|h| h.body.breaks())
+                    || t.finally.as_ref().is_some_and(|f| f.breaks())
+            }
+            // a nested loop catches its own breaks
+            _ => false,
+        }
+    }
 }
 
 impl std::fmt::Display for Return {
@@ -444,9 +841,14 @@ impl std::fmt::Display for Stmt {
             }
             Self::Condition(c) => write!(f, "{}", c),
             Self::While(w) => write!(f, "{}", w),
+            Self::For(v) => write!(f, "{}", v),
             Self::Function(v) => write!(f, "{}", v),
+            Self::Class(v) => write!(f, "{}", v),
             Self::Return(v) => write!(f, "{}", v),
             Self::Assert(v) => write!(f, "{}", v),
+            Self::Import(v) => write!(f, "{}", v),
+            Self::Raise(v) => write!(f, "{}", v),
+            Self::Try(v) => write!(f, "{}", v),
         }
     }
 }
@@ -457,18 +859,62 @@ impl std::fmt::Display for Function {
     }
 }
 
+impl std::fmt::Display for Class {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "class[{}]", self.name)
+    }
+}
+
+impl std::fmt::Display for Import {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.names.is_empty() {
+            true => write!(f, "import[{}]", self.module),
+            false => write!(f, "import[{}: {}]", self.module, self.names.join(", ")),
+        }
+    }
+}
+
 impl std::fmt::Display for Assert {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "assert[{}]", self.value)
     }
 }
 
+impl std::fmt::Display for Raise {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.kind, &self.message) {
+            (Some(kind), Some(message)) => write!(f, "raise[{kind}: {message}]"),
+            (Some(kind), None) => write!(f, "raise[{kind}]"),
+            (None, _) => write!(f, "raise"),
+        }
+    }
+}
+
+impl std::fmt::Display for Try {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "try [{}]", self.body)?;
+        for handler in self.handlers.iter() {
+            write!(f, " except {} [{}]", handler.kinds.join(", "), handler.body)?;
+        }
+        if let Some(finally) = &self.finally {
+            write!(f, " finally [{}]", finally)?;
+        }
+        Ok(())
+    }
+}
+
 impl std::fmt::Display for While {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "while {} [{}]", self.condition, self.body)
     }
 }
 
+impl std::fmt::Display for For {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "for {} in {} [{}]", self.name, self.iterable, self.body)
+    }
+}
+
 impl std::fmt::Display for Condition {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{} {} todo", self.expr, self.then)
@@ -559,6 +1005,22 @@ impl std::fmt::Display for Binary {
     }
 }
 
+impl std::fmt::Display for Comparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Comparison[{}", self.left)?;
+        for (operator, expr) in &self.comparisons {
+            write!(f, ", {}, {}", operator, expr)?;
+        }
+        write!(f, "]")
+    }
+}
+
+impl std::fmt::Display for AugmentedAssign {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}= {}", self.target, self.operator, self.value)
+    }
+}
+
 impl std::fmt::Display for Group {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -604,6 +1066,15 @@ impl std::fmt::Display for Operator {
                 Self::Less => "<",
                 Self::LessEqual => "<=",
                 Self::Modulo => "%",
+                Self::Power => "**",
+                Self::FloorDivide => "//",
+                Self::BitAnd => "&",
+                Self::BitOr => "|",
+                Self::BitXor => "^",
+                Self::ShiftLeft => "<<",
+                Self::ShiftRight => ">>",
+                Self::In => "in",
+                Self::NotIn => "not in",
             }
         )
     }
@@ -617,6 +1088,7 @@ impl std::fmt::Display for UnaryOperator {
             match self {
                 Self::Minus => "-",
                 Self::Not => "!",
+                Self::Invert => "~",
             }
         )
     }
@@ -640,6 +1112,115 @@ impl std::fmt::Display for Call {
     }
 }
 
+impl std::fmt::Display for List {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "[{}]",
+            self.items
+                .iter()
+                .map(|i| format!("{i}"))
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+}
+
+impl std::fmt::Display for Dict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{{{}}}",
+            self.items
+                .iter()
+                .map(|(k, v)| format!("{k}: {v}"))
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+}
+
+impl std::fmt::Display for Tuple {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "({})",
+            self.items
+                .iter()
+                .map(|i| format!("{i}"))
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+}
+
+impl std::fmt::Display for FormatString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "f\"")?;
+        for part in self.parts.iter() {
+            match part {
+                FormatPart::Literal(l) => write!(f, "{}", l.replace('{', "{{").replace('}', "}}"))?,
+                FormatPart::Value(v, spec) if *spec == FormatSpec::default() => {
+                    write!(f, "{{{v}}}")?
+                }
+                FormatPart::Value(v, spec) => write!(f, "{{{v}:{spec}}}")?,
+            }
+        }
+        write!(f, "\"")
+    }
+}
+
+impl std::fmt::Display for FormatSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(fill) = self.fill {
+            write!(f, "{fill}")?;
+        }
+        if let Some(align) = self.align {
+            write!(f, "{align}")?;
+        }
+        if let Some(sign) = self.sign {
+            write!(f, "{sign}")?;
+        }
+        if self.zero {
+            write!(f, "0")?;
+        }
+        if let Some(width) = self.width {
+            write!(f, "{width}")?;
+        }
+        if let Some(precision) = self.precision {
+            write!(f, ".{precision}")?;
+        }
+        if let Some(kind) = self.kind {
+            write!(f, "{kind}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for GetItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}[{}]", self.object, self.index)
+    }
+}
+
+impl std::fmt::Display for SetItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}[{}]={}", self.object, self.index, self.value)
+    }
+}
+
+impl std::fmt::Display for GetAttr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.object, self.name)
+    }
+}
+
+impl std::fmt::Display for SetAttr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}={}", self.object, self.name, self.value)
+    }
+}
+
 impl std::fmt::Display for Expr {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.body)
@@ -650,13 +1231,23 @@ impl std::fmt::Display for ExprBody {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Binary(e) => write!(f, "{}", e),
+            Self::Comparison(e) => write!(f, "{}", e),
             Self::Unary(e) => write!(f, "{}", e),
             Self::Grouping(e) => write!(f, "{}", e),
             Self::Value(e) => write!(f, "Value[{}]", e),
             Self::Assign(e) => write!(f, "Assign[{}]", e),
+            Self::AugmentedAssign(e) => write!(f, "AugmentedAssign[{}]", e),
             Self::Logical(e) => write!(f, "{}", e),
             Self::Call(e) => write!(f, "Call[{}]", e),
             Self::LoopKeyword(e) => write!(f, "{}", e),
+            Self::List(e) => write!(f, "List{}", e),
+            Self::Dict(e) => write!(f, "Dict{}", e),
+            Self::Tuple(e) => write!(f, "Tuple{}", e),
+            Self::GetItem(e) => write!(f, "GetItem[{}]", e),
+            Self::SetItem(e) => write!(f, "SetItem[{}]", e),
+            Self::GetAttr(e) => write!(f, "GetAttr[{}]", e),
+            Self::SetAttr(e) => write!(f, "SetAttr[{}]", e),
+            Self::FormatString(e) => write!(f, "FormatString[{}]", e),
         }
     }
 }
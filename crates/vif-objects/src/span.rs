@@ -2,11 +2,21 @@
 pub struct Span {
     line: usize,
     index: usize,
+    // lines of the modules coming before this one in the program sources
+    offset: usize,
 }
 
 impl Span {
     pub fn new(line: usize, index: usize) -> Self {
-        Span { line, index }
+        Span {
+            line,
+            index,
+            offset: 0,
+        }
+    }
+
+    pub fn set_offset(&mut self, offset: usize) {
+        self.offset = offset
     }
 
     pub fn new_line(&mut self) {
@@ -18,6 +28,11 @@ impl Span {
         self.line
     }
 
+    // the column of the last character scanned, from 1
+    pub fn get_column(&self) -> usize {
+        self.index
+    }
+
     pub fn incr_index(&mut self) {
         self.index += 1
     }
@@ -26,8 +41,14 @@ impl Span {
         self.line -= 1
     }
 
+    pub fn get_row<'a>(&self, content: &'a str) -> &'a str {
+        content
+            .split('\n')
+            .nth(self.offset + self.line - 1)
+            .unwrap()
+    }
+
     pub fn format(&self, content: &str, msg: &str) -> String {
-        let row = content.split('\n').nth(self.line - 1).unwrap();
-        format!("Line {} - {row}\n{msg}", self.line)
+        format!("Line {} - {}\n{msg}", self.line, self.get_row(content))
     }
 }
@@ -1,11 +1,16 @@
 use vif_objects::span::Span;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum ScannerError {
     UnclosedString(UnclosedString),
     Indentation(IndentationError),
     EOF(EOFError),
     Unidentified(UnidentifiedError),
+    FormatString(FormatStringError),
+    UnknownEscape(UnknownEscape),
+    InvalidCodePoint(InvalidCodePoint),
+    InvalidNumber(InvalidNumber),
+    NumberOverflow(NumberOverflow),
 }
 
 impl ScannerError {
@@ -15,11 +20,16 @@ impl ScannerError {
             Self::UnclosedString(e) => e.format(content),
             Self::Indentation(e) => e.format(content),
             Self::Unidentified(e) => e.format(content),
+            Self::FormatString(e) => e.format(content),
+            Self::UnknownEscape(e) => e.format(content),
+            Self::InvalidCodePoint(e) => e.format(content),
+            Self::InvalidNumber(e) => e.format(content),
+            Self::NumberOverflow(e) => e.format(content),
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct IndentationError {
     span: Span,
 }
@@ -30,12 +40,12 @@ impl IndentationError {
     }
 
     pub fn format(&self, content: &str) -> String {
-        let row = content.split('\n').nth(self.span.get_line() - 1).unwrap();
+        let row = self.span.get_row(content);
         format!("Line {} - {row}\nIndentation error", self.span.get_line())
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct EOFError {
     span: Span,
 }
@@ -46,12 +56,12 @@ impl EOFError {
     }
 
     pub fn format(&self, content: &str) -> String {
-        let row = content.split('\n').nth(self.span.get_line() - 1).unwrap();
+        let row = self.span.get_row(content);
         format!("Line {} - {row}\nEOF", self.span.get_line())
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct UnidentifiedError {
     span: Span,
     value: String,
@@ -63,7 +73,7 @@ impl UnidentifiedError {
     }
 
     pub fn format(&self, content: &str) -> String {
-        let row = content.split('\n').nth(self.span.get_line() - 1).unwrap();
+        let row = self.span.get_row(content);
         format!(
             "Line {} - {row}\nUndidentified characters: {}",
             self.span.get_line(),
@@ -72,7 +82,7 @@ impl UnidentifiedError {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct UnclosedString {
     span: Span,
 }
@@ -83,10 +93,133 @@ impl UnclosedString {
     }
 
     pub fn format(&self, content: &str) -> String {
-        let row = content.split('\n').nth(self.span.get_line() - 1).unwrap();
+        let row = self.span.get_row(content);
         format!(
             "Line {} - {row}\nString is not closed",
             self.span.get_line()
         )
     }
 }
+
+#[derive(Debug, Clone)]
+pub struct FormatStringError {
+    span: Span,
+    msg: String,
+}
+
+impl FormatStringError {
+    pub fn new(span: Span, msg: &str) -> ScannerError {
+        ScannerError::FormatString(Self {
+            span,
+            msg: msg.to_owned(),
+        })
+    }
+
+    pub fn format(&self, content: &str) -> String {
+        let row = self.span.get_row(content);
+        format!("Line {} - {row}\n{}", self.span.get_line(), self.msg)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UnknownEscape {
+    span: Span,
+    sequence: String,
+}
+
+impl UnknownEscape {
+    pub fn new(span: Span, sequence: String) -> ScannerError {
+        ScannerError::UnknownEscape(Self { span, sequence })
+    }
+
+    // the backslash is pointed at, below the row
+    pub fn format(&self, content: &str) -> String {
+        let row = self.span.get_row(content);
+        let prefix = format!("Line {} - ", self.span.get_line());
+        let column = prefix.len() + self.span.get_column() - 1;
+        format!(
+            "{prefix}{row}\n{}^\nUnknown escape sequence: {}",
+            " ".repeat(column),
+            self.sequence
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct InvalidCodePoint {
+    span: Span,
+    sequence: String,
+}
+
+impl InvalidCodePoint {
+    pub fn new(span: Span, sequence: String) -> ScannerError {
+        ScannerError::InvalidCodePoint(Self { span, sequence })
+    }
+
+    pub fn format(&self, content: &str) -> String {
+        let row = self.span.get_row(content);
+        let prefix = format!("Line {} - ", self.span.get_line());
+        let column = prefix.len() + self.span.get_column() - 1;
+        format!(
+            "{prefix}{row}\n{}^\nInvalid code point: {}",
+            " ".repeat(column),
+            self.sequence
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct InvalidNumber {
+    span: Span,
+    literal: String,
+}
+
+impl InvalidNumber {
+    pub fn new(span: Span, literal: String) -> ScannerError {
+        ScannerError::InvalidNumber(Self { span, literal })
+    }
+
+    pub fn format(&self, content: &str) -> String {
+        let row = self.span.get_row(content);
+        format!(
+            "Line {} - {row}\nInvalid number: {}",
+            self.span.get_line(),
+            self.literal
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NumberOverflow {
+    span: Span,
+    literal: String,
+    float: bool,
+}
+
+impl NumberOverflow {
+    pub fn new(span: Span, literal: String) -> ScannerError {
+        ScannerError::NumberOverflow(Self {
+            span,
+            literal,
+            float: false,
+        })
+    }
+
+    pub fn new_float(span: Span, literal: String) -> ScannerError {
+        ScannerError::NumberOverflow(Self {
+            span,
+            literal,
+            float: true,
+        })
+    }
+
+    pub fn format(&self, content: &str) -> String {
+        let row = self.span.get_row(content);
+        format!(
+            "Line {} - {row}\n{} {} does not fit in 64 bits",
+            self.span.get_line(),
+            if self.float { "Float" } else { "Integer" },
+            self.literal
+        )
+    }
+}
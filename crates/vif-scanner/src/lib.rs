@@ -4,5 +4,6 @@ mod token;
 
 pub use error::ScannerError;
 pub use scanner::Scanner;
+pub use token::FormatStringPart;
 pub use token::Token;
 pub use token::TokenType;
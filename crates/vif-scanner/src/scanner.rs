@@ -1,8 +1,14 @@
 use crate::error::EOFError;
+use crate::error::FormatStringError;
 use crate::error::IndentationError;
+use crate::error::InvalidCodePoint;
+use crate::error::InvalidNumber;
+use crate::error::NumberOverflow;
 use crate::error::ScannerError;
 use crate::error::UnclosedString;
 use crate::error::UnidentifiedError;
+use crate::error::UnknownEscape;
+use crate::token::FormatStringPart;
 use crate::token::Token;
 use crate::token::TokenType;
 use std::iter::Peekable;
@@ -11,7 +17,8 @@ use vif_loader::log;
 use vif_objects::span::Span;
 
 pub struct Scanner<'a> {
-    next: Option<Token>,
+    // a token peeked at, or the error scanning it gave
+    next: Option<Result<Token, ScannerError>>,
     tokenizer: Tokenizer<'a>,
 }
 
@@ -23,26 +30,36 @@ impl<'a> Scanner<'a> {
         }
     }
 
-    fn advance(&mut self) -> Result<(), ScannerError> {
-        self.next = Some(self.tokenizer.scan()?);
-        Ok(())
+    // scans a piece of source taken out of a bigger one, from the line it is on
+    pub fn new_at_line(source: &'a str, line: usize) -> Self {
+        let mut tokenizer = Tokenizer::new(source);
+        tokenizer.span = Span::new(line.saturating_sub(1), 0);
+
+        Self {
+            next: None,
+            tokenizer,
+        }
     }
 
     pub fn check(&mut self, token_type: &TokenType) -> bool {
         self.peek().is_ok_and(|t| &t.r#type == token_type)
     }
 
+    // an error is kept until scanned, so that peeking does not lose it
     pub fn peek(&mut self) -> Result<&Token, ScannerError> {
         if self.next.is_none() {
-            self.advance()?;
+            self.next = Some(self.tokenizer.scan());
         };
 
-        return Ok(self.next.as_ref().unwrap());
+        match self.next.as_ref().unwrap() {
+            Ok(token) => Ok(token),
+            Err(e) => Err(e.clone()),
+        }
     }
 
     pub fn scan(&mut self) -> Result<Token, ScannerError> {
-        if self.next.is_some() {
-            return Ok(self.next.take().unwrap());
+        if let Some(next) = self.next.take() {
+            return next;
         };
 
         self.tokenizer.scan()
@@ -62,6 +79,8 @@ pub struct Tokenizer<'a> {
     span: Span,
     line_start: bool,
     indent_stack: Vec<u8>,
+    // indentation of a line closing several blocks, already consumed by its first dedent
+    pending_indentation: Option<u8>,
 }
 
 impl<'a> Tokenizer<'a> {
@@ -71,6 +90,7 @@ impl<'a> Tokenizer<'a> {
             span: Span::new(0, 0),
             line_start: true,
             indent_stack: Vec::new(),
+            pending_indentation: None,
         }
     }
 
@@ -101,6 +121,7 @@ impl<'a> Tokenizer<'a> {
                 '}' => TokenType::RightAccolade,
                 '%' => TokenType::Modulo,
                 ',' => TokenType::Comma,
+                '.' => TokenType::Dot,
                 ':' => TokenType::DoubleDot,
                 ';' => TokenType::Semicolon,
                 '@' => TokenType::At,
@@ -119,14 +140,39 @@ impl<'a> Tokenizer<'a> {
                     true => TokenType::EqualEqual,
                     false => TokenType::Equal,
                 },
-                '<' => match self.r#match('=') {
-                    true => TokenType::LessEqual,
-                    false => TokenType::Less,
+                '<' => match self.r#match('<') {
+                    true => match self.r#match('=') {
+                        true => TokenType::LessLessEqual,
+                        false => TokenType::LessLess,
+                    },
+                    false => match self.r#match('=') {
+                        true => TokenType::LessEqual,
+                        false => TokenType::Less,
+                    },
+                },
+                '>' => match self.r#match('>') {
+                    true => match self.r#match('=') {
+                        true => TokenType::GreaterGreaterEqual,
+                        false => TokenType::GreaterGreater,
+                    },
+                    false => match self.r#match('=') {
+                        true => TokenType::GreaterEqual,
+                        false => TokenType::Greater,
+                    },
+                },
+                '&' => match self.r#match('=') {
+                    true => TokenType::AmpersandEqual,
+                    false => TokenType::Ampersand,
                 },
-                '>' => match self.r#match('=') {
-                    true => TokenType::GreaterEqual,
-                    false => TokenType::Greater,
+                '|' => match self.r#match('=') {
+                    true => TokenType::PipeEqual,
+                    false => TokenType::Pipe,
                 },
+                '^' => match self.r#match('=') {
+                    true => TokenType::CaretEqual,
+                    false => TokenType::Caret,
+                },
+                '~' => TokenType::Tilde,
                 '+' => match self.r#match('=') {
                     true => TokenType::PlusEqual,
                     false => TokenType::Plus,
@@ -136,19 +182,43 @@ impl<'a> Tokenizer<'a> {
                         self.advance().unwrap();
                         TokenType::MinusEqual
                     }
+                    '>' => {
+                        self.advance().unwrap();
+                        TokenType::Arrow
+                    }
                     _ => TokenType::Minus,
                 },
-                '/' => match self.r#match('=') {
-                    true => TokenType::SlashEqual,
-                    false => TokenType::Slash,
+                '/' => match self.r#match('/') {
+                    true => match self.r#match('=') {
+                        true => TokenType::SlashSlashEqual,
+                        false => TokenType::SlashSlash,
+                    },
+                    false => match self.r#match('=') {
+                        true => TokenType::SlashEqual,
+                        false => TokenType::Slash,
+                    },
                 },
-                '*' => match self.r#match('=') {
-                    true => TokenType::StarEqual,
-                    false => TokenType::Star,
+                '*' => match self.r#match('*') {
+                    true => match self.r#match('=') {
+                        true => TokenType::StarStarEqual,
+                        false => TokenType::StarStar,
+                    },
+                    false => match self.r#match('=') {
+                        true => TokenType::StarEqual,
+                        false => TokenType::Star,
+                    },
                 },
-                d if d.is_digit(10) => self.parse_number(d),
-                c if c.is_ascii_alphabetic() => self.parse_identifier(c),
-                '"' => self.parse_string()?,
+                d if d.is_digit(10) => self.parse_number(d)?,
+                'r' if self.peek() == &'"' => {
+                    self.advance().unwrap();
+                    self.parse_string(true)?
+                }
+                'f' if self.peek() == &'"' => {
+                    self.advance().unwrap();
+                    self.parse_format_string()?
+                }
+                c if c.is_ascii_alphabetic() || c == '_' => self.parse_identifier(c),
+                '"' => self.parse_string(false)?,
                 ' ' => TokenType::Ignore,
                 '\t' => TokenType::Ignore,
                 '\r' => TokenType::Ignore,
@@ -198,30 +268,38 @@ impl<'a> Tokenizer<'a> {
     }
 
     fn parse_indentation(&mut self) -> Result<TokenType, ScannerError> {
-        let mut stack = 0;
         self.span.new_line();
         self.line_start = false;
 
-        loop {
-            match self.peek() {
-                ' ' => {
-                    self.advance().unwrap();
-                    stack += 1;
-                }
-                '\t' => {
-                    self.advance().unwrap();
-                    stack += 4;
-                }
-                '\n' => {
-                    self.advance().unwrap();
-                    return Ok(TokenType::IgnoreNewLine);
-                }
-                '\0' => {
-                    return Ok(TokenType::EOF);
+        let stack = match self.pending_indentation.take() {
+            Some(stack) => stack,
+            None => {
+                let mut stack = 0;
+
+                loop {
+                    match self.peek() {
+                        ' ' => {
+                            self.advance().unwrap();
+                            stack += 1;
+                        }
+                        '\t' => {
+                            self.advance().unwrap();
+                            stack += 4;
+                        }
+                        '\n' => {
+                            self.advance().unwrap();
+                            return Ok(TokenType::IgnoreNewLine);
+                        }
+                        '\0' => {
+                            return Ok(TokenType::EOF);
+                        }
+                        _ => break,
+                    }
                 }
-                _ => break,
+
+                stack
             }
-        }
+        };
 
         if self.indent_stack.is_empty() {
             self.indent_stack.push(stack);
@@ -245,6 +323,7 @@ impl<'a> Tokenizer<'a> {
                 // so we return here but we decrease the line by 1 as it'll be incr back next iteration
                 self.span.decr_line();
                 self.line_start = true;
+                self.pending_indentation = Some(stack);
                 TokenType::Dedent
             } else {
                 return Err(IndentationError::new(self.span.clone()));
@@ -254,32 +333,106 @@ impl<'a> Tokenizer<'a> {
         Ok(token)
     }
 
-    fn parse_number(&mut self, initial: char) -> TokenType {
-        let mut str = String::from(initial);
+    // 42, 1_000, 0xFF, 0o755, 0b1010, 1.5 or 2.5E3
+    fn parse_number(&mut self, initial: char) -> Result<TokenType, ScannerError> {
+        let mut literal = String::from(initial);
 
-        loop {
-            let c = self.peek();
-            if !c.is_digit(10) {
-                break;
+        let radix = match (initial, *self.peek()) {
+            ('0', 'x' | 'X') => 16,
+            ('0', 'o' | 'O') => 8,
+            ('0', 'b' | 'B') => 2,
+            _ => 10,
+        };
+
+        if radix != 10 {
+            literal.push(self.advance().unwrap());
+            let digits = self.parse_digits(radix, &mut literal, true)?;
+            if digits.is_empty() {
+                return Err(InvalidNumber::new(self.span.clone(), literal));
             }
-            str.push(self.advance().unwrap());
+            self.check_number_end(&literal)?;
+
+            return i64::from_str_radix(&digits, radix)
+                .map(TokenType::ValueInteger)
+                .map_err(|_| NumberOverflow::new(self.span.clone(), literal));
         }
-        if self.peek() == &'.' {
-            str.push(self.advance().unwrap());
-            loop {
-                let c = self.peek();
-                if !c.is_digit(10) {
-                    break;
-                }
-                str.push(self.advance().unwrap());
+
+        let mut number = String::from(initial);
+        let mut is_float = false;
+        number.push_str(&self.parse_digits(10, &mut literal, true)?);
+
+        if self.r#match('.') {
+            is_float = true;
+            literal.push('.');
+            number.push('.');
+            number.push_str(&self.parse_digits(10, &mut literal, false)?);
+        }
+
+        if matches!(self.peek(), 'e' | 'E') {
+            is_float = true;
+            literal.push(self.advance().unwrap());
+            number.push('e');
+            if matches!(self.peek(), '+' | '-') {
+                let sign = self.advance().unwrap();
+                literal.push(sign);
+                number.push(sign);
+            }
+
+            let exponent = self.parse_digits(10, &mut literal, false)?;
+            if exponent.is_empty() {
+                return Err(InvalidNumber::new(self.span.clone(), literal));
             }
+            number.push_str(&exponent);
         }
 
-        if str.contains('.') {
-            TokenType::ValueFloat(str.parse::<f64>().unwrap())
+        self.check_number_end(&literal)?;
+
+        if is_float {
+            // 1e400 parses to infinity, while 1e-400 rounds down to 0
+            let value = number.parse::<f64>().unwrap();
+            if value.is_infinite() {
+                return Err(NumberOverflow::new_float(self.span.clone(), literal));
+            }
+            Ok(TokenType::ValueFloat(value))
         } else {
-            TokenType::ValueInteger(str.parse::<i64>().unwrap())
+            number
+                .parse::<i64>()
+                .map(TokenType::ValueInteger)
+                .map_err(|_| NumberOverflow::new(self.span.clone(), literal))
+        }
+    }
+
+    // digits can be grouped by single underscores, returns them without the underscores
+    fn parse_digits(
+        &mut self,
+        radix: u32,
+        literal: &mut String,
+        follows_digit: bool,
+    ) -> Result<String, ScannerError> {
+        let mut part = String::new();
+        while self.peek().is_digit(radix) || self.peek() == &'_' {
+            part.push(self.advance().unwrap());
+        }
+        literal.push_str(&part);
+
+        if part.contains("__") || part.ends_with('_') || (!follows_digit && part.starts_with('_')) {
+            return Err(InvalidNumber::new(self.span.clone(), literal.clone()));
         }
+
+        Ok(part.replace('_', ""))
+    }
+
+    // 0b102 or 12abc are not a number followed by something else
+    fn check_number_end(&mut self, literal: &str) -> Result<(), ScannerError> {
+        let next = *self.peek();
+        if next.is_ascii_alphanumeric() || next == '_' {
+            return Err(InvalidNumber::new(
+                self.span.clone(),
+                format!("{literal}{next}"),
+            ));
+        }
+
+        Ok(())
     }
 
     fn parse_identifier(&mut self, initial: char) -> TokenType {
@@ -297,6 +450,7 @@ impl<'a> Tokenizer<'a> {
             "not" => TokenType::Not,
             "and" => TokenType::And,
             "or" => TokenType::Or,
+            "in" => TokenType::In,
             "def" => TokenType::Def,
             "class" => TokenType::Class,
             "if" => TokenType::If,
@@ -315,37 +469,264 @@ impl<'a> Tokenizer<'a> {
             "str" => TokenType::Str,
             "float" => TokenType::Float,
             "bool" => TokenType::Bool,
+            "list" => TokenType::List,
+            "dict" => TokenType::Dict,
             "@" => TokenType::At,
             "break" => TokenType::Break,
             "continue" => TokenType::Continue,
             "assert" => TokenType::Assert,
+            "import" => TokenType::Import,
+            "from" => TokenType::From,
+            "try" => TokenType::Try,
+            "except" => TokenType::Except,
+            "finally" => TokenType::Finally,
+            "raise" => TokenType::Raise,
+            "as" => TokenType::As,
             _ => TokenType::ValueIdentifier(str),
         }
     }
 
-    fn parse_string(&mut self) -> Result<TokenType, ScannerError> {
+    // "a", r"a" without escapes, or """a""" spanning several lines. The content of a multi-line
+    // string is not indentation, so the lines are only counted
+    fn parse_string(&mut self, raw: bool) -> Result<TokenType, ScannerError> {
+        // an unclosed string is reported where it opens, not at the end of the file
+        let start = self.span.clone();
+        let mut triple = false;
+        if self.r#match('"') {
+            // two quotes are an empty string, three open a multi-line one
+            if !self.r#match('"') {
+                return Ok(TokenType::ValueString(String::new()));
+            }
+            triple = true;
+        }
+
         let mut str = String::new();
         loop {
             match self.peek() {
                 &'"' => {
                     self.advance().unwrap();
-                    break;
+                    if !triple {
+                        break;
+                    }
+
+                    let mut quotes = 1;
+                    while quotes < 3 && self.r#match('"') {
+                        quotes += 1;
+                    }
+                    if quotes == 3 {
+                        break;
+                    }
+                    str.push_str(&"\"".repeat(quotes));
                 }
                 &'\0' => {
+                    return Err(UnclosedString::new(start));
+                }
+                &'\n' if !triple => {
                     return Err(UnclosedString::new(self.span.clone()));
                 }
                 &'\n' => {
-                    return Err(UnclosedString::new(self.span.clone()));
+                    str.push(self.advance().unwrap());
+                    self.span.new_line();
+                }
+                // a raw string keeps its backslashes, which still stop a quote from closing it
+                &'\\' if raw => {
+                    str.push(self.advance().unwrap());
+                    if matches!(self.peek(), '"' | '\\') {
+                        str.push(self.advance().unwrap());
+                    }
+                }
+                &'\\' => {
+                    self.advance().unwrap();
+                    if let Some(c) = self.parse_escape()? {
+                        str.push(c);
+                    }
                 }
                 _ => str.push(self.advance().unwrap()),
             };
         }
         Ok(TokenType::ValueString(str))
     }
+
+    // the character a backslash stands for along with what follows it, nothing for a
+    // backslash ending the line
+    fn parse_escape(&mut self) -> Result<Option<char>, ScannerError> {
+        let span = self.span.clone();
+        let unknown = |sequence: String| UnknownEscape::new(span.clone(), sequence);
+        let invalid = |sequence: String| InvalidCodePoint::new(span.clone(), sequence);
+
+        let c = match self.peek() {
+            &'\0' => return Err(UnclosedString::new(self.span.clone())),
+            _ => self.advance().unwrap(),
+        };
+
+        Ok(Some(match c {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            '\\' => '\\',
+            '"' => '"',
+            '\'' => '\'',
+            '\n' => {
+                self.span.new_line();
+                return Ok(None);
+            }
+            // \x41
+            'x' => {
+                let mut digits = String::new();
+                while digits.len() < 2 && self.peek().is_ascii_hexdigit() {
+                    digits.push(self.advance().unwrap());
+                }
+                u32::from_str_radix(&digits, 16)
+                    .ok()
+                    .filter(|_| digits.len() == 2)
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| unknown(format!("\\x{digits}")))?
+            }
+            // \u{e9}
+            'u' => {
+                let mut sequence = String::from("\\u");
+                if !self.r#match('{') {
+                    return Err(unknown(sequence));
+                }
+                sequence.push('{');
+
+                let mut digits = String::new();
+                while digits.len() < 6 && self.peek().is_ascii_hexdigit() {
+                    digits.push(self.advance().unwrap());
+                }
+                sequence.push_str(&digits);
+                if !self.r#match('}') {
+                    return Err(unknown(sequence));
+                }
+                sequence.push('}');
+
+                // surrogates and values past 10FFFF are not characters
+                u32::from_str_radix(&digits, 16)
+                    .map_err(|_| unknown(sequence.clone()))
+                    .and_then(|n| char::from_u32(n).ok_or_else(|| invalid(sequence)))?
+            }
+            c => return Err(unknown(format!("\\{c}"))),
+        }))
+    }
+
+    // f"total: {count} items", braces are escaped by doubling them
+    fn parse_format_string(&mut self) -> Result<TokenType, ScannerError> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        loop {
+            match self.peek() {
+                &'"' => {
+                    self.advance().unwrap();
+                    break;
+                }
+                &'\0' | &'\n' => {
+                    return Err(UnclosedString::new(self.span.clone()));
+                }
+                &'{' => {
+                    self.advance().unwrap();
+                    if self.r#match('{') {
+                        literal.push('{');
+                    } else {
+                        if !literal.is_empty() {
+                            parts.push(FormatStringPart::Literal(std::mem::take(&mut literal)));
+                        }
+                        parts.push(self.parse_format_field()?);
+                    }
+                }
+                &'\\' => {
+                    self.advance().unwrap();
+                    if let Some(c) = self.parse_escape()? {
+                        literal.push(c);
+                    }
+                }
+                &'}' => {
+                    self.advance().unwrap();
+                    if !self.r#match('}') {
+                        return Err(FormatStringError::new(
+                            self.span.clone(),
+                            "Single '}' is not allowed in a format string",
+                        ));
+                    }
+                    literal.push('}');
+                }
+                _ => literal.push(self.advance().unwrap()),
+            };
+        }
+
+        if !literal.is_empty() {
+            parts.push(FormatStringPart::Literal(literal));
+        }
+        Ok(TokenType::ValueFormatString(parts))
+    }
+
+    // the expression of a field ends with a colon or a brace outside of any bracket,
+    // strings inside of it can hold anything
+    fn parse_format_field(&mut self) -> Result<FormatStringPart, ScannerError> {
+        let mut expression = String::new();
+        let mut depth = 0;
+        loop {
+            match self.peek() {
+                &'\0' | &'\n' => {
+                    return Err(UnclosedString::new(self.span.clone()));
+                }
+                &'"' => {
+                    expression.push(self.advance().unwrap());
+                    while self.peek() != &'"' {
+                        if matches!(self.peek(), '\0' | '\n') {
+                            return Err(UnclosedString::new(self.span.clone()));
+                        }
+                        // escapes are left to the scanning of the expression
+                        if self.peek() == &'\\' {
+                            expression.push(self.advance().unwrap());
+                        }
+                        expression.push(self.advance()?);
+                    }
+                    expression.push(self.advance().unwrap());
+                }
+                &':' | &'}' if depth == 0 => break,
+                &'(' | &'[' | &'{' => {
+                    depth += 1;
+                    expression.push(self.advance().unwrap());
+                }
+                &')' | &']' | &'}' => {
+                    depth -= 1;
+                    expression.push(self.advance().unwrap());
+                }
+                _ => expression.push(self.advance().unwrap()),
+            }
+        }
+
+        if expression.trim().is_empty() {
+            return Err(FormatStringError::new(
+                self.span.clone(),
+                "Empty expression in a format string",
+            ));
+        }
+
+        let mut spec = None;
+        if self.r#match(':') {
+            let mut s = String::new();
+            loop {
+                match self.peek() {
+                    &'\0' | &'\n' | &'"' => {
+                        return Err(UnclosedString::new(self.span.clone()));
+                    }
+                    &'}' => break,
+                    _ => s.push(self.advance().unwrap()),
+                }
+            }
+            spec = Some(s);
+        }
+        self.advance().unwrap();
+
+        Ok(FormatStringPart::Field(expression, spec))
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::FormatStringPart;
     use super::Scanner;
     use super::TokenType;
 
@@ -364,6 +745,117 @@ mod tests {
         );
     }
 
+    #[test]
+    fn string_escapes() {
+        let string = r#""a\tb\n\"c\" \\ \u{e9}\x41" r"\n""#;
+        let mut scanner = Scanner::new(string);
+
+        assert_eq!(
+            scanner.tokenizer.scan_token().unwrap().r#type,
+            TokenType::ValueString("a\tb\n\"c\" \\ éA".to_owned())
+        );
+        assert_eq!(
+            scanner.tokenizer.scan_token().unwrap().r#type,
+            TokenType::ValueString("\\n".to_owned())
+        );
+    }
+
+    #[test]
+    fn unknown_escape() {
+        let string = "\"ab\\q\"";
+        let mut scanner = Scanner::new(string);
+
+        let err = scanner.tokenizer.scan_token().unwrap_err();
+        assert_eq!(
+            err.format(string),
+            "Line 1 - \"ab\\q\"\n            ^\nUnknown escape sequence: \\q"
+        );
+    }
+
+    #[test]
+    fn raw_string_escaped_quote() {
+        let string = r#"r"a\"b""#;
+        let mut scanner = Scanner::new(string);
+
+        assert_eq!(
+            scanner.tokenizer.scan_token().unwrap().r#type,
+            TokenType::ValueString("a\\\"b".to_owned())
+        );
+    }
+
+    #[test]
+    fn invalid_code_point() {
+        for sequence in ["\\u{d800}", "\\u{110000}"] {
+            let string = format!("\"{sequence}\"");
+            let mut scanner = Scanner::new(&string);
+
+            let err = scanner.tokenizer.scan_token().unwrap_err();
+            assert!(err
+                .format(&string)
+                .ends_with(&format!("Invalid code point: {sequence}")));
+        }
+    }
+
+    #[test]
+    fn unclosed_multi_line_string() {
+        let string = "var a = 1\nvar b = \"\"\"a\nb\nc\n";
+        let mut scanner = Scanner::new(string);
+
+        let err = loop {
+            if let Err(err) = scanner.tokenizer.scan_token() {
+                break err;
+            }
+        };
+        assert_eq!(
+            err.format(string),
+            "Line 2 - var b = \"\"\"a\nString is not closed"
+        );
+    }
+
+    #[test]
+    fn multi_line_string() {
+        let string = "if True:\n    \"\"\"a\n  b \"\" c\n\"\"\"\n    True\n";
+        let mut scanner = Scanner::new(string);
+
+        let mut tokens = Vec::new();
+        while let Ok(token) = scanner.tokenizer.scan_token() {
+            if token.r#type == TokenType::EOF {
+                break;
+            }
+            tokens.push(token.r#type);
+        }
+
+        assert_eq!(
+            tokens,
+            vec![
+                TokenType::If,
+                TokenType::True,
+                TokenType::DoubleDot,
+                TokenType::NewLine,
+                TokenType::Indent,
+                TokenType::ValueString("a\n  b \"\" c\n".to_owned()),
+                TokenType::NewLine,
+                TokenType::True,
+                TokenType::NewLine,
+            ]
+        );
+    }
+
+    #[test]
+    fn format_string() {
+        let string = "f\"{{a}} {b[\"c\"]:>5} d\"\n";
+        let mut scanner = Scanner::new(string);
+
+        assert_eq!(
+            scanner.tokenizer.scan_token().unwrap().r#type,
+            TokenType::ValueFormatString(vec![
+                FormatStringPart::Literal("{a} ".to_owned()),
+                FormatStringPart::Field("b[\"c\"]".to_owned(), Some(">5".to_owned())),
+                FormatStringPart::Literal(" d".to_owned()),
+            ])
+        );
+    }
+
     #[test]
     fn simple_number() {
         let string = "1\n";
@@ -428,6 +920,111 @@ mod tests {
         );
     }
 
+    #[test]
+    fn number_literals() {
+        let string = "0xFF 0o755 0b1010 0x_7fff_ffff_ffff_ffff 1_000_000 1e-9 2.5E3 1.e2 1_0.2_5\n";
+        let mut scanner = Scanner::new(string);
+
+        let expected = vec![
+            TokenType::ValueInteger(255),
+            TokenType::ValueInteger(493),
+            TokenType::ValueInteger(10),
+            TokenType::ValueInteger(i64::MAX),
+            TokenType::ValueInteger(1_000_000),
+            TokenType::ValueFloat(1e-9),
+            TokenType::ValueFloat(2500.),
+            TokenType::ValueFloat(100.),
+            TokenType::ValueFloat(10.25),
+        ];
+        for token_type in expected {
+            assert_eq!(scanner.tokenizer.scan_token().unwrap().r#type, token_type);
+        }
+    }
+
+    #[test]
+    fn invalid_numbers() {
+        for string in ["0b102", "1__0", "1_", "0x", "1e", "1.5_", "12abc"] {
+            let mut scanner = Scanner::new(string);
+            let err = scanner.tokenizer.scan_token().unwrap_err();
+            assert!(
+                err.format(string).contains("Invalid number"),
+                "{string} should be invalid"
+            );
+        }
+    }
+
+    #[test]
+    fn integer_overflow() {
+        let string = "9223372036854775808";
+        let mut scanner = Scanner::new(string);
+
+        let err = scanner.tokenizer.scan_token().unwrap_err();
+        assert_eq!(
+            err.format(string),
+            "Line 1 - 9223372036854775808\nInteger 9223372036854775808 does not fit in 64 bits"
+        );
+    }
+
+    #[test]
+    fn float_overflow() {
+        let string = "1.5e400";
+        let mut scanner = Scanner::new(string);
+
+        let err = scanner.tokenizer.scan_token().unwrap_err();
+        assert_eq!(
+            err.format(string),
+            "Line 1 - 1.5e400\nFloat 1.5e400 does not fit in 64 bits"
+        );
+    }
+
+    #[test]
+    fn arithmetic_operators() {
+        let string = "* *= ** **= / /= // //= %\n";
+        let mut scanner = Scanner::new(string);
+
+        let expected = vec![
+            TokenType::Star,
+            TokenType::StarEqual,
+            TokenType::StarStar,
+            TokenType::StarStarEqual,
+            TokenType::Slash,
+            TokenType::SlashEqual,
+            TokenType::SlashSlash,
+            TokenType::SlashSlashEqual,
+            TokenType::Modulo,
+        ];
+        for token_type in expected {
+            assert_eq!(scanner.tokenizer.scan_token().unwrap().r#type, token_type);
+        }
+    }
+
+    #[test]
+    fn bitwise_operators() {
+        let string = "& &= | |= ^ ^= ~ << <<= >> >>= < <= > >=\n";
+        let mut scanner = Scanner::new(string);
+
+        let expected = vec![
+            TokenType::Ampersand,
+            TokenType::AmpersandEqual,
+            TokenType::Pipe,
+            TokenType::PipeEqual,
+            TokenType::Caret,
+            TokenType::CaretEqual,
+            TokenType::Tilde,
+            TokenType::LessLess,
+            TokenType::LessLessEqual,
+            TokenType::GreaterGreater,
+            TokenType::GreaterGreaterEqual,
+            TokenType::Less,
+            TokenType::LessEqual,
+            TokenType::Greater,
+            TokenType::GreaterEqual,
+        ];
+        for token_type in expected {
+            assert_eq!(scanner.tokenizer.scan_token().unwrap().r#type, token_type);
+        }
+    }
+
     #[test]
     fn simple_identifier() {
         let string = "cou\n";
@@ -651,6 +1248,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn consecutive_dedents() {
+        let string = "True\n    True\n        True\n            True\n    True\n";
+        let mut scanner = Scanner::new(string);
+
+        let tokens = std::iter::from_fn(|| match scanner.tokenizer.scan_token() {
+            Ok(t) if t.r#type == TokenType::EOF => None,
+            t => Some(t.unwrap().r#type),
+        })
+        .filter(|t| t != &TokenType::Ignore)
+        .collect::<Vec<TokenType>>();
+
+        // the last line closes two blocks out of three
+        assert_eq!(
+            tokens,
+            vec![
+                TokenType::True,
+                TokenType::NewLine,
+                TokenType::Indent,
+                TokenType::True,
+                TokenType::NewLine,
+                TokenType::Indent,
+                TokenType::True,
+                TokenType::NewLine,
+                TokenType::Indent,
+                TokenType::True,
+                TokenType::NewLine,
+                TokenType::Dedent,
+                TokenType::Dedent,
+                TokenType::True,
+                TokenType::NewLine,
+            ]
+        );
+    }
+
     #[test]
     fn simple_comment() {
         let string = "True\n# this is a comment\nFalse";
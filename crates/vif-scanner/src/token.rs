@@ -1,6 +1,14 @@
 use std::fmt::Debug;
 use std::fmt::Display;
 
+// replacement fields keep the source of their expression and of their format spec,
+// which are parsed along with the rest of the expressions
+#[derive(Debug, PartialEq, Clone)]
+pub enum FormatStringPart {
+    Literal(String),
+    Field(String, Option<String>),
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum TokenType {
     // singles char
@@ -11,7 +19,7 @@ pub enum TokenType {
     LeftAccolade,
     RightAccolade,
     Comma,
-    // Dot, no need ? Only for numbers ?
+    Dot,
     DoubleDot,
     Minus,
     Plus,
@@ -25,6 +33,10 @@ pub enum TokenType {
     Less,
     NewLine,
     At,
+    Ampersand,
+    Pipe,
+    Caret,
+    Tilde,
 
     // multi chars
     EqualEqual,
@@ -35,10 +47,23 @@ pub enum TokenType {
     MinusEqual,
     StarEqual,
     SlashEqual,
+    StarStar,
+    StarStarEqual,
+    SlashSlash,
+    SlashSlashEqual,
+    LessLess,
+    GreaterGreater,
+    AmpersandEqual,
+    PipeEqual,
+    CaretEqual,
+    LessLessEqual,
+    GreaterGreaterEqual,
+    Arrow,
 
     // literals
     ValueIdentifier(String),
     ValueString(String),
+    ValueFormatString(Vec<FormatStringPart>),
     ValueInteger(i64),
     ValueFloat(f64),
     Comment(String),
@@ -55,6 +80,7 @@ pub enum TokenType {
     Mut,
     For,
     Or,
+    In,
     None,
     True,
     False,
@@ -62,12 +88,21 @@ pub enum TokenType {
     Float,
     Str,
     Bool,
+    List,
+    Dict,
     Return,
     Self_,
     While,
     Break,
     Continue,
     Assert,
+    Import,
+    From,
+    Try,
+    Except,
+    Finally,
+    Raise,
+    As,
 
     // indents
     Indent,
@@ -111,6 +146,7 @@ impl Display for TokenType {
             TokenType::LeftAccolade => write!(f, "{{"),
             TokenType::RightAccolade => write!(f, "}}"),
             TokenType::Comma => write!(f, ","),
+            TokenType::Dot => write!(f, "."),
             TokenType::DoubleDot => write!(f, ":"),
             TokenType::Minus => write!(f, "-"),
             TokenType::Plus => write!(f, "+"),
@@ -136,9 +172,28 @@ impl Display for TokenType {
             TokenType::Float => write!(f, "float"),
             TokenType::Bool => write!(f, "bool"),
             TokenType::Str => write!(f, "str"),
+            TokenType::List => write!(f, "list"),
+            TokenType::Dict => write!(f, "dict"),
             TokenType::SlashEqual => write!(f, "/="),
+            TokenType::StarStar => write!(f, "**"),
+            TokenType::StarStarEqual => write!(f, "**="),
+            TokenType::SlashSlash => write!(f, "//"),
+            TokenType::SlashSlashEqual => write!(f, "//="),
+            TokenType::Ampersand => write!(f, "&"),
+            TokenType::Pipe => write!(f, "|"),
+            TokenType::Caret => write!(f, "^"),
+            TokenType::Tilde => write!(f, "~"),
+            TokenType::LessLess => write!(f, "<<"),
+            TokenType::GreaterGreater => write!(f, ">>"),
+            TokenType::AmpersandEqual => write!(f, "&="),
+            TokenType::PipeEqual => write!(f, "|="),
+            TokenType::CaretEqual => write!(f, "^="),
+            TokenType::LessLessEqual => write!(f, "<<="),
+            TokenType::GreaterGreaterEqual => write!(f, ">>="),
+            TokenType::Arrow => write!(f, "->"),
             TokenType::ValueIdentifier(v) => write!(f, "Identifier {}", v),
             TokenType::ValueString(v) => write!(f, "String {}", v),
+            TokenType::ValueFormatString(v) => write!(f, "FormatString {:?}", v),
             TokenType::ValueInteger(v) => write!(f, "Integer {}", v),
             TokenType::ValueFloat(v) => write!(f, "Float {}", v),
             TokenType::Comment(v) => write!(f, "Comment {}", v),
@@ -152,6 +207,7 @@ impl Display for TokenType {
             TokenType::Mut => write!(f, "mut"),
             TokenType::For => write!(f, "for"),
             TokenType::Or => write!(f, "or"),
+            TokenType::In => write!(f, "in"),
             TokenType::None => write!(f, "None"),
             TokenType::True => write!(f, "True"),
             TokenType::False => write!(f, "False"),
@@ -166,6 +222,13 @@ impl Display for TokenType {
             TokenType::EOF => write!(f, "EOF"),
             TokenType::Not => write!(f, "not"),
             TokenType::Assert => write!(f, "assert"),
+            TokenType::Import => write!(f, "import"),
+            TokenType::From => write!(f, "from"),
+            TokenType::Try => write!(f, "try"),
+            TokenType::Except => write!(f, "except"),
+            TokenType::Finally => write!(f, "finally"),
+            TokenType::Raise => write!(f, "raise"),
+            TokenType::As => write!(f, "as"),
         }
     }
 }
@@ -5,10 +5,24 @@ pub enum TypingError {
     WrongArgumentNumberFunction(WrongArgumentNumberFunction),
     NonMutableArgumentToMutableParameter(NonMutableArgumentToMutableParameter),
     NonMutableArgumentToMutableVariable(NonMutableArgumentToMutableVariable),
+    NonMutableValueMutation(NonMutableValueMutation),
     DifferentSignatureBetweenFunction(DifferentSignatureBetweenFunction),
     DifferentSignatureBetweenReturns(DifferentSignatureBetweenReturns),
+    WrongReturnType(WrongReturnType),
     FunctionReturnsDifferentTypes(FunctionReturnsDifferentTypes),
     IncompatibleTypes(IncompatibleTypes),
+    UnhashableType(UnhashableType),
+    InvalidFormatSpec(InvalidFormatSpec),
+    InvalidTupleIndex(InvalidTupleIndex),
+    UnknownAttribute(UnknownAttribute),
+    NotIterable(NotIterable),
+    NotCallable(NotCallable),
+    UnknownArgument(UnknownArgument),
+    DuplicatedArgument(DuplicatedArgument),
+    MissingArgument(MissingArgument),
+    AmbiguousParameterType(AmbiguousParameterType),
+    MissingReturn(MissingReturn),
+    UnsupportedOperator(UnsupportedOperator),
 }
 
 impl TypingError {
@@ -17,10 +31,24 @@ impl TypingError {
             Self::WrongArgumentNumberFunction(a) => a.format(content),
             Self::NonMutableArgumentToMutableParameter(a) => a.format(content),
             Self::NonMutableArgumentToMutableVariable(a) => a.format(content),
+            Self::NonMutableValueMutation(a) => a.format(content),
             Self::DifferentSignatureBetweenFunction(a) => a.format(content),
             Self::DifferentSignatureBetweenReturns(a) => a.format(content),
+            Self::WrongReturnType(a) => a.format(content),
             Self::FunctionReturnsDifferentTypes(a) => a.format(content),
             Self::IncompatibleTypes(a) => a.format(content),
+            Self::UnhashableType(a) => a.format(content),
+            Self::InvalidFormatSpec(a) => a.format(content),
+            Self::InvalidTupleIndex(a) => a.format(content),
+            Self::UnknownAttribute(a) => a.format(content),
+            Self::NotIterable(a) => a.format(content),
+            Self::NotCallable(a) => a.format(content),
+            Self::UnknownArgument(a) => a.format(content),
+            Self::DuplicatedArgument(a) => a.format(content),
+            Self::MissingArgument(a) => a.format(content),
+            Self::AmbiguousParameterType(a) => a.format(content),
+            Self::MissingReturn(a) => a.format(content),
+            Self::UnsupportedOperator(a) => a.format(content),
         }
     }
 }
@@ -42,7 +70,7 @@ impl IncompatibleTypes {
     }
 
     fn format(&self, content: &str) -> String {
-        let row = content.split('\n').nth(self.span.get_line() - 1).unwrap();
+        let row = self.span.get_row(content);
         format!(
             "Line {} - {row}\nIncompatible type: {} vs {}",
             self.span.get_line(),
@@ -70,6 +98,7 @@ impl FunctionReturnsDifferentTypes {
 #[derive(Debug)]
 pub struct WrongArgumentNumberFunction {
     function_name: String,
+    required: usize,
     expected: usize,
     received: usize,
     span: Span,
@@ -77,7 +106,19 @@ pub struct WrongArgumentNumberFunction {
 
 impl WrongArgumentNumberFunction {
     pub fn new(function_name: String, expected: usize, received: usize, span: Span) -> TypingError {
+        Self::new_with_defaults(function_name, expected, expected, received, span)
+    }
+
+    // only the `required` first parameters have no default value
+    pub fn new_with_defaults(
+        function_name: String,
+        required: usize,
+        expected: usize,
+        received: usize,
+        span: Span,
+    ) -> TypingError {
         TypingError::WrongArgumentNumberFunction(Self {
+            required,
             expected,
             received,
             function_name,
@@ -86,11 +127,15 @@ impl WrongArgumentNumberFunction {
     }
 
     fn format(&self, content: &str) -> String {
-        let row = content.split('\n').nth(self.span.get_line() - 1).unwrap();
+        let row = self.span.get_row(content);
+        let expected = if self.required == self.expected {
+            format!("{}", self.expected)
+        } else {
+            format!("between {} and {}", self.required, self.expected)
+        };
         format!(
-            "Line {} - {row}\nWrong number of argument passed. Expected {} but received {}",
+            "Line {} - {row}\nWrong number of argument passed. Expected {expected} but received {}",
             self.span.get_line(),
-            self.expected,
             self.received
         )
     }
@@ -113,7 +158,7 @@ impl NonMutableArgumentToMutableParameter {
     }
 
     fn format(&self, content: &str) -> String {
-        let row = content.split('\n').nth(self.span.get_line() - 1).unwrap();
+        let row = self.span.get_row(content);
         format!(
             "Line {} - {row}\nCannot pass {} argument (non mutable) to a mutable parameter",
             self.span.get_line(),
@@ -139,7 +184,7 @@ impl NonMutableArgumentToMutableVariable {
     }
 
     fn format(&self, content: &str) -> String {
-        let row = content.split('\n').nth(self.span.get_line() - 1).unwrap();
+        let row = self.span.get_row(content);
         format!(
             "Line {} - {row}\nCannot assign value {} (non mutable) to mutable variable {}",
             self.span.get_line(),
@@ -149,6 +194,241 @@ impl NonMutableArgumentToMutableVariable {
     }
 }
 
+#[derive(Debug)]
+pub struct NonMutableValueMutation {
+    value: String,
+    span: Span,
+}
+
+impl NonMutableValueMutation {
+    pub fn new(value: String, span: Span) -> TypingError {
+        TypingError::NonMutableValueMutation(Self { value, span })
+    }
+
+    fn format(&self, content: &str) -> String {
+        let row = self.span.get_row(content);
+        format!(
+            "Line {} - {row}\nCannot mutate {} (non mutable)",
+            self.span.get_line(),
+            self.value
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct UnhashableType {
+    r#type: String,
+    span: Span,
+}
+
+impl UnhashableType {
+    pub fn new(r#type: String, span: Span) -> TypingError {
+        TypingError::UnhashableType(Self { r#type, span })
+    }
+
+    fn format(&self, content: &str) -> String {
+        let row = self.span.get_row(content);
+        format!(
+            "Line {} - {row}\nType {} cannot be used as a dict key",
+            self.span.get_line(),
+            self.r#type
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidFormatSpec {
+    r#type: String,
+    spec: String,
+    span: Span,
+}
+
+impl InvalidFormatSpec {
+    pub fn new(r#type: String, spec: String, span: Span) -> TypingError {
+        TypingError::InvalidFormatSpec(Self { r#type, spec, span })
+    }
+
+    fn format(&self, content: &str) -> String {
+        let row = self.span.get_row(content);
+        let with = match self.spec.as_str() {
+            "" => String::new(),
+            spec => format!(" with '{spec}'"),
+        };
+        format!(
+            "Line {} - {row}\nType {} cannot be formatted{with}",
+            self.span.get_line(),
+            self.r#type
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidTupleIndex {
+    r#type: String,
+    span: Span,
+}
+
+impl InvalidTupleIndex {
+    pub fn new(r#type: String, span: Span) -> TypingError {
+        TypingError::InvalidTupleIndex(Self { r#type, span })
+    }
+
+    fn format(&self, content: &str) -> String {
+        let row = self.span.get_row(content);
+        format!(
+            "Line {} - {row}\n{} can only be indexed by an integer literal within its bounds",
+            self.span.get_line(),
+            self.r#type
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct UnknownAttribute {
+    r#type: String,
+    name: String,
+    span: Span,
+}
+
+impl UnknownAttribute {
+    pub fn new(r#type: String, name: String, span: Span) -> TypingError {
+        TypingError::UnknownAttribute(Self { r#type, name, span })
+    }
+
+    fn format(&self, content: &str) -> String {
+        let row = self.span.get_row(content);
+        format!(
+            "Line {} - {row}\n{} has no attribute {}",
+            self.span.get_line(),
+            self.r#type,
+            self.name
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct NotIterable {
+    r#type: String,
+    span: Span,
+}
+
+impl NotIterable {
+    pub fn new(r#type: String, span: Span) -> TypingError {
+        TypingError::NotIterable(Self { r#type, span })
+    }
+
+    fn format(&self, content: &str) -> String {
+        let row = self.span.get_row(content);
+        format!(
+            "Line {} - {row}\n{} cannot be iterated over",
+            self.span.get_line(),
+            self.r#type
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct NotCallable {
+    r#type: String,
+    span: Span,
+}
+
+impl NotCallable {
+    pub fn new(r#type: String, span: Span) -> TypingError {
+        TypingError::NotCallable(Self { r#type, span })
+    }
+
+    fn format(&self, content: &str) -> String {
+        let row = self.span.get_row(content);
+        format!(
+            "Line {} - {row}\n{} cannot be called",
+            self.span.get_line(),
+            self.r#type
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct UnknownArgument {
+    function_name: String,
+    name: String,
+    span: Span,
+}
+
+impl UnknownArgument {
+    pub fn new(function_name: String, name: String, span: Span) -> TypingError {
+        TypingError::UnknownArgument(Self {
+            function_name,
+            name,
+            span,
+        })
+    }
+
+    fn format(&self, content: &str) -> String {
+        let row = self.span.get_row(content);
+        format!(
+            "Line {} - {row}\n{}() got an unexpected keyword argument {}",
+            self.span.get_line(),
+            self.function_name,
+            self.name
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct DuplicatedArgument {
+    function_name: String,
+    name: String,
+    span: Span,
+}
+
+impl DuplicatedArgument {
+    pub fn new(function_name: String, name: String, span: Span) -> TypingError {
+        TypingError::DuplicatedArgument(Self {
+            function_name,
+            name,
+            span,
+        })
+    }
+
+    fn format(&self, content: &str) -> String {
+        let row = self.span.get_row(content);
+        format!(
+            "Line {} - {row}\n{}() got multiple values for argument {}",
+            self.span.get_line(),
+            self.function_name,
+            self.name
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct MissingArgument {
+    function_name: String,
+    name: String,
+    span: Span,
+}
+
+impl MissingArgument {
+    pub fn new(function_name: String, name: String, span: Span) -> TypingError {
+        TypingError::MissingArgument(Self {
+            function_name,
+            name,
+            span,
+        })
+    }
+
+    fn format(&self, content: &str) -> String {
+        let row = self.span.get_row(content);
+        format!(
+            "Line {} - {row}\n{}() is missing argument {}",
+            self.span.get_line(),
+            self.function_name,
+            self.name
+        )
+    }
+}
+
 #[derive(Debug)]
 pub struct DifferentSignatureBetweenFunction {
     function_a: String,
@@ -176,7 +456,7 @@ impl DifferentSignatureBetweenFunction {
     }
 
     fn format(&self, content: &str) -> String {
-        let row = content.split('\n').nth(self.span.get_line() - 1).unwrap();
+        let row = self.span.get_row(content);
         format!(
             "Line {} -{row}\n{} and {} have different signature: {} against {}",
             self.span.get_line(),
@@ -207,7 +487,7 @@ impl DifferentSignatureBetweenReturns {
     }
 
     fn format(&self, content: &str) -> String {
-        let row = content.split('\n').nth(self.span.get_line() - 1).unwrap();
+        let row = self.span.get_row(content);
         format!(
             "Line {} - {row}\nThe function {} got several return signature: {} and {}",
             self.span.get_line(),
@@ -217,3 +497,145 @@ impl DifferentSignatureBetweenReturns {
         )
     }
 }
+
+#[derive(Debug)]
+pub struct WrongReturnType {
+    function: String,
+    declared: String,
+    returned: String,
+    span: Span,
+}
+
+impl WrongReturnType {
+    pub fn new(function: String, declared: String, returned: String, span: Span) -> TypingError {
+        TypingError::WrongReturnType(Self {
+            function,
+            declared,
+            returned,
+            span,
+        })
+    }
+
+    fn format(&self, content: &str) -> String {
+        let row = self.span.get_row(content);
+        format!(
+            "Line {} - {row}\nThe function {} is declared to return {} but returns {}",
+            self.span.get_line(),
+            self.function,
+            self.declared,
+            self.returned,
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct AmbiguousParameterType {
+    function_name: String,
+    name: String,
+    // the conflicting types the parameter is given
+    conflict: Option<(String, String)>,
+    // the parameter, or the argument conflicting with the previous ones
+    span: Span,
+}
+
+impl AmbiguousParameterType {
+    // nothing tells the type of the parameter
+    pub fn new(function_name: String, name: String, span: Span) -> TypingError {
+        TypingError::AmbiguousParameterType(Self {
+            function_name,
+            name,
+            conflict: None,
+            span,
+        })
+    }
+
+    // the function is called with arguments of different types
+    pub fn new_with_types(
+        function_name: String,
+        name: String,
+        type_a: String,
+        type_b: String,
+        span: Span,
+    ) -> TypingError {
+        TypingError::AmbiguousParameterType(Self {
+            function_name,
+            name,
+            conflict: Some((type_a, type_b)),
+            span,
+        })
+    }
+
+    fn format(&self, content: &str) -> String {
+        let row = self.span.get_row(content);
+        match &self.conflict {
+            Some((type_a, type_b)) => format!(
+                "Line {} - {row}\nCannot infer the type of parameter {} of {}(), it receives both {} and {}",
+                self.span.get_line(),
+                self.name,
+                self.function_name,
+                type_a,
+                type_b
+            ),
+            None => format!(
+                "Line {} - {row}\nCannot infer the type of parameter {} of {}(), it needs a type annotation",
+                self.span.get_line(),
+                self.name,
+                self.function_name
+            ),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct MissingReturn {
+    function_name: String,
+    output: String,
+    span: Span,
+}
+
+impl MissingReturn {
+    pub fn new(function_name: String, output: String, span: Span) -> TypingError {
+        TypingError::MissingReturn(Self {
+            function_name,
+            output,
+            span,
+        })
+    }
+
+    fn format(&self, content: &str) -> String {
+        let row = self.span.get_row(content);
+        format!(
+            "Line {} - {row}\nFunction {} returns {} but can reach its end without returning",
+            self.span.get_line(),
+            self.function_name,
+            self.output
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct UnsupportedOperator {
+    operator: String,
+    r#type: String,
+    span: Span,
+}
+
+impl UnsupportedOperator {
+    pub fn new(operator: String, r#type: String, span: Span) -> TypingError {
+        TypingError::UnsupportedOperator(Self {
+            operator,
+            r#type,
+            span,
+        })
+    }
+
+    fn format(&self, content: &str) -> String {
+        let row = self.span.get_row(content);
+        format!(
+            "Line {} - {row}\nOperator {} is not supported for {}",
+            self.span.get_line(),
+            self.operator,
+            self.r#type
+        )
+    }
+}
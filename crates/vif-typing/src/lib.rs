@@ -8,8 +8,34 @@ mod typer;
 
 pub fn run_typing_checks(function: &mut Function) -> Result<(), error::TypingError> {
     let mut references = references::References::new();
-    // first pass
-    typer::BottomUpTyper::new(type_merger::SoftTypeMerger {}).run(function, &mut references)?;
+    // first pass, repeated as long as it infers the type of some parameters
+    let mut untyped = typer::get_untyped_parameters(function).len();
+    let mut infer_from_body = false;
+    loop {
+        if infer_from_body {
+            typer::BottomUpTyper::new_inferring_from_body(type_merger::SoftTypeMerger {})
+                .run(function, &mut references)?;
+        } else {
+            typer::BottomUpTyper::new(type_merger::SoftTypeMerger {})
+                .run(function, &mut references)?;
+        }
+
+        let remaining = typer::get_untyped_parameters(function);
+        match remaining.first() {
+            None => break,
+            // another pass would not learn anything more
+            Some((function_name, name, span)) if remaining.len() == untyped && infer_from_body => {
+                return Err(error::AmbiguousParameterType::new(
+                    function_name.clone(),
+                    name.clone(),
+                    span.clone(),
+                ))
+            }
+            // the call sites are not enough, see how the parameters are used
+            Some(_) if remaining.len() == untyped => infer_from_body = true,
+            Some(_) => untyped = remaining.len(),
+        }
+    }
 
     // second pass, with functions parameters typed hopefully
     typer::BottomUpTyper::new(type_merger::HardTypeMerger {}).run(function, &mut references)?;
@@ -39,11 +39,15 @@ The module will look for var declaration, assignment and calls to do its checks.
 use crate::error::DifferentSignatureBetweenFunction;
 use crate::error::NonMutableArgumentToMutableParameter;
 use crate::error::NonMutableArgumentToMutableVariable;
+use crate::error::NonMutableValueMutation;
+use crate::error::NotCallable;
 use crate::error::TypingError;
 use crate::error::WrongArgumentNumberFunction;
 use vif_objects::ast::Expr;
 use vif_objects::ast::ExprBody;
+use vif_objects::ast::FormatPart;
 use vif_objects::ast::Function;
+use vif_objects::ast::Signature;
 use vif_objects::ast::Stmt;
 use vif_objects::ast::Value;
 
@@ -52,6 +56,20 @@ pub fn check_mutability(function: &mut Function) -> Result<(), TypingError> {
 }
 
 fn check_function(function: &Function) -> Result<(), TypingError> {
+    for param in function.params.iter() {
+        if let Some(default) = &param.default {
+            check_expression(default)?;
+
+            if param.typing.mutable && !default.typing.mutable {
+                return Err(NonMutableArgumentToMutableParameter::new(
+                    function.name.clone(),
+                    format!("{}", default.body),
+                    default.span.clone(),
+                ));
+            }
+        }
+    }
+
     check_statements(&function.body)
 }
 
@@ -78,6 +96,12 @@ fn check_statement(stmt: &Stmt) -> Result<(), TypingError> {
             Ok(())
         }
         Stmt::Function(f) => check_function(f),
+        Stmt::Class(c) => {
+            for method in c.methods.iter() {
+                check_function(method)?;
+            }
+            Ok(())
+        }
         Stmt::Expression(e) => check_expression(e),
         Stmt::Block(s) => check_statements(s),
         Stmt::Condition(c) => {
@@ -93,8 +117,41 @@ fn check_statement(stmt: &Stmt) -> Result<(), TypingError> {
             check_statement(&r#w.body)?;
             Ok(())
         }
+        Stmt::For(f) => {
+            check_expression(&f.iterable)?;
+
+            // same as `var mut x = item`
+            if f.typing.mutable && !f.iterable.typing.mutable {
+                return Err(NonMutableArgumentToMutableVariable::new(
+                    f.name.clone(),
+                    format!("{}", f.iterable),
+                    f.iterable.span.clone(),
+                ));
+            }
+
+            check_statement(&f.body)?;
+            if let Some(r#else) = &f.r#else {
+                check_statement(r#else)?;
+            }
+            Ok(())
+        }
         Stmt::Return(r) => check_expression(&r#r.value),
         Stmt::Assert(a) => check_expression(&r#a.value),
+        Stmt::Raise(r) => match &r.message {
+            Some(message) => check_expression(message),
+            None => Ok(()),
+        },
+        Stmt::Try(t) => {
+            check_statement(&t.body)?;
+            for handler in t.handlers.iter() {
+                check_statement(&handler.body)?;
+            }
+            if let Some(finally) = &t.finally {
+                check_statement(finally)?;
+            }
+            Ok(())
+        }
+        Stmt::Import(_) => Ok(()),
     }
 }
 
@@ -109,20 +166,42 @@ fn check_expression(expr: &Expr) -> Result<(), TypingError> {
 
             let callable = match &c.callee.typing.r#type {
                 vif_objects::ast::Type::Callable(c) => c,
-                _ => panic!("{} is not callable: {}", c.callee, c.callee.typing),
+                t => return Err(NotCallable::new(t.as_string(), c.callee.span.clone())),
+            };
+
+            for (_, arg) in c.keywords.iter() {
+                check_expression(arg)?;
+            }
+
+            // a method receives the object it is called on as self
+            let (this, skip) = match &c.callee.body {
+                ExprBody::GetAttr(attr) => (Some(&attr.object), 1),
+                _ => (None, 0),
             };
 
-            if let Some(signature_params) = callable.signature.get_params() {
-                if signature_params.len() != c.arguments.len() {
-                    return Err(WrongArgumentNumberFunction::new(
+            if let Signature::Parameters(signature_params, required) = &callable.signature {
+                // parameters with a default value can be left out
+                let received = skip + c.count_arguments();
+                if !(*required..=signature_params.len()).contains(&received) {
+                    return Err(WrongArgumentNumberFunction::new_with_defaults(
                         format!("{}", c.callee),
+                        *required,
                         signature_params.len(),
-                        c.arguments.len(),
+                        received,
                         c.callee.span.clone(),
                     ));
                 }
 
-                for (arg, param_typing) in c.arguments.iter().zip(signature_params.iter()) {
+                // keywords were already matched to their parameter by the typer
+                let arguments = this
+                    .map(|t| Some(t.as_ref()))
+                    .into_iter()
+                    .chain(callable.order_arguments(c, skip).unwrap_or_default());
+
+                for (arg, param_typing) in arguments.zip(signature_params.iter()) {
+                    let Some(arg) = arg else {
+                        continue;
+                    };
                     if param_typing.mutable && !arg.typing.mutable {
                         return Err(NonMutableArgumentToMutableParameter::new(
                             format!("{}", c.callee),
@@ -134,16 +213,14 @@ fn check_expression(expr: &Expr) -> Result<(), TypingError> {
             }
         }
         ExprBody::Binary(b) => {
+            // operand types are checked by the typer, only mutability matters here
             check_expression(&b.left)?;
             check_expression(&b.right)?;
-            if b.left.typing != b.right.typing {
-                return Err(DifferentSignatureBetweenFunction::new(
-                    format!("{}", b.left),
-                    format!("{}", b.right),
-                    b.left.typing.clone(),
-                    b.right.typing.clone(),
-                    b.right.span.clone(),
-                ));
+        }
+        ExprBody::Comparison(c) => {
+            check_expression(&c.left)?;
+            for (_, e) in c.comparisons.iter() {
+                check_expression(e)?;
             }
         }
         ExprBody::Unary(u) => {
@@ -163,6 +240,22 @@ fn check_expression(expr: &Expr) -> Result<(), TypingError> {
 
             check_expression(&a.value)?;
         }
+        ExprBody::AugmentedAssign(a) => {
+            check_expression(&a.target)?;
+            check_expression(&a.value)?;
+
+            let object = match &a.target.body {
+                ExprBody::GetItem(g) => &g.object,
+                ExprBody::GetAttr(g) => &g.object,
+                _ => return Ok(()),
+            };
+            if !object.typing.mutable {
+                return Err(NonMutableValueMutation::new(
+                    format!("{}", object),
+                    object.span.clone(),
+                ));
+            }
+        }
         ExprBody::Logical(l) => {
             check_expression(&l.left)?;
             check_expression(&l.right)?;
@@ -177,6 +270,59 @@ fn check_expression(expr: &Expr) -> Result<(), TypingError> {
                 ));
             }
         }
+        ExprBody::List(l) => {
+            for item in l.items.iter() {
+                check_expression(item)?;
+            }
+        }
+        ExprBody::Dict(d) => {
+            for (key, value) in d.items.iter() {
+                check_expression(key)?;
+                check_expression(value)?;
+            }
+        }
+        ExprBody::Tuple(t) => {
+            for item in t.items.iter() {
+                check_expression(item)?;
+            }
+        }
+        ExprBody::FormatString(f) => {
+            for part in f.parts.iter() {
+                if let FormatPart::Value(value, _) = part {
+                    check_expression(value)?;
+                }
+            }
+        }
+        ExprBody::GetItem(g) => {
+            check_expression(&g.object)?;
+            check_expression(&g.index)?;
+        }
+        ExprBody::SetItem(s) => {
+            check_expression(&s.object)?;
+            check_expression(&s.index)?;
+            check_expression(&s.value)?;
+
+            if !s.object.typing.mutable {
+                return Err(NonMutableValueMutation::new(
+                    format!("{}", s.object),
+                    s.object.span.clone(),
+                ));
+            }
+        }
+        ExprBody::GetAttr(g) => {
+            check_expression(&g.object)?;
+        }
+        ExprBody::SetAttr(s) => {
+            check_expression(&s.object)?;
+            check_expression(&s.value)?;
+
+            if !s.object.typing.mutable {
+                return Err(NonMutableValueMutation::new(
+                    format!("{}", s.object),
+                    s.object.span.clone(),
+                ));
+            }
+        }
         ExprBody::LoopKeyword(_) => (),
         ExprBody::Value(Value::Variable(_)) => (),
         ExprBody::Value(_) => (),
@@ -247,6 +393,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn cannot_set_item_of_non_mutable_list() {
+        let string = "
+            var xs = [1, 2]
+            xs[0] = 2
+        ";
+
+        let mut ast = build_ast(string).unwrap();
+        let result = check_mutability(&mut ast);
+        assert!(result.is_err());
+
+        let err_msg = result.unwrap_err().format(string);
+        assert_eq!(
+            err_msg,
+            "Line 3 -             xs[0] = 2\nCannot mutate Value[var[xs]] (non mutable)"
+        );
+    }
+
     #[test]
     fn can_assign_mut_to_const() {
         let string = "
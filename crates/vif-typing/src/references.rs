@@ -1,3 +1,4 @@
+use vif_objects::ast::Type;
 use vif_objects::ast::Typing;
 
 pub struct References {
@@ -7,6 +8,7 @@ pub struct References {
 pub enum Reference {
     Variable(VariableReference),
     Function(FunctionReference),
+    Class(ClassReference),
 }
 
 #[derive(Debug, Clone)]
@@ -18,7 +20,23 @@ pub struct VariableReference {
 pub struct FunctionReference {
     pub name: String,
     pub typing: Typing,
-    // pub parameters: Vec<VariableReference>,
+    // types of the unannotated parameters, as given by the call sites
+    pub parameters: Vec<VariableReference>,
+}
+
+pub struct ClassReference {
+    pub name: String,
+    // the constructor, i.e __init__ without self
+    pub typing: Typing,
+    pub attributes: Vec<VariableReference>,
+    // attributes can only be declared while typing __init__
+    pub initializing: bool,
+}
+
+impl ClassReference {
+    pub fn get_attribute(&self, name: &str) -> Option<&VariableReference> {
+        self.attributes.iter().find(|a| a.name == name)
+    }
 }
 
 impl std::cmp::PartialEq for VariableReference {
@@ -39,7 +57,11 @@ impl Reference {
     }
 
     pub fn new_function(name: String, typing: Typing) -> Self {
-        Self::Function(FunctionReference { name, typing })
+        Self::Function(FunctionReference {
+            name,
+            typing,
+            parameters: Vec::new(),
+        })
     }
 }
 
@@ -48,6 +70,7 @@ impl std::fmt::Display for Reference {
         match self {
             Self::Variable(v) => write!(f, "var {}", v.name),
             Self::Function(v) => write!(f, "func {}", v.name),
+            Self::Class(v) => write!(f, "class {}", v.name),
         }
     }
 }
@@ -57,6 +80,7 @@ impl std::fmt::Debug for Reference {
         match self {
             Self::Variable(v) => write!(f, "var {}", v.name),
             Self::Function(v) => write!(f, "func {}", v.name),
+            Self::Class(v) => write!(f, "class {}", v.name),
         }
     }
 }
@@ -94,19 +118,33 @@ impl References {
         self.references.push(value)
     }
 
+    // the innermost declaration shadows the previous ones
     pub fn get_typing(&self, name: &str) -> Option<Typing> {
-        for reference in self.references.iter() {
+        for reference in self.references.iter().rev() {
             match reference {
                 Reference::Variable(v) if v.name == name => return Some(v.typing.clone()),
                 Reference::Function(f) if f.name == name => return Some(f.typing.clone()),
+                Reference::Class(c) if c.name == name => return Some(c.typing.clone()),
                 _ => (),
             };
         }
         None
     }
 
+    pub fn set_variable_type(&mut self, name: &str, r#type: Type) {
+        for reference in self.references.iter_mut().rev() {
+            match reference {
+                Reference::Variable(v) if v.name == name => {
+                    v.typing.r#type = r#type;
+                    return;
+                }
+                _ => (),
+            };
+        }
+    }
+
     pub fn get_function_typing_ref(&mut self, name: &str) -> Option<&mut Typing> {
-        for reference in self.references.iter_mut() {
+        for reference in self.references.iter_mut().rev() {
             match reference {
                 Reference::Function(f) if f.name == name => return Some(&mut f.typing),
                 _ => (),
@@ -114,4 +152,37 @@ impl References {
         }
         None
     }
+
+    // the function named so, unless a variable or a class shadows it
+    pub fn get_function_mut(&mut self, name: &str) -> Option<&mut FunctionReference> {
+        for reference in self.references.iter_mut().rev() {
+            match reference {
+                Reference::Function(f) if f.name == name => return Some(f),
+                Reference::Variable(v) if v.name == name => return None,
+                Reference::Class(c) if c.name == name => return None,
+                _ => (),
+            };
+        }
+        None
+    }
+
+    pub fn get_class(&self, name: &str) -> Option<&ClassReference> {
+        for reference in self.references.iter().rev() {
+            match reference {
+                Reference::Class(c) if c.name == name => return Some(c),
+                _ => (),
+            };
+        }
+        None
+    }
+
+    pub fn get_class_mut(&mut self, name: &str) -> Option<&mut ClassReference> {
+        for reference in self.references.iter_mut().rev() {
+            match reference {
+                Reference::Class(c) if c.name == name => return Some(c),
+                _ => (),
+            };
+        }
+        None
+    }
 }
@@ -41,12 +41,38 @@ impl TypeMerger for HardTypeMerger {
                 Type::Callable(_) => self.merge(right, left),
                 _ => None,
             },
+            // functions only go along with functions of the same signature
             Type::Callable(c1) => match right {
-                Type::Callable(c2) => self.merge(
-                    &c1.output.r#type.get_concrete_type(),
-                    &c2.output.r#type.get_concrete_type(),
-                ),
-                _ => self.merge(&c1.output.r#type.get_concrete_type(), right),
+                Type::Callable(c2) if c1 == c2 => Some(left.clone()),
+                _ => None,
+            },
+            Type::List(t1) => match right {
+                Type::List(t2) => Some(Type::List(Box::new(self.merge_item(t1, t2)?))),
+                Type::Callable(_) => self.merge(right, left),
+                _ => None,
+            },
+            Type::Dict(k1, v1) => match right {
+                Type::Dict(k2, v2) => Some(Type::Dict(
+                    Box::new(self.merge_item(k1, k2)?),
+                    Box::new(self.merge_item(v1, v2)?),
+                )),
+                Type::Callable(_) => self.merge(right, left),
+                _ => None,
+            },
+            Type::Tuple(t1) => match right {
+                Type::Tuple(t2) if t1.len() == t2.len() => Some(Type::Tuple(
+                    t1.iter()
+                        .zip(t2.iter())
+                        .map(|(t1, t2)| self.merge_item(t1, t2))
+                        .collect::<Option<Vec<Type>>>()?,
+                )),
+                Type::Callable(_) => self.merge(right, left),
+                _ => None,
+            },
+            Type::Object(o1) => match right {
+                Type::Object(o2) if o1 == o2 => Some(Type::Object(o1.clone())),
+                Type::Callable(_) => self.merge(right, left),
+                _ => None,
             },
             Type::Unknown => None,
             Type::KeyWord => match right {
@@ -56,6 +82,16 @@ impl TypeMerger for HardTypeMerger {
     }
 }
 
+impl HardTypeMerger {
+    fn merge_item(&self, left: &Type, right: &Type) -> Option<Type> {
+        match (left, right) {
+            // an empty collection takes the type of the other one
+            (Type::Unknown, t) | (t, Type::Unknown) => Some(t.clone()),
+            (t1, t2) => self.merge(t1, t2),
+        }
+    }
+}
+
 impl TypeMerger for SoftTypeMerger {
     fn merge(&self, left: &Type, right: &Type) -> Option<Type> {
         match left {
@@ -91,11 +127,36 @@ impl TypeMerger for SoftTypeMerger {
                 _ => Some(Type::Unknown),
             },
             Type::Callable(c1) => match right {
-                Type::Callable(c2) => self.merge(
-                    &c1.output.r#type.get_concrete_type(),
-                    &c2.output.r#type.get_concrete_type(),
-                ),
-                _ => self.merge(&c1.output.r#type.get_concrete_type(), right),
+                Type::Callable(c2) if c1 == c2 => Some(left.clone()),
+                _ => Some(Type::Unknown),
+            },
+            Type::List(t1) => match right {
+                Type::List(t2) => Some(Type::List(Box::new(self.merge(t1, t2)?))),
+                Type::Callable(_) => self.merge(right, left),
+                _ => Some(Type::Unknown),
+            },
+            Type::Dict(k1, v1) => match right {
+                Type::Dict(k2, v2) => Some(Type::Dict(
+                    Box::new(self.merge(k1, k2)?),
+                    Box::new(self.merge(v1, v2)?),
+                )),
+                Type::Callable(_) => self.merge(right, left),
+                _ => Some(Type::Unknown),
+            },
+            Type::Tuple(t1) => match right {
+                Type::Tuple(t2) if t1.len() == t2.len() => Some(Type::Tuple(
+                    t1.iter()
+                        .zip(t2.iter())
+                        .map(|(t1, t2)| self.merge(t1, t2))
+                        .collect::<Option<Vec<Type>>>()?,
+                )),
+                Type::Callable(_) => self.merge(right, left),
+                _ => Some(Type::Unknown),
+            },
+            Type::Object(o1) => match right {
+                Type::Object(o2) if o1 == o2 => Some(Type::Object(o1.clone())),
+                Type::Callable(_) => self.merge(right, left),
+                _ => Some(Type::Unknown),
             },
             Type::Unknown => Some(Type::Unknown),
             Type::KeyWord => match right {
@@ -1,29 +1,57 @@
+use crate::error::AmbiguousParameterType;
 use crate::error::DifferentSignatureBetweenReturns;
+use crate::error::DuplicatedArgument;
 use crate::error::IncompatibleTypes;
+use crate::error::InvalidFormatSpec;
+use crate::error::InvalidTupleIndex;
+use crate::error::MissingArgument;
+use crate::error::MissingReturn;
+use crate::error::NotIterable;
 use crate::error::TypingError;
-use crate::references::FunctionReference;
+use crate::error::UnhashableType;
+use crate::error::UnknownArgument;
+use crate::error::UnknownAttribute;
+use crate::error::UnsupportedOperator;
+use crate::error::WrongArgumentNumberFunction;
+use crate::error::WrongReturnType;
+use crate::references::ClassReference;
 use crate::references::Reference;
 use crate::references::References;
 use crate::references::VariableReference;
 use crate::type_merger::TypeMerger;
+use vif_objects::ast::ArgumentError;
+use vif_objects::ast::Attribute;
+use vif_objects::ast::Call;
 use vif_objects::ast::Callable;
+use vif_objects::ast::Class;
 use vif_objects::ast::Expr;
 use vif_objects::ast::ExprBody;
+use vif_objects::ast::FormatPart;
+use vif_objects::ast::FormatSpec;
 use vif_objects::ast::Function;
 use vif_objects::ast::FunctionParameter;
+use vif_objects::ast::GetAttr;
+use vif_objects::ast::Group;
+use vif_objects::ast::Grouping;
 use vif_objects::ast::LogicalOperator;
+use vif_objects::ast::Operator;
 use vif_objects::ast::Return;
+use vif_objects::ast::SetAttr;
 use vif_objects::ast::Signature;
 use vif_objects::ast::Stmt;
 use vif_objects::ast::Type;
 use vif_objects::ast::Typing;
+use vif_objects::ast::UnaryOperator;
 use vif_objects::ast::Value;
+use vif_objects::span::Span;
 
 pub struct BottomUpTyper<M>
 where
     M: TypeMerger,
 {
     type_merger: M,
+    // parameters are inferred from the call sites first, how they are used comes second
+    infer_from_body: bool,
 }
 
 impl<M> BottomUpTyper<M>
@@ -31,57 +59,204 @@ where
     M: TypeMerger,
 {
     pub fn new(type_merger: M) -> Self {
-        BottomUpTyper { type_merger }
+        BottomUpTyper {
+            type_merger,
+            infer_from_body: false,
+        }
+    }
+
+    pub fn new_inferring_from_body(type_merger: M) -> Self {
+        BottomUpTyper {
+            type_merger,
+            infer_from_body: true,
+        }
     }
 
     pub fn run(
         &self,
         function: &mut Function,
         references: &mut References,
+    ) -> Result<(), TypingError> {
+        self.visit_function(function, references, true)
+    }
+
+    // the implicit root function is neither callable nor referenced, so that it
+    // cannot hide a user function sharing its name
+    fn visit_function(
+        &self,
+        function: &mut Function,
+        references: &mut References,
+        root: bool,
     ) -> Result<(), TypingError> {
         let index = references.len();
 
-        references.push(Reference::Function(FunctionReference {
-            name: function.name.clone(),
-            typing: function.typing.clone(),
-        }));
+        if !root {
+            references.push(Reference::new_function(
+                function.name.clone(),
+                function.typing.clone(),
+            ));
+        }
 
-        for param in function.params.iter() {
+        for index in 0..function.params.len() {
+            // defaults are evaluated by the function, only knowing about the previous parameters
+            if let Some(mut default) = function.params[index].default.take() {
+                self.visit_expression(&mut function.params, &mut default, references)?;
+
+                // empty collections get their items type from the annotation, and
+                // unannotated parameters get theirs from the default value
+                let param_type = &function.params[index].typing.r#type;
+                match (param_type, &default.typing.r#type) {
+                    (Type::List(_), Type::List(t)) | (Type::Dict(_, _), Type::Dict(t, _))
+                        if **t == Type::Unknown =>
+                    {
+                        default.typing.r#type = param_type.clone()
+                    }
+                    (Type::Unknown, t) => {
+                        function.params[index].typing.r#type = t.get_concrete_type()
+                    }
+                    _ => (),
+                }
+
+                check_argument(&function.params[index].typing, &default, &default.span)?;
+                function.params[index].default = Some(default);
+            }
+
+            let param = &function.params[index];
             references.push(Reference::Variable(VariableReference::new(
                 param.name.clone(),
                 param.typing.clone(),
             )));
         }
 
+        // functions with a known signature can be called before their declaration
+        for stmt in function.body.iter() {
+            if let Stmt::Function(f) = stmt {
+                if get_declared_output(&f.typing).is_some() {
+                    references.push(Reference::new_function(f.name.clone(), f.typing.clone()));
+                }
+            }
+        }
+
         for stmt in function.body.iter_mut() {
             self.visit_statement(&mut function.params, stmt, references)?;
         }
 
-        if function.name != "main" {
+        set_inferred_parameters(&mut function.body, references);
+
+        references.truncate(index);
+
+        if !root {
             self.update_function_typing(function)?;
+
+            references.push(Reference::new_function(
+                function.name.clone(),
+                function.typing.clone(),
+            ));
         }
 
-        references.truncate(index);
+        Ok(())
+    }
 
-        references.push(Reference::new_function(
-            function.name.clone(),
-            function.typing.clone(),
-        ));
+    fn visit_class(
+        &self,
+        class: &mut Class,
+        references: &mut References,
+    ) -> Result<(), TypingError> {
+        let index = references.len();
+        let init_name = format!("{}.__init__", class.name);
+
+        let constructor_params = class
+            .methods
+            .iter()
+            .find(|m| m.name == init_name)
+            .and_then(|m| m.params.get(1..))
+            .unwrap_or_default();
+
+        let mut constructor = Callable::new(
+            get_signature(constructor_params),
+            Typing::new(true, Type::Object(class.name.clone())),
+            false,
+        );
+        constructor.names = constructor_params.iter().map(|p| p.name.clone()).collect();
+
+        references.push(Reference::Class(ClassReference {
+            name: class.name.clone(),
+            // constructors always return a new object
+            typing: Typing::new(true, Type::Callable(Box::new(constructor))),
+            attributes: Vec::new(),
+            initializing: false,
+        }));
+
+        // methods can call each other whatever their order in the class
+        for method in class.methods.iter() {
+            references.push(Reference::new_function(
+                method.name.clone(),
+                method.typing.clone(),
+            ));
+        }
+
+        // __init__ goes first as it declares the attributes
+        let (init, methods): (Vec<&mut Function>, Vec<&mut Function>) =
+            class.methods.iter_mut().partition(|m| m.name == init_name);
+
+        for method in init.into_iter().chain(methods) {
+            let initializing = method.name == init_name;
+            references.get_class_mut(&class.name).unwrap().initializing = initializing;
+
+            self.visit_function(method, references, false)?;
+        }
+
+        // the types given to the methods parameters by calls made within the class, as
+        // recorded on the references each run has pushed
+        let inferred = class
+            .methods
+            .iter()
+            .map(|m| {
+                references
+                    .get_function_mut(&m.name)
+                    .map(|f| std::mem::take(&mut f.parameters))
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<Vec<VariableReference>>>();
+
+        let class_reference = references.get_class_mut(&class.name).unwrap();
+        class_reference.initializing = false;
+        class.attributes = class_reference
+            .attributes
+            .iter()
+            .map(|a| Attribute {
+                name: a.name.clone(),
+                typing: a.typing.clone(),
+            })
+            .collect();
+
+        // only keep the class and its methods, dropping what each run has pushed
+        references.truncate(index + 1 + class.methods.len());
+
+        for (method, parameters) in class.methods.iter().zip(inferred) {
+            *references.get_function_typing_ref(&method.name).unwrap() = method.typing.clone();
+            let reference = references.get_function_mut(&method.name).unwrap();
+            reference.parameters.extend(parameters);
+        }
 
         Ok(())
     }
 
     fn update_function_typing(&self, function: &mut Function) -> Result<(), TypingError> {
-        let returns = function
+        let all_returns = function
             .body
             .iter()
             .map(|b| b.get_all_returns())
             .flatten()
+            .collect::<Vec<&Return>>();
+
+        let returns = all_returns
+            .iter()
+            .copied()
             .filter(|r| r.value.typing.r#type != Type::Unknown)
             .collect::<Vec<&Return>>();
 
-        let signature =
-            Signature::new_with_params(function.params.iter().map(|p| p.typing.clone()).collect());
+        let signature = get_signature(&function.params);
 
         let param_names = function
             .params
@@ -102,12 +277,19 @@ where
             false
         });
 
-        let callable = if returns.is_empty() {
+        let mut callable = if all_returns.is_empty() {
             Box::new(Callable::new(
                 signature,
                 Typing::new(false, Type::None),
                 false,
             ))
+        } else if returns.is_empty() {
+            // the returned values depend on parameters that are not inferred yet
+            Box::new(Callable::new(
+                signature,
+                Typing::new(false, Type::Unknown),
+                false,
+            ))
         } else {
             Box::new(Callable::new(
                 signature,
@@ -116,16 +298,47 @@ where
             ))
         };
 
-        for return_stmt in returns.iter() {
-            if return_stmt.value.typing.r#type.get_concrete_type()
-                != callable.output.r#type.get_concrete_type()
-            {
-                return Err(DifferentSignatureBetweenReturns::new(
-                    function.name.clone(),
-                    return_stmt.value.typing.clone(),
-                    callable.output.clone(),
-                    return_stmt.value.span.clone(),
-                ));
+        callable.names = function.params.iter().map(|p| p.name.clone()).collect();
+
+        match get_declared_output(&function.typing).filter(|_| function.declared_output) {
+            // returned values widen to the declared type, like an int to a float
+            Some(output) => {
+                let declared = output.get_concrete_type();
+                for return_stmt in returns.iter() {
+                    let returned = return_stmt.value.typing.r#type.get_concrete_type();
+                    if returned != declared && !widens_to(&declared, &returned) {
+                        return Err(WrongReturnType::new(
+                            function.name.clone(),
+                            output.as_string(),
+                            returned.as_string(),
+                            return_stmt.value.span.clone(),
+                        ));
+                    }
+                }
+
+                if output != Type::None && !function.body.iter().any(|s| s.always_returns()) {
+                    return Err(MissingReturn::new(
+                        function.name.clone(),
+                        output.as_string(),
+                        function.span.clone(),
+                    ));
+                }
+
+                callable.output.r#type = output;
+            }
+            None => {
+                for return_stmt in returns.iter() {
+                    if return_stmt.value.typing.r#type.get_concrete_type()
+                        != callable.output.r#type.get_concrete_type()
+                    {
+                        return Err(DifferentSignatureBetweenReturns::new(
+                            function.name.clone(),
+                            return_stmt.value.typing.clone(),
+                            callable.output.clone(),
+                            return_stmt.value.span.clone(),
+                        ));
+                    }
+                }
             }
         }
 
@@ -159,13 +372,71 @@ where
             }
             Stmt::Return(ret) => self.visit_expression(params, &mut ret.value, references)?,
             Stmt::Assert(assert) => self.visit_expression(params, &mut assert.value, references)?,
+            Stmt::Raise(raise) => {
+                if let Some(message) = &mut raise.message {
+                    self.visit_expression(params, message, references)?;
+                    match message.typing.r#type.get_concrete_type() {
+                        Type::String | Type::Unknown => (),
+                        t => {
+                            return Err(IncompatibleTypes::new(
+                                Type::String.as_string(),
+                                t.as_string(),
+                                message.span.clone(),
+                            ))
+                        }
+                    }
+                }
+            }
+            Stmt::Try(r#try) => {
+                self.visit_statement(params, &mut r#try.body, references)?;
+                for handler in r#try.handlers.iter_mut() {
+                    // the name holds the message of the error
+                    if let Some(name) = &handler.name {
+                        references.push(Reference::new_variable(
+                            name.clone(),
+                            Typing::new(false, Type::String),
+                        ));
+                    }
+                    self.visit_statement(params, &mut handler.body, references)?;
+                }
+                if let Some(finally) = &mut r#try.finally {
+                    self.visit_statement(params, finally, references)?;
+                }
+            }
             Stmt::While(block) => {
                 self.visit_expression(params, &mut block.condition, references)?;
                 self.visit_statement(params, &mut block.body, references)?;
             }
+            Stmt::For(r#for) => {
+                self.visit_expression(params, &mut r#for.iterable, references)?;
+
+                r#for.typing.r#type = match r#for.iterable.typing.r#type.get_concrete_type() {
+                    Type::List(t) => *t,
+                    // dicts are iterated over their keys
+                    Type::Dict(k, _) => *k,
+                    Type::Unknown => Type::Unknown,
+                    t => return Err(NotIterable::new(t.as_string(), r#for.iterable.span.clone())),
+                };
+
+                references.push(Reference::new_variable(
+                    r#for.name.clone(),
+                    r#for.typing.clone(),
+                ));
+
+                self.visit_statement(params, &mut r#for.body, references)?;
+                if let Some(stmt_else) = &mut r#for.r#else {
+                    self.visit_statement(params, stmt_else, references)?;
+                }
+            }
             Stmt::Var(v) => {
                 self.visit_expression(params, &mut v.value, references)?;
-                v.typing.r#type = v.value.typing.r#type.clone();
+
+                // annotated variables keep their type, or the one given by a previous pass
+                if v.typing.r#type.is_complete() {
+                    check_declared_type(&v.typing.r#type, &mut v.value)?;
+                } else {
+                    v.typing.r#type = v.value.typing.r#type.clone();
+                }
 
                 // should not be needed as we get identifier typing from the call above
 
@@ -188,8 +459,11 @@ where
                 references.push(Reference::new_variable(v.name.clone(), v.typing.clone()))
             }
             Stmt::Function(f) => {
-                self.run(f, references)?;
+                self.visit_function(f, references, false)?;
             }
+            Stmt::Class(c) => self.visit_class(c, references)?,
+            // modules are merged into the program before typing
+            Stmt::Import(_) => (),
         };
 
         Ok(())
@@ -206,23 +480,50 @@ where
                 self.visit_expression(params, &mut binary.left, references)?;
                 self.visit_expression(params, &mut binary.right, references)?;
 
-                expr.typing.r#type = self
-                    .type_merger
-                    .merge(&binary.left.typing.r#type, &binary.right.typing.r#type)
-                    .ok_or_else(|| {
-                        IncompatibleTypes::new(
-                            binary.left.typing.r#type.as_string(),
-                            binary.right.typing.r#type.as_string(),
-                            expr.span.clone(),
-                        )
-                    })?;
+                let r#type = self.binary_type(
+                    params,
+                    &mut binary.left,
+                    &binary.operator,
+                    &mut binary.right,
+                    &expr.span,
+                    references,
+                )?;
+                expr.typing = Typing::new(true, r#type);
+            }
+            ExprBody::Comparison(comparison) => {
+                self.visit_expression(params, &mut comparison.left, references)?;
+                for (_, right) in comparison.comparisons.iter_mut() {
+                    self.visit_expression(params, right, references)?;
+                }
 
-                expr.typing.mutable = true;
+                let mut left = &mut comparison.left;
+                for (operator, right) in comparison.comparisons.iter_mut() {
+                    self.binary_type(params, left, operator, right, &expr.span, references)?;
+                    left = right;
+                }
+                expr.typing = Typing::new(true, Type::Bool);
             }
             ExprBody::Unary(unary) => {
                 self.visit_expression(params, &mut unary.right, references)?;
-                expr.typing.r#type = unary.right.typing.r#type.clone();
                 expr.typing = unary.right.typing.clone();
+                match unary.operator {
+                    UnaryOperator::Not => expr.typing.r#type = Type::Bool,
+                    UnaryOperator::Minus if unary.right.typing.r#type == Type::Bool => {
+                        expr.typing.r#type = Type::Int
+                    }
+                    UnaryOperator::Minus => (),
+                    UnaryOperator::Invert => match unary.right.typing.r#type {
+                        Type::Int | Type::Bool => expr.typing.r#type = Type::Int,
+                        Type::Unknown => (),
+                        ref t => {
+                            return Err(IncompatibleTypes::new(
+                                t.as_string(),
+                                Type::Int.as_string(),
+                                expr.span.clone(),
+                            ))
+                        }
+                    },
+                }
             }
             ExprBody::Grouping(grouping) => {
                 self.visit_expression(params, &mut grouping.expr, references)?;
@@ -233,6 +534,9 @@ where
                 self.visit_expression(params, &mut assign.value, references)?;
 
                 if let Some(t) = references.get_typing(&assign.name) {
+                    if t.r#type.is_complete() {
+                        check_declared_type(&t.r#type, &mut assign.value)?;
+                    }
                     expr.typing.r#type = t.r#type.clone();
                     expr.typing.mutable = t.mutable;
                 }
@@ -264,45 +568,89 @@ where
                 }
             }
             ExprBody::Call(call) => {
-                self.visit_expression(params, &mut call.callee, references)?;
-                for arg in call.arguments.iter_mut() {
-                    self.visit_expression(params, arg, references)?;
+                let typed = self.clone_method_call(params, call, references)?;
+                if !typed {
+                    self.visit_expression(params, &mut call.callee, references)?;
                 }
 
-                expr.typing.r#type = call.callee.typing.r#type.clone();
-                expr.typing.mutable = call.callee.typing.mutable;
-                let callable_names = get_identifier_names(&call.callee);
+                // a function stored in an attribute is called as any value, `(obj.f)()`,
+                // it is not a method receiving the object
+                if is_attribute(&call.callee, references) {
+                    let typing = call.callee.typing.clone();
+                    let span = call.callee.span.clone();
+                    let placeholder =
+                        Expr::new(ExprBody::Value(Value::None), typing.clone(), span.clone());
+                    let callee = std::mem::replace(&mut call.callee, Box::new(placeholder));
+                    call.callee = Box::new(Expr::new(
+                        ExprBody::Grouping(Grouping {
+                            left: Group::LeftParen,
+                            expr: callee,
+                            right: Group::RightParen,
+                        }),
+                        typing,
+                        span,
+                    ));
+                }
+                if !typed {
+                    for arg in call.arguments.iter_mut() {
+                        self.visit_expression(params, arg, references)?;
+                    }
+                    for (_, arg) in call.keywords.iter_mut() {
+                        self.visit_expression(params, arg, references)?;
+                    }
+                }
 
-                // check function parameters typing
-                for callable_name in callable_names.iter() {
-                    if let Some(function_reference) =
-                        references.get_function_typing_ref(callable_name)
-                    {
-                        match &mut function_reference.r#type {
-                            Type::Callable(callable) => match &mut callable.signature {
-                                Signature::Parameters(params) => {
-                                    for (param, arg) in params.iter_mut().zip(call.arguments.iter())
-                                    {
-                                        if param.r#type != arg.typing.r#type {
-                                            return Err(IncompatibleTypes::new(
-                                                param.r#type.as_string(),
-                                                arg.typing.r#type.as_string(),
-                                                expr.span.clone(),
-                                            ));
-                                        }
-                                    }
-                                }
-                                _ => (),
-                            },
-                            _ => continue,
+                let mut is_builtin = false;
+                if let ExprBody::Value(Value::Variable(name)) = &call.callee.body {
+                    if references.get_typing(name).is_none() {
+                        if let Some((keyword, _)) = call.keywords.first() {
+                            return Err(UnknownArgument::new(
+                                name.clone(),
+                                keyword.clone(),
+                                expr.span.clone(),
+                            ));
+                        }
+                        if let Some(typing) =
+                            self.builtin_call_typing(name, &mut call.arguments, references)?
+                        {
+                            call.callee.typing = typing;
                         }
+                        is_builtin = true;
                     }
                 }
 
-                // update function parameters typing if it's them being called
-                for param in params.iter_mut() {
-                    if callable_names.contains(&param.name) {
-                        param.typing = call.callee.typing.clone();
+                // anything callable is checked through its type, be it a function, a class,
+                // a method or a function value
+                expr.typing.r#type = match &call.callee.typing.r#type {
+                    Type::Callable(callable) => {
+                        if !is_builtin {
+                            // methods receive the object as self
+                            let skip = matches!(call.callee.body, ExprBody::GetAttr(_)) as usize;
+                            if self.infer_from_body {
+                                infer_arguments(
+                                    callable,
+                                    &mut call.arguments,
+                                    &mut call.keywords,
+                                    skip,
+                                    params,
+                                    references,
+                                );
+                            }
+                            check_arguments(callable, call, skip, &expr.span)?;
+                            record_arguments(callable, call, skip, &expr.span, references)?;
+                        }
+                        callable.output.r#type.clone()
+                    }
+                    t => t.clone(),
+                };
+                expr.typing.mutable = call.callee.typing.mutable;
+
+                // update function parameters typing if it's them being called, unless annotated
+                if let ExprBody::Value(Value::Variable(name)) = &call.callee.body {
+                    for param in params.iter_mut() {
+                        if &param.name == name && param.typing.r#type == Type::Unknown {
+                            param.typing = call.callee.typing.clone();
+                        }
                     }
                 }
             }
@@ -321,6 +669,18 @@ where
                                 ))),
                             )
                         }
+                        // the actual signature depends on the arguments, see builtin_call_typing
+                        "len" | "append" | "pop" | "keys" | "values" | "items" | "range"
+                        | "clone" => {
+                            expr.typing = Typing::new(
+                                false,
+                                Type::Callable(Box::new(Callable::new(
+                                    Signature::new_with_infinite(),
+                                    Typing::new(true, Type::Unknown),
+                                    false,
+                                ))),
+                            )
+                        }
                         // "get_time" =>
                         // "sleep" =>
                         _ => panic!("Unknown variable ? {}", v),
@@ -328,10 +688,1014 @@ where
                 }
             }
             ExprBody::Value(_) => expr.typing.mutable = true,
+            ExprBody::GetAttr(attr) => {
+                self.visit_expression(params, &mut attr.object, references)?;
+                expr.typing = self.attribute_typing(attr, &expr.span, references)?;
+            }
+            ExprBody::AugmentedAssign(assign) => {
+                self.visit_expression(params, &mut assign.target, references)?;
+                self.visit_expression(params, &mut assign.value, references)?;
+
+                if let ExprBody::GetItem(item) = &assign.target.body {
+                    if let t @ Type::Tuple(_) = item.object.typing.r#type.get_concrete_type() {
+                        return Err(IncompatibleTypes::new(
+                            "List".to_owned(),
+                            t.as_string(),
+                            item.object.span.clone(),
+                        ));
+                    }
+                }
+
+                let r#type = self.binary_type(
+                    params,
+                    &mut assign.target,
+                    &assign.operator,
+                    &mut assign.value,
+                    &expr.span,
+                    references,
+                )?;
+
+                // the result is stored back in place, it must keep the target's type
+                let target_type = assign.target.typing.r#type.get_concrete_type();
+                match self.type_merger.merge(&target_type, &r#type) {
+                    _ if target_type == Type::Unknown => (),
+                    Some(t) if t == target_type || t == Type::Unknown => (),
+                    _ => {
+                        return Err(IncompatibleTypes::new(
+                            target_type.as_string(),
+                            r#type.as_string(),
+                            expr.span.clone(),
+                        ))
+                    }
+                }
+
+                expr.typing = Typing::new(true, Type::None);
+            }
+            ExprBody::SetAttr(attr) => {
+                self.visit_expression(params, &mut attr.object, references)?;
+                self.visit_expression(params, &mut attr.value, references)?;
+                self.set_attribute(attr, &expr.span, references)?;
+
+                expr.typing = Typing::new(true, Type::None);
+            }
             ExprBody::LoopKeyword(_) => expr.typing.mutable = false,
+            ExprBody::List(list) => {
+                let mut item_type: Option<Type> = None;
+
+                for item in list.items.iter_mut() {
+                    self.visit_expression(params, item, references)?;
+                    let t = item.typing.r#type.get_concrete_type();
+
+                    item_type = Some(match item_type {
+                        None => t,
+                        Some(previous) => {
+                            self.type_merger.merge(&previous, &t).ok_or_else(|| {
+                                IncompatibleTypes::new(
+                                    previous.as_string(),
+                                    t.as_string(),
+                                    item.span.clone(),
+                                )
+                            })?
+                        }
+                    });
+                }
+
+                expr.typing = Typing::new(
+                    true,
+                    Type::List(Box::new(item_type.unwrap_or(Type::Unknown))),
+                );
+            }
+            ExprBody::Dict(dict) => {
+                let mut key_type: Option<Type> = None;
+                let mut value_type: Option<Type> = None;
+
+                for (key, value) in dict.items.iter_mut() {
+                    self.visit_expression(params, key, references)?;
+                    self.visit_expression(params, value, references)?;
+
+                    key_type = Some(self.merge_literal_type(key_type, key)?);
+                    value_type = Some(self.merge_literal_type(value_type, value)?);
+                }
+
+                let key_type = key_type.unwrap_or(Type::Unknown);
+                if !key_type.is_hashable() {
+                    return Err(UnhashableType::new(key_type.as_string(), expr.span.clone()));
+                }
+
+                expr.typing = Typing::new(
+                    true,
+                    Type::Dict(
+                        Box::new(key_type),
+                        Box::new(value_type.unwrap_or(Type::Unknown)),
+                    ),
+                );
+            }
+            ExprBody::Tuple(tuple) => {
+                for item in tuple.items.iter_mut() {
+                    self.visit_expression(params, item, references)?;
+                }
+
+                expr.typing = Typing::new(
+                    true,
+                    Type::Tuple(
+                        tuple
+                            .items
+                            .iter()
+                            .map(|i| i.typing.r#type.get_concrete_type())
+                            .collect(),
+                    ),
+                );
+            }
+            ExprBody::FormatString(format) => {
+                for part in format.parts.iter_mut() {
+                    if let FormatPart::Value(value, spec) = part {
+                        self.visit_expression(params, value, references)?;
+                        check_format_spec(value, spec)?;
+                    }
+                }
+
+                expr.typing = Typing::new(true, Type::String);
+            }
+            ExprBody::GetItem(item) => {
+                self.visit_expression(params, &mut item.object, references)?;
+                self.visit_expression(params, &mut item.index, references)?;
+
+                let item_type = match item.object.typing.r#type.get_concrete_type() {
+                    Type::Dict(key_type, value_type) => {
+                        self.merge_item_type(&key_type, &item.index)?;
+                        *value_type
+                    }
+                    Type::Tuple(types) => {
+                        let position =
+                            item.index.as_tuple_position(types.len()).ok_or_else(|| {
+                                InvalidTupleIndex::new(
+                                    Type::Tuple(types.clone()).as_string(),
+                                    item.index.span.clone(),
+                                )
+                            })?;
+                        types[position].clone()
+                    }
+                    _ => {
+                        self.check_index(&item.index)?;
+                        self.list_item_type(&item.object)?
+                    }
+                };
+
+                expr.typing = Typing::new(item.object.typing.mutable, item_type);
+            }
+            ExprBody::SetItem(item) => {
+                self.visit_expression(params, &mut item.object, references)?;
+                self.visit_expression(params, &mut item.index, references)?;
+                self.visit_expression(params, &mut item.value, references)?;
+
+                match item.object.typing.r#type.get_concrete_type() {
+                    Type::Dict(key_type, value_type) => {
+                        let key_type = self.merge_item_type(&key_type, &item.index)?;
+                        let value_type = self.merge_item_type(&value_type, &item.value)?;
+
+                        if !key_type.is_hashable() {
+                            return Err(UnhashableType::new(
+                                key_type.as_string(),
+                                item.index.span.clone(),
+                            ));
+                        }
+
+                        let dict_type = Type::Dict(Box::new(key_type), Box::new(value_type));
+
+                        // an empty dict gets its keys and values types from the first insertion
+                        refine_type(&item.object, &dict_type, references);
+                        item.object.typing.r#type = dict_type;
+                    }
+                    _ => {
+                        self.check_index(&item.index)?;
+                        let item_type = self.list_item_type(&item.object)?;
+                        self.merge_item_type(&item_type, &item.value)?;
+                    }
+                }
+
+                expr.typing = Typing::new(true, Type::None);
+            }
         };
         Ok(())
     }
+
+    // the object is typed already
+    fn attribute_typing(
+        &self,
+        attr: &GetAttr,
+        span: &Span,
+        references: &References,
+    ) -> Result<Typing, TypingError> {
+        let object = &attr.object;
+        let typing = match object.typing.r#type.get_concrete_type() {
+            Type::Object(class) => {
+                let attribute = references
+                    .get_class(&class)
+                    .and_then(|c| c.get_attribute(&attr.name))
+                    .map(|a| Typing::new(object.typing.mutable, a.typing.r#type.clone()));
+
+                match attribute {
+                    Some(typing) => typing,
+                    None => references
+                        .get_typing(&format!("{}.{}", class, attr.name))
+                        .ok_or_else(|| {
+                            UnknownAttribute::new(class.clone(), attr.name.clone(), span.clone())
+                        })?,
+                }
+            }
+            Type::Unknown => Typing::new(object.typing.mutable, Type::Unknown),
+            t => {
+                return Err(UnknownAttribute::new(
+                    t.as_string(),
+                    attr.name.clone(),
+                    span.clone(),
+                ))
+            }
+        };
+
+        Ok(typing)
+    }
+
+    // attributes are declared by assigning them on self in __init__
+    fn set_attribute(
+        &self,
+        attr: &SetAttr,
+        span: &Span,
+        references: &mut References,
+    ) -> Result<(), TypingError> {
+        let class = match attr.object.typing.r#type.get_concrete_type() {
+            Type::Object(class) => class,
+            Type::Unknown => return Ok(()),
+            t => {
+                return Err(UnknownAttribute::new(
+                    t.as_string(),
+                    attr.name.clone(),
+                    span.clone(),
+                ))
+            }
+        };
+        let unknown_attribute =
+            || UnknownAttribute::new(class.clone(), attr.name.clone(), span.clone());
+
+        let is_self =
+            matches!(&attr.object.body, ExprBody::Value(Value::Variable(v)) if v == "self");
+        let class_reference = references
+            .get_class_mut(&class)
+            .ok_or_else(unknown_attribute)?;
+
+        match class_reference
+            .attributes
+            .iter_mut()
+            .find(|a| a.name == attr.name)
+        {
+            Some(attribute) => {
+                attribute.typing.r#type =
+                    self.merge_item_type(&attribute.typing.r#type, &attr.value)?;
+            }
+            None if class_reference.initializing && is_self => {
+                class_reference.attributes.push(VariableReference::new(
+                    attr.name.clone(),
+                    Typing::new(true, attr.value.typing.r#type.get_concrete_type()),
+                ));
+            }
+            None => return Err(unknown_attribute()),
+        };
+
+        Ok(())
+    }
+
+    fn list_item_type(&self, list: &Expr) -> Result<Type, TypingError> {
+        let list_type = list.typing.r#type.get_concrete_type();
+
+        match list_type {
+            Type::List(t) => Ok(*t),
+            t => match self
+                .type_merger
+                .merge(&t, &Type::List(Box::new(Type::Unknown)))
+            {
+                Some(_) => Ok(Type::Unknown),
+                None => Err(IncompatibleTypes::new(
+                    "List".to_owned(),
+                    t.as_string(),
+                    list.span.clone(),
+                )),
+            },
+        }
+    }
+
+    fn dict_types(&self, dict: &Expr) -> Result<(Type, Type), TypingError> {
+        let dict_type = dict.typing.r#type.get_concrete_type();
+
+        match dict_type {
+            Type::Dict(k, v) => Ok((*k, *v)),
+            t => match self.type_merger.merge(
+                &t,
+                &Type::Dict(Box::new(Type::Unknown), Box::new(Type::Unknown)),
+            ) {
+                Some(_) => Ok((Type::Unknown, Type::Unknown)),
+                None => Err(IncompatibleTypes::new(
+                    "Dict".to_owned(),
+                    t.as_string(),
+                    dict.span.clone(),
+                )),
+            },
+        }
+    }
+
+    fn merge_literal_type(&self, previous: Option<Type>, item: &Expr) -> Result<Type, TypingError> {
+        let t = item.typing.r#type.get_concrete_type();
+
+        match previous {
+            None => Ok(t),
+            Some(previous) => self.type_merger.merge(&previous, &t).ok_or_else(|| {
+                IncompatibleTypes::new(previous.as_string(), t.as_string(), item.span.clone())
+            }),
+        }
+    }
+
+    // the operands are typed already, chained comparisons type each link with it
+    fn binary_type(
+        &self,
+        params: &mut Vec<FunctionParameter>,
+        left: &mut Expr,
+        operator: &Operator,
+        right: &mut Expr,
+        span: &Span,
+        references: &mut References,
+    ) -> Result<Type, TypingError> {
+        let is_membership = matches!(operator, Operator::In | Operator::NotIn);
+
+        // an unannotated parameter takes the type of what it is combined with
+        if self.infer_from_body && !is_membership {
+            let right_type = right.typing.r#type.clone();
+            infer_parameter(params, left, &right_type, references);
+            let left_type = left.typing.r#type.clone();
+            infer_parameter(params, right, &left_type, references);
+        }
+
+        if is_membership {
+            self.check_membership(left, right)?;
+            return Ok(Type::Bool);
+        }
+
+        let merged_type = self
+            .type_merger
+            .merge(&left.typing.r#type, &right.typing.r#type);
+
+        let incompatible_types = || {
+            IncompatibleTypes::new(
+                left.typing.r#type.as_string(),
+                right.typing.r#type.as_string(),
+                span.clone(),
+            )
+        };
+
+        Ok(match (operator, merged_type) {
+            // values of different types are simply never equal
+            (Operator::Equal | Operator::BangEqual, _) => Type::Bool,
+            (_, None) => return Err(incompatible_types()),
+            // collections and objects only support equality
+            (
+                _,
+                Some(t @ (Type::List(_) | Type::Dict(_, _) | Type::Tuple(_) | Type::Object(_))),
+            ) => {
+                return Err(UnsupportedOperator::new(
+                    operator.to_string(),
+                    t.as_string(),
+                    span.clone(),
+                ))
+            }
+            // True & False is a bool, True << 1 an int
+            (Operator::BitAnd | Operator::BitOr | Operator::BitXor, Some(Type::Bool)) => Type::Bool,
+            (o, Some(Type::Int | Type::Bool)) if o.is_bitwise() => Type::Int,
+            (o, Some(t)) if o.is_bitwise() && t != Type::Unknown => {
+                return Err(incompatible_types())
+            }
+            (
+                Operator::Greater | Operator::GreaterEqual | Operator::Less | Operator::LessEqual,
+                _,
+            ) => Type::Bool,
+            // strings only support concatenation
+            (Operator::Plus, Some(Type::String)) => Type::String,
+            (_, Some(Type::String)) => return Err(incompatible_types()),
+            // dividing always gives a float, `7 / 2` being 3.5
+            (Operator::Divide, Some(Type::Int | Type::Bool)) => Type::Float,
+            // True + True == 2
+            (_, Some(Type::Bool)) => Type::Int,
+            (_, Some(merged_type)) => merged_type,
+        })
+    }
+
+    // `in` looks for a key in a dict, an item in a list or a substring in a string
+    fn check_membership(&self, value: &Expr, container: &Expr) -> Result<(), TypingError> {
+        match container.typing.r#type.get_concrete_type() {
+            Type::Dict(key_type, _) => self.merge_item_type(&key_type, value).map(|_| ()),
+            Type::List(item_type) => self.merge_item_type(&item_type, value).map(|_| ()),
+            Type::String => self.merge_item_type(&Type::String, value).map(|_| ()),
+            Type::Unknown => Ok(()),
+            t => Err(IncompatibleTypes::new(
+                "Dict, List or String".to_owned(),
+                t.as_string(),
+                container.span.clone(),
+            )),
+        }
+    }
+
+    // a value stored in a list must fit its items type, unless the list is still empty
+    fn merge_item_type(&self, item_type: &Type, value: &Expr) -> Result<Type, TypingError> {
+        let value_type = value.typing.r#type.get_concrete_type();
+
+        if *item_type == Type::Unknown {
+            return Ok(value_type);
+        }
+
+        match self.type_merger.merge(item_type, &value_type) {
+            Some(t) if t == *item_type => Ok(t),
+            Some(Type::Unknown) => Ok(item_type.clone()),
+            _ => Err(IncompatibleTypes::new(
+                item_type.as_string(),
+                value_type.as_string(),
+                value.span.clone(),
+            )),
+        }
+    }
+
+    fn check_index(&self, index: &Expr) -> Result<(), TypingError> {
+        let index_type = index.typing.r#type.get_concrete_type();
+
+        match self.type_merger.merge(&index_type, &Type::Int) {
+            Some(Type::Int) | Some(Type::Unknown) => Ok(()),
+            _ => Err(IncompatibleTypes::new(
+                Type::Int.as_string(),
+                index_type.as_string(),
+                index.span.clone(),
+            )),
+        }
+    }
+
+    // `x.clone()` stands for `clone(x)`, unless x is an object whose class defines a clone method.
+    // x is typed once, the callee and arguments of the call being typed when it returns true
+    fn clone_method_call(
+        &self,
+        params: &mut Vec<FunctionParameter>,
+        call: &mut Call,
+        references: &mut References,
+    ) -> Result<bool, TypingError> {
+        let ExprBody::GetAttr(attr) = &mut call.callee.body else {
+            return Ok(false);
+        };
+        if attr.name != "clone"
+            || !call.arguments.is_empty()
+            || !call.keywords.is_empty()
+            || references.get_typing("clone").is_some()
+        {
+            return Ok(false);
+        }
+
+        self.visit_expression(params, &mut attr.object, references)?;
+        if let Type::Object(class) = attr.object.typing.r#type.get_concrete_type() {
+            let has_clone = references
+                .get_class(&class)
+                .is_some_and(|c| c.get_attribute("clone").is_some())
+                || references.get_typing(&format!("{}.clone", class)).is_some();
+            if has_clone {
+                call.callee.typing = self.attribute_typing(attr, &call.callee.span, references)?;
+                return Ok(true);
+            }
+        }
+
+        let span = call.callee.span.clone();
+        let placeholder = Expr::new(
+            ExprBody::Value(Value::None),
+            Typing::new(true, Type::None),
+            span.clone(),
+        );
+        let object = std::mem::replace(&mut attr.object, Box::new(placeholder));
+        call.arguments.push(object);
+        call.callee = Box::new(Expr::new(
+            ExprBody::Value(Value::Variable("clone".to_owned())),
+            Typing::new(false, Type::Unknown),
+            span,
+        ));
+        self.visit_expression(params, &mut call.callee, references)?;
+
+        Ok(true)
+    }
+
+    // builtins are generic over their arguments, so their signature is only known at the call site
+    fn builtin_call_typing(
+        &self,
+        name: &str,
+        arguments: &mut Vec<Box<Expr>>,
+        references: &mut References,
+    ) -> Result<Option<Typing>, TypingError> {
+        let callable = |params: Vec<Typing>, output: Typing| {
+            Some(Typing::new(
+                false,
+                Type::Callable(Box::new(Callable::new(
+                    Signature::new_with_params(params),
+                    output,
+                    false,
+                ))),
+            ))
+        };
+
+        let Some(first) = arguments.first() else {
+            // let the mutability checks report the missing arguments
+            let arity = match name {
+                "len" | "pop" | "keys" | "values" | "items" | "range" | "clone" => 1,
+                "append" => 2,
+                _ => return Ok(None),
+            };
+            return Ok(callable(
+                vec![Typing::new(false, Type::Unknown); arity],
+                Typing::new(true, Type::Unknown),
+            ));
+        };
+        let first_type = first.typing.r#type.get_concrete_type();
+
+        Ok(match name {
+            "len" => match first_type {
+                Type::String
+                | Type::List(_)
+                | Type::Dict(_, _)
+                | Type::Tuple(_)
+                | Type::Unknown => callable(
+                    vec![Typing::new(false, first_type)],
+                    Typing::new(true, Type::Int),
+                ),
+                t => {
+                    return Err(IncompatibleTypes::new(
+                        "List".to_owned(),
+                        t.as_string(),
+                        first.span.clone(),
+                    ))
+                }
+            },
+            "append" => {
+                let mut item_type = self.list_item_type(first)?;
+
+                if let Some(value) = arguments.get(1) {
+                    item_type = self.merge_item_type(&item_type, value)?;
+                }
+
+                let list_type = Type::List(Box::new(item_type.clone()));
+
+                // an empty list gets its items type from the first append
+                refine_type(&arguments[0], &list_type, references);
+                arguments[0].typing.r#type = list_type.clone();
+
+                callable(
+                    vec![Typing::new(true, list_type), Typing::new(false, item_type)],
+                    Typing::new(true, Type::None),
+                )
+            }
+            "pop" => {
+                let item_type = self.list_item_type(first)?;
+                callable(
+                    vec![Typing::new(true, Type::List(Box::new(item_type.clone())))],
+                    Typing::new(true, item_type),
+                )
+            }
+            "keys" | "values" | "items" => {
+                let (key_type, value_type) = self.dict_types(first)?;
+                let item_type = match name {
+                    "keys" => key_type.clone(),
+                    "values" => value_type.clone(),
+                    _ => Type::Tuple(vec![key_type.clone(), value_type.clone()]),
+                };
+                callable(
+                    vec![Typing::new(
+                        false,
+                        Type::Dict(Box::new(key_type), Box::new(value_type)),
+                    )],
+                    Typing::new(true, Type::List(Box::new(item_type))),
+                )
+            }
+            // range(stop), range(start, stop) or range(start, stop, step)
+            "range" => {
+                if arguments.len() > 3 {
+                    return Err(WrongArgumentNumberFunction::new(
+                        "range".to_owned(),
+                        3,
+                        arguments.len(),
+                        first.span.clone(),
+                    ));
+                }
+
+                for argument in arguments.iter() {
+                    match argument.typing.r#type.get_concrete_type() {
+                        Type::Int | Type::Bool | Type::Unknown => (),
+                        t => {
+                            return Err(IncompatibleTypes::new(
+                                "Int".to_owned(),
+                                t.as_string(),
+                                argument.span.clone(),
+                            ))
+                        }
+                    }
+                }
+
+                callable(
+                    vec![Typing::new(false, Type::Int); arguments.len()],
+                    Typing::new(true, Type::List(Box::new(Type::Int))),
+                )
+                // a new list is built on every call
+                .map(|t| Typing::new(true, t.r#type))
+            }
+            // the copy is owned by the caller, whatever the mutability of the original
+            "clone" => callable(
+                vec![Typing::new(false, first_type.clone())],
+                Typing::new(true, first_type),
+            )
+            .map(|t| Typing::new(true, t.r#type)),
+            _ => None,
+        })
+    }
+}
+
+// the return type of a function, as annotated or inferred by a previous pass
+fn get_declared_output(typing: &Typing) -> Option<Type> {
+    match &typing.r#type {
+        Type::Callable(c) if c.output.r#type.is_complete() => Some(c.output.r#type.clone()),
+        _ => None,
+    }
+}
+
+// parameters with a default value always come last
+fn get_signature(params: &[FunctionParameter]) -> Signature {
+    Signature::new_with_defaults(
+        params.iter().map(|p| p.typing.clone()).collect(),
+        params.iter().filter(|p| p.default.is_none()).count(),
+    )
+}
+
+fn check_arguments(
+    callable: &Callable,
+    call: &Call,
+    skip: usize,
+    span: &Span,
+) -> Result<(), TypingError> {
+    let Signature::Parameters(params, required) = &callable.signature else {
+        return Ok(());
+    };
+
+    let function = get_callee_name(&call.callee);
+    let arguments = callable.order_arguments(call, skip).map_err(|e| match e {
+        ArgumentError::Unknown(name) => UnknownArgument::new(function.clone(), name, span.clone()),
+        ArgumentError::Duplicated(name) => {
+            DuplicatedArgument::new(function.clone(), name, span.clone())
+        }
+    })?;
+
+    let params = params.iter().enumerate().skip(skip);
+    for ((index, param), arg) in params.zip(arguments.iter()) {
+        match arg {
+            Some(arg) => check_argument(param, arg, span)?,
+            // missing positional arguments are reported by the mutability checks
+            None if index < *required && !call.keywords.is_empty() => {
+                return Err(MissingArgument::new(
+                    function,
+                    callable.names[index].clone(),
+                    span.clone(),
+                ))
+            }
+            None => (),
+        }
+    }
+    Ok(())
+}
+
+fn is_attribute(callee: &Expr, references: &References) -> bool {
+    let ExprBody::GetAttr(attr) = &callee.body else {
+        return false;
+    };
+
+    match attr.object.typing.r#type.get_concrete_type() {
+        Type::Object(class) => references
+            .get_class(&class)
+            .and_then(|c| c.get_attribute(&attr.name))
+            .is_some(),
+        _ => false,
+    }
+}
+
+fn get_callee_name(callee: &Expr) -> String {
+    match &callee.body {
+        ExprBody::Value(Value::Variable(name)) => name.clone(),
+        ExprBody::GetAttr(attr) => attr.name.clone(),
+        _ => "function".to_owned(),
+    }
+}
+
+fn widens_to(declared: &Type, value: &Type) -> bool {
+    matches!(
+        (declared, value),
+        (Type::Float, Type::Int | Type::Bool) | (Type::Int, Type::Bool)
+    )
+}
+
+// a value fits the type of its variable, or widens to it like an int does to a float
+fn check_declared_type(declared: &Type, value: &mut Expr) -> Result<(), TypingError> {
+    let value_type = value.typing.r#type.get_concrete_type();
+
+    let fits = match (declared, &value_type) {
+        (d, v) if widens_to(d, v) => true,
+        // empty collections get their items type from the annotation
+        (Type::List(_), Type::List(_)) | (Type::Dict(_, _), Type::Dict(_, _))
+            if !value_type.is_complete() =>
+        {
+            value.typing.r#type = declared.clone();
+            true
+        }
+        // not known until a later pass
+        (_, t) if !t.is_complete() => true,
+        (d, v) => d == v && same_parameters_mutability(d, v),
+    };
+
+    if !fits {
+        return Err(IncompatibleTypes::new(
+            declared.as_string(),
+            value_type.as_string(),
+            value.span.clone(),
+        ));
+    }
+    Ok(())
+}
+
+fn check_argument(param: &Typing, arg: &Expr, span: &Span) -> Result<(), TypingError> {
+    // unannotated parameters are typed after their arguments, see record_arguments
+    if param.r#type == Type::Unknown || arg.typing.r#type == Type::Unknown {
+        return Ok(());
+    }
+
+    // a function is never a valid argument for a value, and the other way around
+    let both_callable =
+        matches!(param.r#type, Type::Callable(_)) == matches!(arg.typing.r#type, Type::Callable(_));
+
+    if !both_callable
+        || param.r#type.get_concrete_type() != arg.typing.r#type.get_concrete_type()
+        || !same_parameters_mutability(&param.r#type, &arg.typing.r#type)
+    {
+        return Err(IncompatibleTypes::new(
+            param.r#type.as_string(),
+            arg.typing.r#type.as_string(),
+            span.clone(),
+        ));
+    }
+    Ok(())
+}
+
+// an unannotated parameter, used as an argument of a typed parameter, takes its type
+fn infer_arguments(
+    callable: &Callable,
+    arguments: &mut [Box<Expr>],
+    keywords: &mut [(String, Box<Expr>)],
+    skip: usize,
+    params: &mut [FunctionParameter],
+    references: &mut References,
+) {
+    let Signature::Parameters(types, _) = &callable.signature else {
+        return;
+    };
+
+    for (arg, param) in arguments.iter_mut().zip(types.iter().skip(skip)) {
+        infer_parameter(params, arg, &param.r#type, references);
+    }
+    for (name, arg) in keywords.iter_mut() {
+        let position = callable.names.iter().position(|n| n == name);
+        if let Some(param) = position.and_then(|p| types.get(p)) {
+            infer_parameter(params, arg, &param.r#type, references);
+        }
+    }
+}
+
+// the types an unannotated parameter receives at the call sites are recorded on its
+// function, which is typed again once they are all known
+fn record_arguments(
+    callable: &Callable,
+    call: &Call,
+    skip: usize,
+    span: &Span,
+    references: &mut References,
+) -> Result<(), TypingError> {
+    let Signature::Parameters(types, _) = &callable.signature else {
+        return Ok(());
+    };
+    let Some(function_name) = get_function_name(&call.callee, references) else {
+        return Ok(());
+    };
+    let Ok(arguments) = callable.order_arguments(call, skip) else {
+        return Ok(());
+    };
+
+    let parameters = callable.names.iter().zip(types.iter()).skip(skip);
+    for ((name, param), arg) in parameters.zip(arguments.iter()) {
+        let Some(arg) = arg else { continue };
+        let arg_type = arg.typing.r#type.get_concrete_type();
+        if param.r#type != Type::Unknown || !arg_type.is_complete() {
+            continue;
+        }
+
+        let Some(function) = references.get_function_mut(&function_name) else {
+            return Ok(());
+        };
+        match function.parameters.iter().find(|p| &p.name == name) {
+            Some(p) if p.typing.r#type != arg_type => {
+                return Err(AmbiguousParameterType::new_with_types(
+                    function_name,
+                    name.clone(),
+                    p.typing.r#type.as_string(),
+                    arg_type.as_string(),
+                    span.clone(),
+                ))
+            }
+            Some(_) => (),
+            None => function.parameters.push(VariableReference::new(
+                name.clone(),
+                Typing::new(false, arg_type),
+            )),
+        }
+    }
+    Ok(())
+}
+
+// the function a call refers to: a function, a method or a class constructor
+fn get_function_name(callee: &Expr, references: &mut References) -> Option<String> {
+    match &callee.body {
+        ExprBody::Value(Value::Variable(name)) => {
+            if references.get_function_mut(name).is_some() {
+                Some(name.clone())
+            } else {
+                references
+                    .get_class(name)
+                    .map(|c| format!("{}.__init__", c.name))
+            }
+        }
+        ExprBody::GetAttr(attr) => match attr.object.typing.r#type.get_concrete_type() {
+            Type::Object(class) => Some(format!("{class}.{}", attr.name)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// a function parameter without annotation takes the type it is used as
+fn infer_parameter(
+    params: &mut [FunctionParameter],
+    expr: &mut Expr,
+    r#type: &Type,
+    references: &mut References,
+) {
+    let ExprBody::Value(Value::Variable(name)) = &expr.body else {
+        return;
+    };
+    if expr.typing.r#type != Type::Unknown || !r#type.is_complete() {
+        return;
+    }
+
+    if let Some(param) = params
+        .iter_mut()
+        .find(|p| &p.name == name && p.typing.r#type == Type::Unknown)
+    {
+        let r#type = r#type.get_concrete_type();
+        param.typing.r#type = r#type.clone();
+        references.set_variable_type(name, r#type.clone());
+        expr.typing.r#type = r#type;
+    }
+}
+
+// nested functions get the parameter types recorded at their call sites
+fn set_inferred_parameters(body: &mut [Stmt], references: &mut References) {
+    for function in get_nested_functions(body) {
+        let Some(reference) = references.get_function_mut(&function.name) else {
+            continue;
+        };
+        for param in function.params.iter_mut() {
+            if param.typing.r#type != Type::Unknown {
+                continue;
+            }
+            if let Some(p) = reference.parameters.iter().find(|p| p.name == param.name) {
+                param.typing.r#type = p.typing.r#type.clone();
+            }
+        }
+    }
+}
+
+// parameters whose type could not be inferred, as (function, parameter)
+pub fn get_untyped_parameters(function: &mut Function) -> Vec<(String, String, Span)> {
+    let mut untyped = Vec::new();
+    for function in get_nested_functions(&mut function.body) {
+        for param in function.params.iter() {
+            if param.typing.r#type == Type::Unknown {
+                untyped.push((
+                    function.name.clone(),
+                    param.name.clone(),
+                    param.span.clone(),
+                ));
+            }
+        }
+        untyped.extend(get_untyped_parameters(function));
+    }
+    untyped
+}
+
+// functions and methods declared in a body, not the ones declared within them
+fn get_nested_functions(body: &mut [Stmt]) -> Vec<&mut Function> {
+    let mut functions = Vec::new();
+    for stmt in body.iter_mut() {
+        match stmt {
+            Stmt::Function(f) => functions.push(f),
+            Stmt::Class(c) => functions.extend(c.methods.iter_mut()),
+            Stmt::Block(b) => functions.extend(get_nested_functions(b)),
+            Stmt::Condition(c) => {
+                functions.extend(get_nested_functions(std::slice::from_mut(&mut c.then)));
+                if let Some(r#else) = &mut c.r#else {
+                    functions.extend(get_nested_functions(std::slice::from_mut(r#else)));
+                }
+            }
+            Stmt::While(w) => {
+                functions.extend(get_nested_functions(std::slice::from_mut(&mut w.body)))
+            }
+            Stmt::For(f) => {
+                functions.extend(get_nested_functions(std::slice::from_mut(&mut f.body)));
+                if let Some(r#else) = &mut f.r#else {
+                    functions.extend(get_nested_functions(std::slice::from_mut(r#else)));
+                }
+            }
+            Stmt::Try(t) => {
+                functions.extend(get_nested_functions(std::slice::from_mut(&mut t.body)));
+                for handler in t.handlers.iter_mut() {
+                    functions.extend(get_nested_functions(std::slice::from_mut(
+                        &mut handler.body,
+                    )));
+                }
+                if let Some(finally) = &mut t.finally {
+                    functions.extend(get_nested_functions(std::slice::from_mut(finally)));
+                }
+            }
+            _ => (),
+        }
+    }
+    functions
+}
+
+// a function only stands for another if they agree on which parameters they mutate
+fn same_parameters_mutability(left: &Type, right: &Type) -> bool {
+    let (Type::Callable(left), Type::Callable(right)) = (left, right) else {
+        return true;
+    };
+
+    match (&left.signature, &right.signature) {
+        (Signature::Parameters(left, _), Signature::Parameters(right, _)) => {
+            left.iter().zip(right.iter()).all(|(l, r)| {
+                l.mutable == r.mutable && same_parameters_mutability(&l.r#type, &r.r#type)
+            })
+        }
+        _ => true,
+    }
+}
+
+// empty collections get their items type from their first insertion
+fn refine_type(expr: &Expr, r#type: &Type, references: &mut References) {
+    match &expr.body {
+        ExprBody::Value(Value::Variable(v)) => references.set_variable_type(v, r#type.clone()),
+        ExprBody::GetAttr(attr) => {
+            if let Type::Object(class) = attr.object.typing.r#type.get_concrete_type() {
+                if let Some(attribute) = references
+                    .get_class_mut(&class)
+                    .and_then(|c| c.attributes.iter_mut().find(|a| a.name == attr.name))
+                {
+                    attribute.typing.r#type = r#type.clone();
+                }
+            }
+        }
+        _ => (),
+    }
+}
+
+// values are formatted the way printf does, only the presentations it knows about are allowed
+fn check_format_spec(value: &Expr, spec: &FormatSpec) -> Result<(), TypingError> {
+    let is_float_kind = spec.kind.is_some_and(|k| "eEfFgG%".contains(k));
+    let is_valid = match value.typing.r#type.get_concrete_type() {
+        Type::Int | Type::Bool => {
+            spec.kind != Some('s') && (spec.precision.is_none() || is_float_kind)
+        }
+        Type::Float => spec.kind.is_none() || is_float_kind,
+        Type::String | Type::None => matches!(spec.kind, None | Some('s')) && spec.sign.is_none(),
+        Type::Unknown => true,
+        _ => false,
+    };
+
+    if !is_valid {
+        return Err(InvalidFormatSpec::new(
+            value.typing.r#type.as_string(),
+            format!("{spec}"),
+            value.span.clone(),
+        ));
+    }
+
+    Ok(())
 }
 
 fn get_identifier_names(expr: &Expr) -> Vec<String> {
@@ -348,8 +1712,101 @@ fn get_identifier_names(expr: &Expr) -> Vec<String> {
         }
         ExprBody::Call(c) => get_identifier_names(&c.callee),
         ExprBody::Binary(_) => Vec::new(),
+        ExprBody::Comparison(_) => Vec::new(),
+        ExprBody::List(_) => Vec::new(),
+        ExprBody::Dict(_) => Vec::new(),
+        ExprBody::Tuple(_) => Vec::new(),
+        ExprBody::FormatString(_) => Vec::new(),
+        ExprBody::GetItem(_) => Vec::new(),
+        ExprBody::SetItem(_) => Vec::new(),
+        ExprBody::GetAttr(_) => Vec::new(),
+        ExprBody::SetAttr(_) => Vec::new(),
         ExprBody::Assign(_) => Vec::new(),
+        ExprBody::AugmentedAssign(_) => Vec::new(),
         ExprBody::LoopKeyword(_) => Vec::new(),
         ExprBody::Value(_) => Vec::new(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::run_typing_checks;
+    use vif_ast::build_ast;
+
+    #[test]
+    fn chained_call_keeps_callable_annotation() {
+        let string = "
+def make(a: int):
+    def add(b: int):
+        return a + b
+    return add
+
+def apply(f: (int) -> (int) -> int):
+    return f(1)(2)
+
+assert apply(make) == 3
+";
+
+        let mut ast = build_ast(string).unwrap();
+        let result = run_typing_checks(&mut ast);
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn missing_return_is_located_on_the_function() {
+        let string = "
+def sign(n: int) -> int:
+    if n < 0:
+        return -1
+";
+
+        let mut ast = build_ast(string).unwrap();
+        let result = run_typing_checks(&mut ast);
+
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().format(string),
+            "Line 2 - def sign(n: int) -> int:\nFunction sign returns Int but can reach its end without returning"
+        );
+    }
+
+    #[test]
+    fn loop_breaking_before_its_else_can_miss_return() {
+        let string = "
+def find(n: int) -> int:
+    for i in range(n):
+        if i == 2:
+            break
+        return i
+    else:
+        return -1
+";
+
+        let mut ast = build_ast(string).unwrap();
+        let result = run_typing_checks(&mut ast);
+
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().format(string),
+            "Line 2 - def find(n: int) -> int:\nFunction find returns Int but can reach its end without returning"
+        );
+    }
+
+    #[test]
+    fn ordering_lists_is_not_supported() {
+        let string = "
+var xs = [1]
+assert xs < [2]
+";
+
+        let mut ast = build_ast(string).unwrap();
+        let result = run_typing_checks(&mut ast);
+
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().format(string),
+            "Line 3 - assert xs < [2]\nOperator < is not supported for List[Int]"
+        );
+    }
+}